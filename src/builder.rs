@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -6,14 +7,18 @@ use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use tokio::fs;
 
+use crate::cache::Cache;
 use crate::config::Config as GajiConfig;
 use crate::executor;
+use crate::fetcher::ActionMetadata;
 
 pub struct WorkflowBuilder {
     input_paths: Vec<PathBuf>,
     output_dir: PathBuf,
     dry_run: bool,
     ignored_patterns: Vec<String>,
+    validate: bool,
+    cache: Option<Cache>,
 }
 
 fn default_ignored_patterns() -> Vec<String> {
@@ -26,22 +31,22 @@ fn default_ignored_patterns() -> Vec<String> {
 
 impl WorkflowBuilder {
     pub fn new(input_paths: Vec<PathBuf>, output_dir: PathBuf, dry_run: bool) -> Self {
-        let ignored_patterns = GajiConfig::load().map_or_else(
-            |_| default_ignored_patterns(),
-            |config| {
-                if config.watch.ignored_patterns.is_empty() {
-                    default_ignored_patterns()
-                } else {
-                    config.watch.ignored_patterns
-                }
-            },
-        );
+        let config = GajiConfig::load().ok();
+        let ignored_patterns = match &config {
+            Some(config) if !config.watch.ignored_patterns.is_empty() => {
+                config.watch.ignored_patterns.clone()
+            }
+            _ => default_ignored_patterns(),
+        };
+        let validate = config.as_ref().is_none_or(|config| config.build.validate);
 
         Self {
             input_paths,
             output_dir,
             dry_run,
             ignored_patterns,
+            validate,
+            cache: Cache::load_or_create().ok(),
         }
     }
 
@@ -140,6 +145,34 @@ impl WorkflowBuilder {
             }
         }
 
+        // Modules imported by other workflow files that define no workflow
+        // themselves (such as the `shared.ts` written by `gaji migrate
+        // --dedupe`) are bundled into those workflows, not built on their own.
+        let mut sources = HashMap::new();
+        for file in &files {
+            if let Ok(source) = fs::read_to_string(file).await {
+                sources.insert(file.clone(), source);
+            }
+        }
+        let imported: HashSet<PathBuf> = sources
+            .iter()
+            .flat_map(|(file, source)| executor::local_module_paths(file, source))
+            .collect();
+        files.retain(|file| {
+            let helper = imported.contains(&executor::normalize_path(file))
+                && sources
+                    .get(file)
+                    .is_some_and(|source| !executor::calls_build(source));
+            if helper {
+                println!(
+                    "{} Skipping {} (imported module without a build() call)",
+                    "⏭️".dimmed(),
+                    file.display()
+                );
+            }
+            !helper
+        });
+
         Ok(files)
     }
 
@@ -220,6 +253,22 @@ impl WorkflowBuilder {
                 validate_workflow_yaml(&yaml_content)?;
             }
 
+            if self.validate {
+                if let Some(cache) = &self.cache {
+                    let report = check_action_inputs(&build_output.json, |action_ref| {
+                        cache.get(action_ref)
+                    })?;
+                    report.print(&build_output.id);
+                    if !report.errors.is_empty() {
+                        return Err(anyhow::anyhow!(
+                            "{} has {} action input error(s)",
+                            build_output.id,
+                            report.errors.len()
+                        ));
+                    }
+                }
+            }
+
             if self.dry_run {
                 // Print YAML to stdout without writing files
                 println!("--- {} ({}) ---", build_output.id, build_output.output_type);
@@ -328,6 +377,137 @@ fn validate_workflow_yaml(yaml: &str) -> Result<()> {
     Ok(())
 }
 
+/// Problems found by checking `with:` against cached action metadata.
+#[derive(Debug, Default)]
+pub struct InputCheckReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl InputCheckReport {
+    fn print(&self, id: &str) {
+        for warning in &self.warnings {
+            eprintln!("   {} {}: {}", "⚠️".yellow(), id, warning);
+        }
+        for error in &self.errors {
+            eprintln!("   {} {}: {}", "❌".red(), id, error);
+        }
+    }
+}
+
+/// Check every `uses:` step of a built workflow or composite action against
+/// its action metadata. Missing required inputs (without a default) and
+/// unknown `with` keys are errors; deprecated inputs are warnings. Steps whose
+/// action has no metadata (local actions, `docker://`, uncached refs) are skipped.
+pub fn check_action_inputs(
+    json_str: &str,
+    lookup: impl Fn(&str) -> Option<ActionMetadata>,
+) -> Result<InputCheckReport> {
+    let json_value: serde_json::Value =
+        serde_json::from_str(json_str).context("Invalid JSON output from workflow")?;
+
+    let mut report = InputCheckReport::default();
+
+    if let Some(jobs) = json_value.get("jobs").and_then(|j| j.as_object()) {
+        for (job_id, job) in jobs {
+            if let Some(steps) = job.get("steps").and_then(|s| s.as_array()) {
+                for (index, step) in steps.iter().enumerate() {
+                    let path = format!("jobs.{}.steps[{}]", job_id, index);
+                    check_step_inputs(step, &path, &lookup, &mut report);
+                }
+            }
+        }
+    }
+
+    // Composite action steps
+    if let Some(steps) = json_value
+        .get("runs")
+        .and_then(|runs| runs.get("steps"))
+        .and_then(|s| s.as_array())
+    {
+        for (index, step) in steps.iter().enumerate() {
+            let path = format!("runs.steps[{}]", index);
+            check_step_inputs(step, &path, &lookup, &mut report);
+        }
+    }
+
+    Ok(report)
+}
+
+fn check_step_inputs(
+    step: &serde_json::Value,
+    path: &str,
+    lookup: &impl Fn(&str) -> Option<ActionMetadata>,
+    report: &mut InputCheckReport,
+) {
+    let Some(uses) = step.get("uses").and_then(|u| u.as_str()) else {
+        return;
+    };
+    if uses.starts_with("./") || uses.starts_with("docker://") {
+        return;
+    }
+    let Some(metadata) = lookup(uses) else {
+        return;
+    };
+
+    let location = match step
+        .get("name")
+        .or_else(|| step.get("id"))
+        .and_then(|n| n.as_str())
+    {
+        Some(name) => format!("{} '{}' ({})", path, name, uses),
+        None => format!("{} ({})", path, uses),
+    };
+
+    let empty = serde_json::Map::new();
+    let with = step
+        .get("with")
+        .and_then(|w| w.as_object())
+        .unwrap_or(&empty);
+    let inputs = metadata.inputs.unwrap_or_default();
+
+    let mut missing: Vec<&String> = inputs
+        .iter()
+        .filter(|(name, input)| {
+            input.required.unwrap_or(false) && input.default.is_none() && !with.contains_key(*name)
+        })
+        .map(|(name, _)| name)
+        .collect();
+    missing.sort();
+    for name in missing {
+        report
+            .errors
+            .push(format!("{}: missing required input '{}'", location, name));
+    }
+
+    // Docker container actions also accept `args` and `entrypoint`
+    let is_docker = metadata
+        .runs
+        .as_ref()
+        .is_some_and(|runs| runs.using == "docker");
+
+    for key in with.keys() {
+        match inputs.get(key) {
+            Some(input) => {
+                if let Some(message) = &input.deprecation_message {
+                    report.warnings.push(format!(
+                        "{}: input '{}' is deprecated: {}",
+                        location,
+                        key,
+                        message.trim()
+                    ));
+                }
+            }
+            None if is_docker && (key == "args" || key == "entrypoint") => {}
+            None => {
+                report
+                    .errors
+                    .push(format!("{}: unknown input '{}'", location, key));
+            }
+        }
+    }
+}
+
 async fn should_write_file(path: &Path, new_content: &str) -> Result<bool> {
     if !path.exists() {
         return Ok(true);
@@ -700,6 +880,43 @@ mod tests {
         assert!(!filenames.contains(&"node_modules_test.ts".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_find_workflow_files_skips_imported_helpers() {
+        let dir = TempDir::new().unwrap();
+
+        tokio::fs::write(
+            dir.path().join("ci.ts"),
+            "import {\n    steps,\n} from \"./shared\";\nimport { release } from \"./release\";\nnew Workflow({}).build(\"ci\");\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(dir.path().join("shared.ts"), "export const steps = [];\n")
+            .await
+            .unwrap();
+        // Imported, but a workflow of its own
+        tokio::fs::write(
+            dir.path().join("release.ts"),
+            "export const release = new Workflow({});\nrelease.build(\"release\");\n",
+        )
+        .await
+        .unwrap();
+
+        let builder = WorkflowBuilder::new(
+            vec![dir.path().to_path_buf()],
+            dir.path().join("output"),
+            false,
+        );
+        let mut filenames: Vec<String> = builder
+            .find_workflow_files()
+            .await
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        filenames.sort();
+        assert_eq!(filenames, vec!["ci.ts", "release.ts"]);
+    }
+
     // --- build_all tests ---
 
     #[tokio::test]
@@ -736,4 +953,61 @@ mod tests {
         let result = copy_node_shell_files("not json", &workflow_path, dir.path()).await;
         assert!(result.is_ok()); // Should silently succeed on invalid JSON
     }
+
+    // --- check_action_inputs tests ---
+
+    fn setup_node_metadata() -> ActionMetadata {
+        serde_yaml::from_str(
+            r#"
+name: Setup Node
+inputs:
+  node-version:
+    description: Version to use
+  token:
+    required: true
+    default: ${{ github.token }}
+  registry-url:
+    required: true
+  always-auth:
+    deprecationMessage: Not needed anymore
+"#,
+        )
+        .unwrap()
+    }
+
+    fn lookup(action_ref: &str) -> Option<ActionMetadata> {
+        (action_ref == "actions/setup-node@v4").then(setup_node_metadata)
+    }
+
+    #[test]
+    fn test_check_action_inputs_reports_problems() {
+        let json = r#"{"on":{},"jobs":{"build":{"steps":[
+            {"uses":"actions/checkout@v5"},
+            {"name":"Node","uses":"actions/setup-node@v4","with":{"node-versoin":"20","always-auth":"true"}}
+        ]}}}"#;
+        let report = check_action_inputs(json, lookup).unwrap();
+
+        assert_eq!(
+            report.errors,
+            vec![
+                "jobs.build.steps[1] 'Node' (actions/setup-node@v4): missing required input 'registry-url'",
+                "jobs.build.steps[1] 'Node' (actions/setup-node@v4): unknown input 'node-versoin'",
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec!["jobs.build.steps[1] 'Node' (actions/setup-node@v4): input 'always-auth' is deprecated: Not needed anymore"]
+        );
+    }
+
+    #[test]
+    fn test_check_action_inputs_composite_ok() {
+        let json = r#"{"runs":{"using":"composite","steps":[
+            {"uses":"actions/setup-node@v4","with":{"registry-url":"https://npm.pkg.github.com"}},
+            {"uses":"./.github/actions/local","with":{"anything":"goes"}}
+        ]}}"#;
+        let report = check_action_inputs(json, lookup).unwrap();
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
 }
@@ -1,12 +1,27 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::fetcher::ActionMetadata;
+use crate::fetcher::{ActionMetadata, FetchError, WorkflowCallMetadata};
 
-const CACHE_FILE: &str = ".gaji-cache.json";
+/// Current schema of the cache file. Version 1 was the per-project
+/// `.gaji-cache.json`, keyed only by ref.
+pub const CACHE_VERSION: u32 = 2;
+
+/// Per-project cache file of older gaji versions, imported into the global
+/// cache the first time it is created.
+pub const LEGACY_CACHE_FILE: &str = ".gaji-cache.json";
+
+const CACHE_FILE: &str = "metadata.json";
+
+/// How long a ref that was not found is remembered before it is looked up
+/// again.
+pub const MISS_TTL_SECS: u64 = 10 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheEntry {
@@ -16,122 +31,580 @@ pub struct CacheEntry {
     pub metadata: ActionMetadata,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowCacheEntry {
+    pub workflow_ref: String,
+    pub content_hash: String,
+    pub generated_at: u64,
+    pub metadata: WorkflowCallMetadata,
+}
+
+/// The commit a ref pointed to when it was last resolved. Refs expire with
+/// the cache TTL; the entries they point to never do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefEntry {
+    /// Commit SHA, or `None` when it could not be resolved and the entry is
+    /// keyed by the ref itself.
+    pub sha: Option<String>,
+    pub resolved_at: u64,
+}
+
+/// A ref that was not found, so a typo does not cost a lookup on every run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissEntry {
+    pub error: FetchError,
+    pub expires_at: u64,
+}
+
+/// Whether a cached ref is an action or a reusable workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKind {
+    Action,
+    Workflow,
+}
+
+/// A cached ref as listed by `gaji cache ls`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedRef {
+    pub reference: String,
+    pub kind: CacheKind,
+    pub sha: Option<String>,
+    pub content_hash: String,
+    /// Unix time the ref was last resolved.
+    pub resolved_at: u64,
+    /// Unix time the ref is resolved again, `None` for refs pinned to a SHA.
+    pub expires_at: Option<u64>,
+    pub expired: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CacheData {
     pub version: u32,
+    /// `owner/repo@ref` -> resolved commit.
+    #[serde(default)]
+    pub refs: HashMap<String, RefEntry>,
+    /// Action metadata keyed by `owner/repo@sha`, or by ref when unresolved.
     pub entries: HashMap<String, CacheEntry>,
+    #[serde(default)]
+    pub workflows: HashMap<String, WorkflowCacheEntry>,
+    /// Refs that were not found, until [`MISS_TTL_SECS`] passes.
+    #[serde(default)]
+    pub misses: HashMap<String, MissEntry>,
+}
+
+impl CacheData {
+    /// Key of the entry `action_ref` currently resolves to.
+    fn key_for(&self, action_ref: &str) -> String {
+        match self.refs.get(action_ref).and_then(|r| r.sha.as_deref()) {
+            Some(sha) => pinned_key(action_ref, sha),
+            None => action_ref.to_string(),
+        }
+    }
+
+    /// When `action_ref` was last resolved. Entries written before refs were
+    /// tracked fall back to their own timestamp.
+    fn resolved_at(&self, action_ref: &str) -> Option<u64> {
+        self.refs
+            .get(action_ref)
+            .map(|r| r.resolved_at)
+            .or_else(|| self.entries.get(action_ref).map(|e| e.generated_at))
+            .or_else(|| self.workflows.get(action_ref).map(|e| e.generated_at))
+    }
+
+    /// `None` when nothing is cached for `action_ref`, `Some(None)` when the
+    /// cached entry never expires, otherwise the unix time it expires at.
+    fn expires_at(&self, action_ref: &str, max_age_days: u64) -> Option<Option<u64>> {
+        let key = self.key_for(action_ref);
+        let cached = self.entries.contains_key(&key) || self.workflows.contains_key(&key);
+        let pinned = action_ref
+            .rsplit_once('@')
+            .is_some_and(|(_, r)| is_commit_sha(r));
+        if cached && pinned {
+            return Some(None);
+        }
+
+        self.resolved_at(action_ref)
+            .map(|resolved_at| Some(resolved_at + max_age_days * 24 * 60 * 60))
+    }
+
+    /// Bring data written by an older gaji up to `CACHE_VERSION`.
+    fn migrate(mut self) -> Self {
+        if self.version < 2 {
+            // v1 entries were keyed by ref, so each becomes an unresolved ref
+            // that keeps the entry's age
+            for (reference, entry) in &self.entries {
+                self.refs.entry(reference.clone()).or_insert(RefEntry {
+                    sha: None,
+                    resolved_at: entry.generated_at,
+                });
+            }
+            for (reference, entry) in &self.workflows {
+                self.refs.entry(reference.clone()).or_insert(RefEntry {
+                    sha: None,
+                    resolved_at: entry.generated_at,
+                });
+            }
+            self.version = 2;
+        }
+        self
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Action metadata cache shared by every project of the user.
+///
+/// Writes take an exclusive lock, re-read the file so entries written by
+/// concurrent fetches or other processes are kept, and replace the file
+/// atomically.
+#[derive(Debug)]
 pub struct Cache {
-    data: CacheData,
+    data: Mutex<CacheData>,
     cache_file: PathBuf,
 }
 
 impl Cache {
+    /// Load the global cache, importing the project's legacy
+    /// `.gaji-cache.json` when the global one does not exist yet.
     pub fn load_or_create() -> Result<Self> {
-        let cache_file = PathBuf::from(CACHE_FILE);
+        let cache_file = default_path();
+        let is_new = !cache_file.exists();
+        let cache = Self::load_from(cache_file)?;
 
-        let data = if cache_file.exists() {
-            let content = std::fs::read_to_string(&cache_file)?;
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            CacheData {
-                version: 1,
-                ..Default::default()
-            }
-        };
+        let legacy = Path::new(LEGACY_CACHE_FILE);
+        if is_new && legacy.exists() {
+            let legacy = read_data(legacy)?;
+            cache.update(|data| {
+                data.refs.extend(legacy.refs);
+                data.entries.extend(legacy.entries);
+                data.workflows.extend(legacy.workflows);
+            })?;
+        }
+
+        Ok(cache)
+    }
+
+    /// Load the cache stored at `cache_file`, or start an empty one.
+    pub fn load_from(cache_file: PathBuf) -> Result<Self> {
+        let data = read_data(&cache_file)?;
+        Ok(Self {
+            data: Mutex::new(data),
+            cache_file,
+        })
+    }
 
-        Ok(Self { data, cache_file })
+    /// Path of the cache file.
+    pub fn path(&self) -> &Path {
+        &self.cache_file
     }
 
     pub fn get(&self, action_ref: &str) -> Option<ActionMetadata> {
-        self.data
-            .entries
-            .get(action_ref)
+        let data = self.data.lock().unwrap();
+        data.entries
+            .get(&data.key_for(action_ref))
             .map(|entry| entry.metadata.clone())
     }
 
+    /// Metadata cached for `action_ref` at commit `sha`, regardless of where
+    /// the ref points now.
+    pub fn get_pinned(&self, action_ref: &str, sha: &str) -> Option<ActionMetadata> {
+        let data = self.data.lock().unwrap();
+        data.entries
+            .get(&pinned_key(action_ref, sha))
+            .map(|entry| entry.metadata.clone())
+    }
+
+    /// Store metadata for `action_ref`, keyed by the commit it resolved to.
     pub fn set(
         &self,
         action_ref: &str,
+        sha: Option<&str>,
         metadata: &ActionMetadata,
         yaml_content: &str,
     ) -> Result<()> {
-        let mut data = self.data.clone();
+        let key = sha.map_or_else(|| action_ref.to_string(), |sha| pinned_key(action_ref, sha));
+        let entry = CacheEntry {
+            action_ref: action_ref.to_string(),
+            content_hash: calculate_hash(yaml_content),
+            generated_at: now(),
+            metadata: metadata.clone(),
+        };
 
-        let content_hash = calculate_hash(yaml_content);
-        let generated_at = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        self.update(|data| {
+            data.entries.insert(key, entry);
+            data.refs.insert(action_ref.to_string(), ref_entry(sha));
+            data.misses.remove(action_ref);
+        })
+    }
 
-        data.entries.insert(
-            action_ref.to_string(),
-            CacheEntry {
-                action_ref: action_ref.to_string(),
-                content_hash,
-                generated_at,
-                metadata: metadata.clone(),
-            },
-        );
+    /// Record that `action_ref` still resolves to `sha` without touching the
+    /// entry itself.
+    pub fn record_ref(&self, action_ref: &str, sha: &str) -> Result<()> {
+        self.update(|data| {
+            data.refs
+                .insert(action_ref.to_string(), ref_entry(Some(sha)));
+        })
+    }
 
-        let json = serde_json::to_string_pretty(&data)?;
-        std::fs::write(&self.cache_file, json)?;
+    /// The not-found error recorded for `reference`, unless it expired.
+    pub fn get_miss(&self, reference: &str) -> Option<FetchError> {
+        let data = self.data.lock().unwrap();
+        data.misses
+            .get(reference)
+            .filter(|miss| now() < miss.expires_at)
+            .map(|miss| miss.error.clone())
+    }
 
-        Ok(())
+    /// Remember that `reference` was not found for [`MISS_TTL_SECS`].
+    pub fn record_miss(&self, reference: &str, error: &FetchError) -> Result<()> {
+        let miss = MissEntry {
+            error: error.clone(),
+            expires_at: now() + MISS_TTL_SECS,
+        };
+        self.update(|data| {
+            data.misses.insert(reference.to_string(), miss);
+        })
+    }
+
+    pub fn get_workflow(&self, workflow_ref: &str) -> Option<WorkflowCallMetadata> {
+        let data = self.data.lock().unwrap();
+        data.workflows
+            .get(&data.key_for(workflow_ref))
+            .map(|entry| entry.metadata.clone())
+    }
+
+    pub fn get_pinned_workflow(
+        &self,
+        workflow_ref: &str,
+        sha: &str,
+    ) -> Option<WorkflowCallMetadata> {
+        let data = self.data.lock().unwrap();
+        data.workflows
+            .get(&pinned_key(workflow_ref, sha))
+            .map(|entry| entry.metadata.clone())
+    }
+
+    pub fn set_workflow(
+        &self,
+        workflow_ref: &str,
+        sha: Option<&str>,
+        metadata: &WorkflowCallMetadata,
+        yaml_content: &str,
+    ) -> Result<()> {
+        let key = sha.map_or_else(
+            || workflow_ref.to_string(),
+            |sha| pinned_key(workflow_ref, sha),
+        );
+        let entry = WorkflowCacheEntry {
+            workflow_ref: workflow_ref.to_string(),
+            content_hash: calculate_hash(yaml_content),
+            generated_at: now(),
+            metadata: metadata.clone(),
+        };
+
+        self.update(|data| {
+            data.workflows.insert(key, entry);
+            data.refs.insert(workflow_ref.to_string(), ref_entry(sha));
+            data.misses.remove(workflow_ref);
+        })
     }
 
     pub fn should_regenerate(&self, action_ref: &str, new_hash: &str) -> bool {
-        match self.data.entries.get(action_ref) {
+        let data = self.data.lock().unwrap();
+        match data.entries.get(&data.key_for(action_ref)) {
             Some(entry) => entry.content_hash != new_hash,
             None => true,
         }
     }
 
     pub fn clear(&self) -> Result<()> {
+        let _lock = self.lock()?;
         if self.cache_file.exists() {
             std::fs::remove_file(&self.cache_file)?;
         }
+        *self.data.lock().unwrap() = CacheData::default().migrate();
         Ok(())
     }
 
     pub fn remove(&self, action_ref: &str) -> Result<()> {
-        let mut data = self.data.clone();
-        data.entries.remove(action_ref);
+        self.update(|data| {
+            let key = data.key_for(action_ref);
+            data.entries.remove(&key);
+            data.workflows.remove(&key);
+            data.refs.remove(action_ref);
+            data.misses.remove(action_ref);
+        })
+    }
 
+    /// Cached refs, actions and reusable workflows alike.
+    pub fn list(&self) -> Vec<String> {
+        let data = self.data.lock().unwrap();
+        let mut refs: Vec<String> = data
+            .entries
+            .values()
+            .map(|e| e.action_ref.clone())
+            .chain(data.workflows.values().map(|e| e.workflow_ref.clone()))
+            .collect();
+        refs.sort();
+        refs.dedup();
+        refs
+    }
+
+    /// Whether `action_ref` should be resolved again. Refs that are already a
+    /// commit SHA never expire once cached.
+    pub fn is_expired(&self, action_ref: &str, max_age_days: u64) -> bool {
+        match self
+            .data
+            .lock()
+            .unwrap()
+            .expires_at(action_ref, max_age_days)
+        {
+            Some(Some(expires_at)) => now() > expires_at,
+            Some(None) => false,
+            None => true,
+        }
+    }
+
+    /// Every cached ref with its resolution and expiry, sorted by ref.
+    pub fn refs(&self, max_age_days: u64) -> Vec<CachedRef> {
+        let data = self.data.lock().unwrap();
+        let mut refs: Vec<CachedRef> = data
+            .entries
+            .values()
+            .map(|e| (&e.action_ref, CacheKind::Action))
+            .chain(
+                data.workflows
+                    .values()
+                    .map(|e| (&e.workflow_ref, CacheKind::Workflow)),
+            )
+            .filter_map(|(reference, kind)| {
+                let key = data.key_for(reference);
+                let content_hash = match kind {
+                    CacheKind::Action => &data.entries.get(&key)?.content_hash,
+                    CacheKind::Workflow => &data.workflows.get(&key)?.content_hash,
+                };
+                let expires_at = data.expires_at(reference, max_age_days).flatten();
+                Some(CachedRef {
+                    reference: reference.clone(),
+                    kind,
+                    sha: data.refs.get(reference).and_then(|r| r.sha.clone()),
+                    content_hash: content_hash.clone(),
+                    resolved_at: data.resolved_at(reference).unwrap_or_default(),
+                    expires_at,
+                    expired: expires_at.is_some_and(|at| now() > at),
+                })
+            })
+            .collect();
+        refs.sort_by(|a, b| a.reference.cmp(&b.reference));
+        refs.dedup_by(|a, b| a.reference == b.reference);
+        refs
+    }
+
+    /// Drop entries no ref points to anymore, such as the old commit of a
+    /// moved tag, and expired misses. With `used`, refs outside that set are
+    /// dropped first. Returns the number of entries removed.
+    pub fn prune(&self, used: Option<&HashSet<String>>) -> Result<usize> {
+        let mut removed = 0;
+        self.update(|data| {
+            if let Some(used) = used {
+                data.refs.retain(|reference, _| used.contains(reference));
+            }
+
+            let live: HashSet<String> = data.refs.keys().map(|r| data.key_for(r)).collect();
+            let before = data.entries.len() + data.workflows.len();
+            data.entries.retain(|key, _| live.contains(key));
+            data.workflows.retain(|key, _| live.contains(key));
+            removed = before - data.entries.len() - data.workflows.len();
+            data.misses.retain(|_, miss| now() < miss.expires_at);
+        })?;
+        Ok(removed)
+    }
+
+    /// Write the whole cache to `path`, for seeding other machines.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let data = {
+            let _lock = self.lock()?;
+            read_data(&self.cache_file)?
+        };
         let json = serde_json::to_string_pretty(&data)?;
-        std::fs::write(&self.cache_file, json)?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Merge a cache written by `export` into this one. Imported refs replace
+    /// existing ones. Returns the number of refs imported.
+    pub fn import(&self, path: &Path) -> Result<usize> {
+        if !path.is_file() {
+            anyhow::bail!("File not found: {}", path.display());
+        }
+        let imported = parse_data(path)?
+            .with_context(|| format!("{} is not a gaji cache export", path.display()))?
+            .migrate();
+        let count = imported.refs.len();
+        self.update(|data| {
+            data.refs.extend(imported.refs);
+            data.entries.extend(imported.entries);
+            data.workflows.extend(imported.workflows);
+        })?;
+        Ok(count)
+    }
+
+    /// Apply `change` to the latest on-disk data under an exclusive lock and
+    /// write the result atomically.
+    fn update(&self, change: impl FnOnce(&mut CacheData)) -> Result<()> {
+        let _lock = self.lock()?;
+
+        let mut data = read_data(&self.cache_file)?;
+        change(&mut data);
+        write_atomic(&self.cache_file, &data)?;
 
+        *self.data.lock().unwrap() = data;
         Ok(())
     }
 
-    pub fn list(&self) -> Vec<&str> {
-        self.data.entries.keys().map(|s| s.as_str()).collect()
+    /// Exclusive lock on a sidecar file, released when the handle is dropped.
+    fn lock(&self) -> Result<File> {
+        if let Some(parent) = self.cache_file.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory {}", parent.display())
+            })?;
+        }
+        let lock_path = self.cache_file.with_extension("lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        file.lock()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        Ok(file)
     }
+}
 
-    pub fn is_expired(&self, action_ref: &str, max_age_days: u64) -> bool {
-        let max_age_secs = max_age_days * 24 * 60 * 60;
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+/// Path of the global cache file.
+pub fn default_path() -> PathBuf {
+    cache_dir().join(CACHE_FILE)
+}
 
-        match self.data.entries.get(action_ref) {
-            Some(entry) => now - entry.generated_at > max_age_secs,
-            None => true,
+/// Directory of the global cache: `$GAJI_CACHE_DIR`, then the platform cache
+/// directory (`$XDG_CACHE_HOME/gaji`, `~/.cache/gaji`, `~/Library/Caches/gaji`
+/// or `%LOCALAPPDATA%\gaji`).
+pub fn cache_dir() -> PathBuf {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+
+    if let Some(dir) = env_dir("GAJI_CACHE_DIR") {
+        return dir;
+    }
+
+    let base = if cfg!(windows) {
+        env_dir("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Caches"))
+    } else {
+        env_dir("XDG_CACHE_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".cache")))
+    };
+
+    base.unwrap_or_else(std::env::temp_dir).join("gaji")
+}
+
+/// Read and migrate a cache file. A missing or unreadable file yields an
+/// empty cache so a corrupt file never blocks a build.
+fn read_data(path: &Path) -> Result<CacheData> {
+    if !path.exists() {
+        return Ok(CacheData::default().migrate());
+    }
+
+    match parse_data(path)? {
+        Some(data) => Ok(data.migrate()),
+        None => {
+            // Keep the corrupt file for inspection instead of overwriting it
+            let aside = path.with_extension("json.corrupt");
+            std::fs::rename(path, &aside)
+                .with_context(|| format!("Failed to move aside {}", path.display()))?;
+            eprintln!(
+                "Warning: {} is corrupt; moved it to {}",
+                path.display(),
+                aside.display()
+            );
+            Ok(CacheData::default().migrate())
         }
     }
 }
 
-fn calculate_hash(content: &str) -> String {
-    // Simple hash implementation
-    // In production, use sha256
-    let mut hash: u64 = 0;
-    for byte in content.bytes() {
-        hash = hash.wrapping_mul(31).wrapping_add(byte as u64);
+/// Parse the cache data at `path`, checking `version` before the rest so a
+/// newer layout is rejected rather than mistaken for a corrupt file. Returns
+/// `None` when the file is not valid cache data.
+fn parse_data(path: &Path) -> Result<Option<CacheData>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Ok(None);
+    };
+
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > u64::from(CACHE_VERSION) {
+        anyhow::bail!(
+            "{} was written by a newer gaji (cache version {}). Upgrade gaji or set GAJI_CACHE_DIR",
+            path.display(),
+            version
+        );
+    }
+
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Write to a temporary file next to `path` and rename it into place, so
+/// readers never see a partial file.
+fn write_atomic(path: &Path, data: &CacheData) -> Result<()> {
+    let json = serde_json::to_string_pretty(data)?;
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+
+    let mut file =
+        File::create(&tmp).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    file.write_all(json.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+
+    Ok(())
+}
+
+/// `owner/repo[/path]@sha` for a ref.
+fn pinned_key(action_ref: &str, sha: &str) -> String {
+    let name = action_ref
+        .rsplit_once('@')
+        .map_or(action_ref, |(name, _)| name);
+    format!("{}@{}", name, sha)
+}
+
+fn ref_entry(sha: Option<&str>) -> RefEntry {
+    RefEntry {
+        sha: sha.map(str::to_string),
+        resolved_at: now(),
     }
-    format!("{:016x}", hash)
+}
+
+/// Whether `r` is a full 40-character commit SHA.
+pub fn is_commit_sha(r: &str) -> bool {
+    r.len() == 40 && r.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// SHA-256 of `content`, hex encoded.
+pub fn calculate_hash(content: &str) -> String {
+    ring::digest::digest(&ring::digest::SHA256, content.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
 
 #[cfg(test)]
@@ -159,10 +632,10 @@ mod tests {
     fn test_should_regenerate_missing_entry() {
         let data = CacheData {
             version: 1,
-            entries: HashMap::new(),
+            ..Default::default()
         };
         let cache = Cache {
-            data,
+            data: Mutex::new(data),
             cache_file: PathBuf::from(".test-cache.json"),
         };
         assert!(cache.should_regenerate("actions/checkout@v5", "somehash"));
@@ -188,10 +661,11 @@ mod tests {
             },
         );
         let cache = Cache {
-            data: CacheData {
+            data: Mutex::new(CacheData {
                 version: 1,
                 entries,
-            },
+                ..Default::default()
+            }),
             cache_file: PathBuf::from(".test-cache.json"),
         };
         assert!(!cache.should_regenerate("actions/checkout@v5", &hash));
@@ -216,10 +690,11 @@ mod tests {
             },
         );
         let cache = Cache {
-            data: CacheData {
+            data: Mutex::new(CacheData {
                 version: 1,
                 entries,
-            },
+                ..Default::default()
+            }),
             cache_file: PathBuf::from(".test-cache.json"),
         };
         assert!(cache.should_regenerate("actions/checkout@v5", "newhash"));
@@ -231,10 +706,10 @@ mod tests {
         let cache_file = dir.path().join("cache.json");
 
         let cache = Cache {
-            data: CacheData {
+            data: Mutex::new(CacheData {
                 version: 1,
-                entries: HashMap::new(),
-            },
+                ..Default::default()
+            }),
             cache_file: cache_file.clone(),
         };
 
@@ -246,7 +721,7 @@ mod tests {
             runs: None,
         };
         cache
-            .set("test/action@v1", &metadata, "yaml content")
+            .set("test/action@v1", None, &metadata, "yaml content")
             .unwrap();
 
         // Read back the file and verify
@@ -259,13 +734,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_workflow_cache_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache_file = dir.path().join("cache.json");
+
+        let cache = Cache {
+            data: Mutex::new(CacheData {
+                version: 1,
+                ..Default::default()
+            }),
+            cache_file: cache_file.clone(),
+        };
+
+        let metadata = WorkflowCallMetadata {
+            name: Some("Deploy".to_string()),
+            ..Default::default()
+        };
+        cache
+            .set_workflow(
+                "org/repo/.github/workflows/deploy.yml@v1",
+                None,
+                &metadata,
+                "yaml",
+            )
+            .unwrap();
+
+        let content = std::fs::read_to_string(&cache_file).unwrap();
+        let loaded: CacheData = serde_json::from_str(&content).unwrap();
+        let entry = &loaded.workflows["org/repo/.github/workflows/deploy.yml@v1"];
+        assert_eq!(entry.metadata.name.as_deref(), Some("Deploy"));
+        assert!(loaded.entries.is_empty());
+    }
+
     #[test]
     fn test_is_expired_missing_entry() {
         let cache = Cache {
-            data: CacheData {
+            data: Mutex::new(CacheData {
                 version: 1,
-                entries: HashMap::new(),
-            },
+                ..Default::default()
+            }),
             cache_file: PathBuf::from(".test-cache.json"),
         };
         assert!(cache.is_expired("nonexistent@v1", 30));
@@ -294,10 +802,11 @@ mod tests {
             },
         );
         let cache = Cache {
-            data: CacheData {
+            data: Mutex::new(CacheData {
                 version: 1,
                 entries,
-            },
+                ..Default::default()
+            }),
             cache_file: PathBuf::from(".test-cache.json"),
         };
         assert!(!cache.is_expired("actions/checkout@v5", 30));
@@ -328,12 +837,248 @@ mod tests {
             },
         );
         let cache = Cache {
-            data: CacheData {
+            data: Mutex::new(CacheData {
                 version: 1,
                 entries,
-            },
+                ..Default::default()
+            }),
             cache_file: PathBuf::from(".test-cache.json"),
         };
         assert!(cache.is_expired("actions/checkout@v5", 30));
     }
+
+    fn checkout_metadata() -> ActionMetadata {
+        ActionMetadata {
+            name: "Checkout".to_string(),
+            description: None,
+            inputs: None,
+            outputs: None,
+            runs: None,
+        }
+    }
+
+    #[test]
+    fn test_entries_keyed_by_sha() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = Cache::load_from(dir.path().join("cache.json")).unwrap();
+        let sha = "a".repeat(40);
+
+        cache
+            .set(
+                "actions/checkout@v5",
+                Some(&sha),
+                &checkout_metadata(),
+                "yaml",
+            )
+            .unwrap();
+
+        let key = format!("actions/checkout@{}", sha);
+        let loaded = read_data(cache.path()).unwrap();
+        assert!(loaded.entries.contains_key(&key));
+        assert_eq!(
+            loaded.refs["actions/checkout@v5"].sha.as_deref(),
+            Some(sha.as_str())
+        );
+        assert!(cache.get("actions/checkout@v5").is_some());
+        assert!(cache.get_pinned("actions/checkout@v5", &sha).is_some());
+
+        // A ref that is itself the SHA hits the same entry and never expires
+        assert!(!cache.is_expired(&key, 0));
+    }
+
+    #[test]
+    fn test_concurrent_writes_keep_all_entries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    // Each thread has its own handle, like separate processes
+                    let cache = Cache::load_from(path).unwrap();
+                    cache
+                        .set(
+                            &format!("owner/action{}@v1", i),
+                            None,
+                            &checkout_metadata(),
+                            "yaml",
+                        )
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let cache = Cache::load_from(path).unwrap();
+        assert_eq!(cache.list().len(), 8);
+    }
+
+    #[test]
+    fn test_migrate_v1_cache() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+        let now = now();
+        std::fs::write(
+            &path,
+            format!(
+                r#"{{"version": 1, "entries": {{"actions/checkout@v5": {{
+                    "action_ref": "actions/checkout@v5", "content_hash": "1234",
+                    "generated_at": {}, "metadata": {{"name": "Checkout"}}}}}}}}"#,
+                now
+            ),
+        )
+        .unwrap();
+
+        let cache = Cache::load_from(path.clone()).unwrap();
+        assert_eq!(cache.get("actions/checkout@v5").unwrap().name, "Checkout");
+        assert!(!cache.is_expired("actions/checkout@v5", 30));
+
+        cache
+            .record_ref("actions/checkout@v5", &"b".repeat(40))
+            .unwrap();
+        assert_eq!(read_data(&path).unwrap().version, CACHE_VERSION);
+    }
+
+    #[test]
+    fn test_newer_cache_version_is_rejected() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+        std::fs::write(&path, r#"{"version": 99, "entries": {}}"#).unwrap();
+        assert!(Cache::load_from(path.clone()).is_err());
+
+        // A newer layout must not be mistaken for a corrupt file
+        std::fs::write(&path, r#"{"version": 99, "entries": []}"#).unwrap();
+        assert!(Cache::load_from(path.clone()).is_err());
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_corrupt_cache_is_moved_aside() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+        std::fs::write(&path, r#"{"version": 2, "entries": {"#).unwrap();
+
+        let cache = Cache::load_from(path.clone()).unwrap();
+        cache
+            .set("actions/checkout@v5", None, &checkout_metadata(), "yaml")
+            .unwrap();
+
+        let aside = dir.path().join("cache.json.corrupt");
+        assert_eq!(
+            std::fs::read_to_string(aside).unwrap(),
+            r#"{"version": 2, "entries": {"#
+        );
+        assert!(cache.get("actions/checkout@v5").is_some());
+    }
+
+    #[test]
+    fn test_prune_drops_superseded_and_unused_entries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = Cache::load_from(dir.path().join("cache.json")).unwrap();
+        let metadata = checkout_metadata();
+
+        // The tag moved, leaving the entry of its old commit behind
+        cache
+            .set(
+                "actions/checkout@v5",
+                Some(&"a".repeat(40)),
+                &metadata,
+                "old",
+            )
+            .unwrap();
+        cache
+            .set(
+                "actions/checkout@v5",
+                Some(&"b".repeat(40)),
+                &metadata,
+                "new",
+            )
+            .unwrap();
+        cache
+            .set("actions/setup-node@v4", None, &metadata, "yaml")
+            .unwrap();
+
+        let refs = cache.refs(30);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].reference, "actions/checkout@v5");
+        assert_eq!(refs[0].content_hash, calculate_hash("new"));
+        assert!(!refs[0].expired);
+
+        assert_eq!(cache.prune(None).unwrap(), 1);
+        assert!(cache.get("actions/checkout@v5").is_some());
+
+        let used = HashSet::from(["actions/checkout@v5".to_string()]);
+        assert_eq!(cache.prune(Some(&used)).unwrap(), 1);
+        assert_eq!(cache.list(), vec!["actions/checkout@v5"]);
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let source = Cache::load_from(dir.path().join("source.json")).unwrap();
+        source
+            .set(
+                "actions/checkout@v5",
+                Some(&"a".repeat(40)),
+                &checkout_metadata(),
+                "yaml",
+            )
+            .unwrap();
+        let exported = dir.path().join("export.json");
+        source.export(&exported).unwrap();
+
+        let target = Cache::load_from(dir.path().join("target.json")).unwrap();
+        assert_eq!(target.import(&exported).unwrap(), 1);
+        assert_eq!(target.get("actions/checkout@v5").unwrap().name, "Checkout");
+        assert!(target.import(&dir.path().join("missing.json")).is_err());
+
+        let garbage = dir.path().join("garbage.json");
+        std::fs::write(&garbage, "not json").unwrap();
+        assert!(target.import(&garbage).is_err());
+        assert!(garbage.exists());
+    }
+
+    #[test]
+    fn test_misses_expire_and_clear_on_success() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = Cache::load_from(temp.path().join("cache.json")).unwrap();
+        let error = FetchError::RefNotFound {
+            repo: "actions/checkout".to_string(),
+            ref_: "v99".to_string(),
+            host: "https://api.github.com".to_string(),
+        };
+
+        cache.record_miss("actions/checkout@v99", &error).unwrap();
+        let reloaded = Cache::load_from(temp.path().join("cache.json")).unwrap();
+        assert!(matches!(
+            reloaded.get_miss("actions/checkout@v99"),
+            Some(FetchError::RefNotFound { .. })
+        ));
+
+        cache
+            .set(
+                "actions/checkout@v99",
+                None,
+                &checkout_metadata(),
+                "name: Checkout",
+            )
+            .unwrap();
+        assert!(cache.get_miss("actions/checkout@v99").is_none());
+
+        cache.record_miss("actions/checkout@v99", &error).unwrap();
+        cache
+            .update(|data| {
+                data.misses
+                    .get_mut("actions/checkout@v99")
+                    .unwrap()
+                    .expires_at = 0
+            })
+            .unwrap();
+        assert!(cache.get_miss("actions/checkout@v99").is_none());
+        cache.prune(None).unwrap();
+        assert!(cache.data.lock().unwrap().misses.is_empty());
+    }
 }
@@ -8,6 +8,11 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Never access the network: use vendored and cached action metadata only,
+    /// and fail listing the refs that are missing
+    #[arg(long, global = true)]
+    pub offline: bool,
 }
 
 #[derive(Subcommand)]
@@ -29,6 +34,16 @@ pub enum Commands {
         /// Interactive mode
         #[arg(short, long)]
         interactive: bool,
+
+        /// Starter workflow template: a builtin name (node, pnpm, rust, python, go, docker,
+        /// release-please, pages), a local directory or gh:owner/repo/path@ref
+        #[arg(long, value_name = "NAME", conflicts_with = "skip_examples")]
+        template: Option<String>,
+
+        /// Package manager for the gaji scripts (npm, pnpm, yarn, bun, deno, or none to
+        /// skip package.json). Detected from lockfiles by default
+        #[arg(long, value_name = "NAME")]
+        package_manager: Option<String>,
     },
 
     /// Start development mode (one-time scan by default)
@@ -57,6 +72,32 @@ pub enum Commands {
         dry_run: bool,
     },
 
+    /// Migrate YAML workflows and actions to TypeScript
+    Migrate {
+        /// Workflow or action.yml files (defaults to everything in .github/)
+        files: Vec<String>,
+
+        /// Print the TypeScript without writing files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output directory for TypeScript files (defaults to the workflows directory)
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Keep the original YAML files in place
+        #[arg(long)]
+        no_backup: bool,
+
+        /// Replace TypeScript files that already exist
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Extract step sequences repeated across workflows into shared.ts
+        #[arg(long)]
+        dedupe: bool,
+    },
+
     /// List all GitHub Actions used in workflow files
     List {
         /// Workflow directories or individual .ts files
@@ -74,11 +115,39 @@ pub enum Commands {
         action: String,
     },
 
+    /// Store the definitions of every action in use under .gaji/actions
+    Vendor {
+        /// Workflow directories or individual .ts files
+        #[arg(short, long, num_args = 1..)]
+        input: Vec<String>,
+    },
+
     /// Clean generated files
     Clean {
         /// Also clean cache
         #[arg(long)]
         cache: bool,
+
+        /// Only remove types of actions no workflow uses anymore
+        #[arg(long)]
+        unused: bool,
+    },
+
+    /// Inspect and manage the action metadata cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Check the project setup and GitHub access
+    Doctor {
+        /// Output as JSON for support tickets
+        #[arg(long)]
+        json: bool,
+
+        /// Skip the GitHub connectivity and rate-limit check
+        #[arg(long)]
+        no_network: bool,
     },
 
     /// Generate shell completions
@@ -87,3 +156,52 @@ pub enum Commands {
         shell: String,
     },
 }
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// List cached actions and workflows with their age and expiry
+    Ls {
+        /// Output as JSON for scripting
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the cached metadata of a ref
+    Show {
+        /// Action or reusable workflow reference (e.g., actions/checkout@v5)
+        reference: String,
+
+        /// Output as JSON for scripting
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Fetch cached refs again, ignoring the TTL
+    Refresh {
+        /// Ref to refresh (defaults to every cached ref)
+        reference: Option<String>,
+    },
+
+    /// Remove entries for commits no ref points to anymore
+    Prune {
+        /// Also remove refs that no workflow in this project uses
+        #[arg(long)]
+        unused: bool,
+
+        /// Confirm that `--unused` may remove refs other projects still use
+        #[arg(long)]
+        all_projects: bool,
+    },
+
+    /// Write the cache to a file
+    Export {
+        /// Destination JSON file
+        file: String,
+    },
+
+    /// Merge a file written by 'gaji cache export' into the cache
+    Import {
+        /// Exported JSON file
+        file: String,
+    },
+}
@@ -1,15 +1,19 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use anyhow::{Context, Result};
+use colored::Colorize;
 use rquickjs::{function::Func, Context as JsContext, Runtime as JsRuntime};
 use serde::Deserialize;
 
+use crate::credentials::{host_of, Credential, CredentialChain};
+
 pub const TS_CONFIG_FILE: &str = "gaji.config.ts";
 pub const TS_LOCAL_CONFIG_FILE: &str = "gaji.config.local.ts";
 
-const TOML_CONFIG_FILE: &str = ".gaji.toml";
+pub const TOML_CONFIG_FILE: &str = ".gaji.toml";
 const TOML_LOCAL_CONFIG_FILE: &str = ".gaji.local.toml";
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -25,6 +29,16 @@ pub struct Config {
 
     #[serde(default)]
     pub github: GitHubConfig,
+
+    #[serde(default)]
+    pub network: NetworkConfig,
+
+    #[serde(default)]
+    pub types: TypesConfig,
+
+    /// Hosts to fetch actions from, keyed by `owner/repo`, `owner/*` or `*`.
+    #[serde(default)]
+    pub sources: HashMap<String, SourceConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -64,6 +78,97 @@ pub struct BuildConfig {
 pub struct GitHubConfig {
     pub token: Option<String>,
     pub api_url: Option<String>,
+    /// Tokens keyed by host name, for the default host and `sources` alike.
+    #[serde(default)]
+    pub tokens: HashMap<String, String>,
+    /// Shell command that prints a token for the host in `GAJI_HOST`.
+    pub credential_helper: Option<String>,
+}
+
+/// One entry of `sources`.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SourceConfig {
+    /// `github`, `gitea`, `forgejo` or `dir`
+    #[serde(rename = "type")]
+    pub source_type: String,
+    /// Web URL of the host. Optional for `github`, where it selects GitHub
+    /// Enterprise instead of github.com.
+    pub url: Option<String>,
+    /// Directory of checkouts for `dir`.
+    pub path: Option<String>,
+    pub token: Option<String>,
+}
+
+/// HTTP settings for fetching action metadata.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkConfig {
+    /// Proxy for all requests. Without it, `HTTPS_PROXY` and friends apply.
+    pub proxy: Option<String>,
+
+    /// Hosts that bypass `proxy`, in `NO_PROXY` syntax.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+
+    /// PEM bundles trusted in addition to the system roots.
+    #[serde(default)]
+    pub ca_certs: Vec<String>,
+
+    /// PEM client certificate for mutual TLS, which may also hold the key.
+    pub client_cert: Option<String>,
+
+    /// PEM private key of `client_cert`, when it is a separate file.
+    pub client_key: Option<String>,
+
+    /// Seconds a request may take, 0 for no limit.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Seconds connecting may take, 0 for no limit.
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// Refs fetched at the same time.
+    #[serde(default = "default_concurrency", deserialize_with = "at_least_one")]
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct TypesConfig {
+    /// Hand-written type corrections keyed by action ref (`owner/repo@v1` or `owner/repo`)
+    #[serde(default)]
+    pub overrides: HashMap<String, TypeOverride>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+pub struct TypeOverride {
+    /// Input name -> TypeScript type, used verbatim in the generated `.d.ts`
+    #[serde(default)]
+    pub inputs: HashMap<String, String>,
+
+    /// Output name -> TypeScript type
+    #[serde(default)]
+    pub outputs: HashMap<String, String>,
+}
+
+impl TypeOverride {
+    /// Layer `other` on top of `self`; entries in `other` win.
+    pub fn merge(&mut self, other: &TypeOverride) {
+        self.inputs
+            .extend(other.inputs.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.outputs
+            .extend(other.outputs.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+impl TypesConfig {
+    /// Find the override for an action ref, preferring an exact match over the
+    /// unversioned `owner/repo` form.
+    pub fn override_for(&self, action_ref: &str) -> Option<&TypeOverride> {
+        self.overrides.get(action_ref).or_else(|| {
+            let unversioned = action_ref.split('@').next()?;
+            self.overrides.get(unversioned)
+        })
+    }
 }
 
 impl Default for ProjectConfig {
@@ -99,6 +204,21 @@ impl Default for BuildConfig {
     }
 }
 
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            no_proxy: Vec::new(),
+            ca_certs: Vec::new(),
+            client_cert: None,
+            client_key: None,
+            timeout_secs: default_timeout_secs(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            concurrency: default_concurrency(),
+        }
+    }
+}
+
 fn default_workflows_dir() -> String {
     "workflows".to_string()
 }
@@ -119,6 +239,23 @@ fn default_cache_ttl_days() -> u64 {
     30
 }
 
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_concurrency() -> usize {
+    10
+}
+
+/// Zero would never fetch anything, so it counts as one.
+fn at_least_one<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    usize::deserialize(deserializer).map(|n| n.max(1))
+}
+
 fn default_true() -> bool {
     true
 }
@@ -134,6 +271,9 @@ struct TsGajiConfig {
     watch: Option<TsWatchConfig>,
     build: Option<TsBuildConfig>,
     github: Option<TsGitHubConfig>,
+    network: Option<TsNetworkConfig>,
+    types: Option<TypesConfig>,
+    sources: Option<HashMap<String, SourceConfig>>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -158,6 +298,263 @@ struct TsGitHubConfig {
     token: Option<String>,
     #[serde(rename = "apiUrl")]
     api_url: Option<String>,
+    tokens: Option<HashMap<String, String>>,
+    #[serde(rename = "credentialHelper")]
+    credential_helper: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct TsNetworkConfig {
+    proxy: Option<String>,
+    no_proxy: Option<Vec<String>>,
+    ca_certs: Option<Vec<String>>,
+    client_cert: Option<String>,
+    client_key: Option<String>,
+    timeout: Option<u64>,
+    connect_timeout: Option<u64>,
+    concurrency: Option<usize>,
+}
+
+// -- TS config validation --
+
+/// Expected shape of a config value. Mirrors `TsGajiConfig` so mistakes are
+/// reported with their path instead of as serde errors, or silently ignored.
+enum Schema {
+    String,
+    Bool,
+    /// Non-negative integer.
+    Integer,
+    StringArray,
+    Object(&'static [(&'static str, Schema)]),
+    /// Object with arbitrary keys whose values all match the inner schema.
+    Map(&'static Schema),
+    /// One of a fixed set of strings.
+    Enum(&'static [&'static str]),
+}
+
+const TYPE_OVERRIDE_SCHEMA: Schema = Schema::Object(&[
+    ("inputs", Schema::Map(&Schema::String)),
+    ("outputs", Schema::Map(&Schema::String)),
+]);
+
+const SOURCE_SCHEMA: Schema = Schema::Object(&[
+    ("type", Schema::Enum(crate::source::SOURCE_TYPES)),
+    ("url", Schema::String),
+    ("path", Schema::String),
+    ("token", Schema::String),
+]);
+
+const TS_CONFIG_SCHEMA: Schema = Schema::Object(&[
+    ("workflows", Schema::String),
+    ("output", Schema::String),
+    ("generated", Schema::String),
+    (
+        "watch",
+        Schema::Object(&[
+            ("debounce", Schema::Integer),
+            ("ignore", Schema::StringArray),
+        ]),
+    ),
+    (
+        "build",
+        Schema::Object(&[
+            ("validate", Schema::Bool),
+            ("format", Schema::Bool),
+            ("cacheTtlDays", Schema::Integer),
+        ]),
+    ),
+    (
+        "github",
+        Schema::Object(&[
+            ("token", Schema::String),
+            ("apiUrl", Schema::String),
+            ("tokens", Schema::Map(&Schema::String)),
+            ("credentialHelper", Schema::String),
+        ]),
+    ),
+    (
+        "network",
+        Schema::Object(&[
+            ("proxy", Schema::String),
+            ("noProxy", Schema::StringArray),
+            ("caCerts", Schema::StringArray),
+            ("clientCert", Schema::String),
+            ("clientKey", Schema::String),
+            ("timeout", Schema::Integer),
+            ("connectTimeout", Schema::Integer),
+            ("concurrency", Schema::Integer),
+        ]),
+    ),
+    (
+        "types",
+        Schema::Object(&[("overrides", Schema::Map(&TYPE_OVERRIDE_SCHEMA))]),
+    ),
+    ("sources", Schema::Map(&SOURCE_SCHEMA)),
+]);
+
+/// Check a config object against `TS_CONFIG_SCHEMA`, returning one message
+/// per problem.
+fn validate_ts_config(value: &serde_json::Value) -> Vec<String> {
+    let mut issues = Vec::new();
+    validate_value(value, &TS_CONFIG_SCHEMA, "", &mut issues);
+    issues
+}
+
+fn validate_value(
+    value: &serde_json::Value,
+    schema: &Schema,
+    path: &str,
+    issues: &mut Vec<String>,
+) {
+    use serde_json::Value;
+
+    let field = |path: &str| {
+        if path.is_empty() {
+            "config".to_string()
+        } else {
+            format!("`{}`", path)
+        }
+    };
+    let mut expect = |expected: &str| {
+        issues.push(format!(
+            "{}: expected {}, found {}",
+            field(path),
+            expected,
+            describe_json(value)
+        ))
+    };
+
+    match (schema, value) {
+        (Schema::String, Value::String(_)) | (Schema::Bool, Value::Bool(_)) => {}
+        (Schema::Integer, Value::Number(n)) if n.is_u64() => {}
+        (Schema::String, _) => expect("a string"),
+        (Schema::Bool, _) => expect("a boolean"),
+        (Schema::Integer, _) => expect("a non-negative integer"),
+        (Schema::StringArray, Value::Array(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                validate_value(item, &Schema::String, &format!("{}[{}]", path, i), issues);
+            }
+        }
+        (Schema::StringArray, _) => expect("an array of strings"),
+        (Schema::Enum(names), Value::String(s)) if names.contains(&s.as_str()) => {}
+        (Schema::Enum(names), _) => expect(&format!("one of {}", names.join(", "))),
+        (Schema::Object(fields), Value::Object(map)) => {
+            for (key, item) in map {
+                let item_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match fields.iter().find(|(name, _)| name == key) {
+                    // `undefined` is dropped by JSON.stringify; treat `null` the same
+                    Some(_) if item.is_null() => {}
+                    Some((_, field_schema)) => {
+                        validate_value(item, field_schema, &item_path, issues)
+                    }
+                    None => {
+                        let names: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+                        let hint = match suggest_key(key, &names) {
+                            Some(name) => format!(" (did you mean `{}`?)", name),
+                            None => format!(" (expected one of: {})", names.join(", ")),
+                        };
+                        issues.push(format!("unknown key `{}`{}", item_path, hint));
+                    }
+                }
+            }
+        }
+        (Schema::Map(inner), Value::Object(map)) => {
+            for (key, item) in map {
+                validate_value(item, inner, &format!("{}[\"{}\"]", path, key), issues);
+            }
+        }
+        (Schema::Object(_) | Schema::Map(_), _) => expect("an object"),
+    }
+}
+
+fn describe_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(b) => format!("a boolean ({})", b),
+        serde_json::Value::Number(n) => format!("a number ({})", n),
+        serde_json::Value::String(s) => format!("a string (\"{}\")", s),
+        serde_json::Value::Array(_) => "an array".to_string(),
+        serde_json::Value::Object(_) => "an object".to_string(),
+    }
+}
+
+/// The closest known key to `key`, ignoring case, `_` and `-`, if it is close
+/// enough to be a likely typo.
+fn suggest_key<'a>(key: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let normalize = |s: &str| -> Vec<char> {
+        s.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let key = normalize(key);
+
+    candidates
+        .iter()
+        .map(|candidate| {
+            let normalized = normalize(candidate);
+            (
+                edit_distance(&key, &normalized),
+                normalized.len(),
+                *candidate,
+            )
+        })
+        .filter(|(distance, len, _)| *distance <= (*len / 3).max(1))
+        .min_by_key(|(distance, _, _)| *distance)
+        .map(|(_, _, candidate)| candidate)
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Recursively merge `overlay` into `base`. Objects are merged key by key;
+/// any other value in `overlay`, including arrays, replaces the one in `base`.
+fn merge_json(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// TOML counterpart of `merge_json`, for the legacy `.gaji.local.toml`.
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
 }
 
 impl From<TsGajiConfig> for Config {
@@ -198,12 +595,85 @@ impl From<TsGajiConfig> for Config {
         if let Some(github) = ts.github {
             config.github.token = github.token;
             config.github.api_url = github.api_url;
+            config.github.tokens = github.tokens.unwrap_or_default();
+            config.github.credential_helper = github.credential_helper;
+        }
+
+        if let Some(network) = ts.network {
+            config.network.proxy = network.proxy;
+            config.network.client_cert = network.client_cert;
+            config.network.client_key = network.client_key;
+            if let Some(no_proxy) = network.no_proxy {
+                config.network.no_proxy = no_proxy;
+            }
+            if let Some(ca_certs) = network.ca_certs {
+                config.network.ca_certs = ca_certs;
+            }
+            if let Some(timeout) = network.timeout {
+                config.network.timeout_secs = timeout;
+            }
+            if let Some(connect_timeout) = network.connect_timeout {
+                config.network.connect_timeout_secs = connect_timeout;
+            }
+            if let Some(concurrency) = network.concurrency {
+                // Zero would never fetch anything
+                config.network.concurrency = concurrency.max(1);
+            }
+        }
+
+        if let Some(types) = ts.types {
+            config.types = types;
+        }
+
+        if let Some(sources) = ts.sources {
+            config.sources = sources;
         }
 
         config
     }
 }
 
+/// Evaluate a TypeScript config file and return the object passed to
+/// `defineConfig`, checking only that it is an object.
+fn evaluate_ts_config(path: &Path) -> Result<serde_json::Value> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+    let filename = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    // Strip TypeScript types
+    let js = crate::executor::strip_typescript(&source, &filename)?;
+
+    // Remove import/export statements
+    let js = crate::executor::remove_imports(&js);
+
+    // Wrap: override defineConfig to identity, capture result
+    let wrapped = format!(
+        r#"function defineConfig(c) {{ return c; }}
+var __config_result = {};
+__gha_set_config(JSON.stringify(__config_result));"#,
+        js.trim().trim_end_matches(';')
+    );
+
+    let json = execute_config_js(&wrapped)?;
+
+    let value: serde_json::Value = serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse config JSON from {}", path.display()))?;
+    if !value.is_object() {
+        anyhow::bail!(
+            "{} must export an object, found {}",
+            path.display(),
+            describe_json(&value)
+        );
+    }
+
+    Ok(value)
+}
+
 /// Execute JavaScript in QuickJS and capture config JSON via `__gha_set_config`.
 fn execute_config_js(code: &str) -> Result<String> {
     let result: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
@@ -243,24 +713,37 @@ fn execute_config_js(code: &str) -> Result<String> {
 impl Config {
     /// Load config: try `gaji.config.ts` first, fall back to `.gaji.toml`.
     pub fn load() -> Result<Self> {
-        let ts_path = Path::new(TS_CONFIG_FILE);
+        Self::load_in(Path::new(""))
+    }
+
+    /// Load config from the project at `root`.
+    pub fn load_in(root: &Path) -> Result<Self> {
+        let ts_path = root.join(TS_CONFIG_FILE);
         if ts_path.exists() {
-            let mut config = Self::load_from_ts(ts_path)?;
+            let mut value = evaluate_ts_config(&ts_path)?;
 
-            // Merge local TS config if it exists
-            let ts_local_path = Path::new(TS_LOCAL_CONFIG_FILE);
+            // Deep-merge the local TS config if it exists; any field may be overridden
+            let ts_local_path = root.join(TS_LOCAL_CONFIG_FILE);
             if ts_local_path.exists() {
-                let local = Self::load_from_ts(ts_local_path)?;
-                config.merge_local(local);
+                merge_json(&mut value, evaluate_ts_config(&ts_local_path)?);
             }
 
-            return Ok(config);
+            return Self::from_ts_value(value, &ts_path);
         }
 
         // Fall back to TOML
+        let toml_path = root.join(TOML_CONFIG_FILE);
+        if toml_path.exists() {
+            eprintln!(
+                "{} {} is deprecated. Run 'gaji init' to migrate it to {}",
+                "⚠️ ".yellow(),
+                TOML_CONFIG_FILE,
+                TS_CONFIG_FILE
+            );
+        }
         Self::load_with_local(
-            Path::new(TOML_CONFIG_FILE),
-            Path::new(TOML_LOCAL_CONFIG_FILE),
+            &root.join(TOML_CONFIG_FILE),
+            &root.join(TOML_LOCAL_CONFIG_FILE),
         )
     }
 
@@ -270,32 +753,21 @@ impl Config {
             return Ok(Config::default());
         }
 
-        let source = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-
-        let filename = path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        // Strip TypeScript types
-        let js = crate::executor::strip_typescript(&source, &filename)?;
-
-        // Remove import/export statements
-        let js = crate::executor::remove_imports(&js);
-
-        // Wrap: override defineConfig to identity, capture result
-        let wrapped = format!(
-            r#"function defineConfig(c) {{ return c; }}
-var __config_result = {};
-__gha_set_config(JSON.stringify(__config_result));"#,
-            js.trim().trim_end_matches(';')
-        );
+        Self::from_ts_value(evaluate_ts_config(path)?, path)
+    }
 
-        let json = execute_config_js(&wrapped)?;
+    /// Validate the evaluated config object and convert it.
+    fn from_ts_value(value: serde_json::Value, path: &Path) -> Result<Self> {
+        let issues = validate_ts_config(&value);
+        if !issues.is_empty() {
+            anyhow::bail!(
+                "Invalid config in {}:\n  - {}",
+                path.display(),
+                issues.join("\n  - ")
+            );
+        }
 
-        let ts_config: TsGajiConfig = serde_json::from_str(&json)
+        let ts_config: TsGajiConfig = serde_json::from_value(value)
             .with_context(|| format!("Failed to parse config JSON from {}", path.display()))?;
 
         Ok(Config::from(ts_config))
@@ -311,33 +783,47 @@ __gha_set_config(JSON.stringify(__config_result));"#,
         }
     }
 
+    /// Load a TOML config and deep-merge the local file over it.
     pub fn load_with_local(config_path: &Path, local_path: &Path) -> Result<Self> {
-        let mut config = Self::load_from(config_path)?;
+        let read = |path: &Path| -> Result<toml::Value> {
+            if !path.exists() {
+                return Ok(toml::Value::Table(Default::default()));
+            }
+            let content = std::fs::read_to_string(path)?;
+            toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+        };
 
-        if local_path.exists() {
-            let local_content = std::fs::read_to_string(local_path)?;
-            let local: Config = toml::from_str(&local_content)?;
-            config.merge_local(local);
-        }
+        let mut value = read(config_path)?;
+        merge_toml(&mut value, read(local_path)?);
 
+        let config = value
+            .try_into()
+            .with_context(|| format!("Invalid config in {}", config_path.display()))?;
         Ok(config)
     }
 
-    fn merge_local(&mut self, local: Config) {
-        if local.github.token.is_some() {
-            self.github.token = local.github.token;
-        }
-        if local.github.api_url.is_some() {
-            self.github.api_url = local.github.api_url;
-        }
+    /// Resolve the token for the default GitHub host. See
+    /// [`CredentialChain`] for where it is looked up.
+    pub fn resolve_token(&self) -> Option<String> {
+        self.resolve_credential().map(|credential| credential.token)
     }
 
-    /// Resolve the GitHub token with priority: env var > local config > public config
-    pub fn resolve_token(&self) -> Option<String> {
-        std::env::var("GITHUB_TOKEN")
-            .ok()
-            .filter(|s| !s.is_empty())
-            .or_else(|| self.github.token.clone())
+    /// Token for the default GitHub host along with where it was found.
+    pub fn resolve_credential(&self) -> Option<Credential> {
+        CredentialChain::new(&self.github).resolve(&self.github_host(), true)
+    }
+
+    /// Token for `host`, which may be the default GitHub host or the host of
+    /// a `sources` entry.
+    pub fn token_for_host(&self, host: &str) -> Option<String> {
+        CredentialChain::new(&self.github)
+            .resolve(host, host == self.github_host())
+            .map(|credential| credential.token)
+    }
+
+    /// Host name of github.com or `github.apiUrl`.
+    pub fn github_host(&self) -> String {
+        host_of(self.github.api_url.as_deref())
     }
 
     /// Resolve the GitHub API base URL for raw content.
@@ -435,43 +921,133 @@ api_url = "https://github.example.com"
         );
     }
 
+    fn merged(base: serde_json::Value, local: serde_json::Value) -> Config {
+        let mut value = base;
+        merge_json(&mut value, local);
+        Config::from_ts_value(value, Path::new(TS_CONFIG_FILE)).unwrap()
+    }
+
     #[test]
     fn test_merge_local_overrides_token() {
-        let mut config = Config::default();
-        config.github.token = Some("public_token".to_string());
-
-        let mut local = Config::default();
-        local.github.token = Some("local_secret_token".to_string());
-
-        config.merge_local(local);
+        let config = merged(
+            serde_json::json!({ "github": { "token": "public_token" } }),
+            serde_json::json!({ "github": { "token": "local_secret_token" } }),
+        );
         assert_eq!(config.github.token, Some("local_secret_token".to_string()));
     }
 
     #[test]
     fn test_merge_local_does_not_clear_existing() {
-        let mut config = Config::default();
-        config.github.token = Some("existing_token".to_string());
-
-        let local = Config::default(); // no token set
-        config.merge_local(local);
+        let config = merged(
+            serde_json::json!({ "github": { "token": "existing_token" } }),
+            serde_json::json!({}), // no token set
+        );
 
         assert_eq!(config.github.token, Some("existing_token".to_string()));
     }
 
     #[test]
     fn test_merge_local_overrides_api_url() {
-        let mut config = Config::default();
-
-        let mut local = Config::default();
-        local.github.api_url = Some("https://ghe.corp.com".to_string());
-
-        config.merge_local(local);
+        let config = merged(
+            serde_json::json!({}),
+            serde_json::json!({ "github": { "apiUrl": "https://ghe.corp.com" } }),
+        );
         assert_eq!(
             config.github.api_url,
             Some("https://ghe.corp.com".to_string())
         );
     }
 
+    #[test]
+    fn test_merge_local_is_deep() {
+        let config = merged(
+            serde_json::json!({
+                "workflows": "ci",
+                "build": { "validate": false, "cacheTtlDays": 7 },
+                "watch": { "ignore": ["a", "b"] },
+            }),
+            serde_json::json!({
+                "build": { "cacheTtlDays": 1 },
+                "watch": { "ignore": ["c"] },
+            }),
+        );
+        assert_eq!(config.project.workflows_dir, "ci");
+        assert!(!config.build.validate);
+        assert_eq!(config.build.cache_ttl_days, 1);
+        // Arrays are replaced, not concatenated
+        assert_eq!(config.watch.ignored_patterns, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_unknown_key_suggestion() {
+        let issues = validate_ts_config(&serde_json::json!({
+            "workflowDir": "src/workflows",
+            "build": { "cache_ttl_days": 3 },
+            "github": { "hostname": "x" },
+        }));
+        assert_eq!(issues.len(), 3);
+        assert!(
+            issues.contains(&"unknown key `workflowDir` (did you mean `workflows`?)".to_string())
+        );
+        assert!(issues.contains(
+            &"unknown key `build.cache_ttl_days` (did you mean `cacheTtlDays`?)".to_string()
+        ));
+        assert!(issues.contains(
+            &"unknown key `github.hostname` (expected one of: token, apiUrl, tokens, credentialHelper)".to_string()
+        ));
+    }
+
+    #[test]
+    fn test_validate_type_errors() {
+        let issues = validate_ts_config(&serde_json::json!({
+            "output": null,
+            "build": { "cacheTtlDays": "14" },
+            "watch": { "ignore": ["ok", 3] },
+            "types": { "overrides": { "actions/cache": { "inputs": { "key": true } } } },
+        }));
+        assert_eq!(
+            issues,
+            vec![
+                "`build.cacheTtlDays`: expected a non-negative integer, found a string (\"14\")",
+                "`types.overrides[\"actions/cache\"].inputs[\"key\"]`: expected a string, found a boolean (true)",
+                "`watch.ignore[1]`: expected a string, found a number (3)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_from_ts_reports_invalid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("gaji.config.ts");
+        std::fs::write(
+            &config_path,
+            r#"export default defineConfig({ workflowDir: "src", build: { format: "yes" } });"#,
+        )
+        .unwrap();
+
+        let err = Config::load_from_ts(&config_path).unwrap_err().to_string();
+        assert!(err.contains("Invalid config in"));
+        assert!(err.contains("did you mean `workflows`?"));
+        assert!(err.contains("`build.format`: expected a boolean"));
+    }
+
+    #[test]
+    fn test_load_with_local_toml_is_deep() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join(".gaji.toml");
+        let local_path = dir.path().join(".gaji.local.toml");
+        std::fs::write(
+            &config_path,
+            "[project]\nworkflows_dir = \"ci\"\noutput_dir = \"out\"\n",
+        )
+        .unwrap();
+        std::fs::write(&local_path, "[project]\noutput_dir = \"local-out\"\n").unwrap();
+
+        let config = Config::load_with_local(&config_path, &local_path).unwrap();
+        assert_eq!(config.project.workflows_dir, "ci");
+        assert_eq!(config.project.output_dir, "local-out");
+    }
+
     #[test]
     fn test_load_with_local_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -512,6 +1088,7 @@ api_url = "https://ghe.internal.com"
         config.github.token = Some("config_token".to_string());
 
         // When GITHUB_TOKEN env is set, it takes priority
+        std::env::remove_var("GH_TOKEN");
         std::env::set_var("GITHUB_TOKEN", "env_token");
         assert_eq!(config.resolve_token(), Some("env_token".to_string()));
         std::env::remove_var("GITHUB_TOKEN");
@@ -519,7 +1096,8 @@ api_url = "https://ghe.internal.com"
 
     #[test]
     fn test_resolve_token_falls_back_to_config() {
-        // Ensure env var is not set
+        // Ensure env vars are not set
+        std::env::remove_var("GH_TOKEN");
         std::env::remove_var("GITHUB_TOKEN");
 
         let mut config = Config::default();
@@ -530,12 +1108,39 @@ api_url = "https://ghe.internal.com"
 
     #[test]
     fn test_resolve_token_returns_none_when_empty() {
+        std::env::remove_var("GH_TOKEN");
         std::env::remove_var("GITHUB_TOKEN");
 
-        let config = Config::default();
+        // A host no netrc or gh login on this machine has a token for
+        let mut config = Config::default();
+        config.github.api_url = Some("https://ghe.invalid".to_string());
         assert_eq!(config.resolve_token(), None);
     }
 
+    #[test]
+    fn test_per_host_tokens() {
+        let config = Config::from(
+            serde_json::from_value::<TsGajiConfig>(serde_json::json!({
+                "github": {
+                    "apiUrl": "https://ghe.corp.com",
+                    "token": "default_token",
+                    "tokens": { "codeberg.org": "codeberg_token" },
+                    "credentialHelper": "pass show gaji",
+                },
+            }))
+            .unwrap(),
+        );
+        assert_eq!(config.github_host(), "ghe.corp.com");
+        assert_eq!(
+            config.github.credential_helper.as_deref(),
+            Some("pass show gaji")
+        );
+        assert_eq!(
+            config.token_for_host("codeberg.org"),
+            Some("codeberg_token".to_string())
+        );
+    }
+
     #[test]
     fn test_load_from_ts_basic() {
         let dir = tempfile::tempdir().unwrap();
@@ -597,9 +1202,7 @@ export default defineConfig({
         )
         .unwrap();
 
-        let mut config = Config::load_from_ts(&config_path).unwrap();
-        let local = Config::load_from_ts(&local_path).unwrap();
-        config.merge_local(local);
+        let config = Config::load_in(dir.path()).unwrap();
 
         assert_eq!(config.project.workflows_dir, "workflows");
         assert_eq!(config.github.token, Some("ghp_secret_local".to_string()));
@@ -650,6 +1253,7 @@ export default defineConfig({
         assert_eq!(config.github.token, Some("ts_config_token".to_string()));
 
         // Set env var — should take precedence via resolve_token()
+        std::env::remove_var("GH_TOKEN");
         std::env::set_var("GITHUB_TOKEN", "env_override_token");
         assert_eq!(
             config.resolve_token(),
@@ -660,4 +1264,123 @@ export default defineConfig({
         // Without env var, falls back to TS config value
         assert_eq!(config.resolve_token(), Some("ts_config_token".to_string()));
     }
+
+    #[test]
+    fn test_load_from_ts_type_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("gaji.config.ts");
+        std::fs::write(
+            &config_path,
+            r#"
+export default defineConfig({
+    types: {
+        overrides: {
+            "actions/setup-node": {
+                inputs: { cache: "'npm' | 'yarn' | 'pnpm'" },
+            },
+            "actions/setup-node@v3": {
+                inputs: { "node-version": "string" },
+            },
+        },
+    },
+});
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_from_ts(&config_path).unwrap();
+        let v4 = config.types.override_for("actions/setup-node@v4").unwrap();
+        assert_eq!(v4.inputs["cache"], "'npm' | 'yarn' | 'pnpm'");
+        // An exact ref match takes precedence over the unversioned entry
+        let v3 = config.types.override_for("actions/setup-node@v3").unwrap();
+        assert_eq!(v3.inputs["node-version"], "string");
+        assert!(config.types.override_for("actions/cache@v4").is_none());
+    }
+
+    #[test]
+    fn test_load_from_ts_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("gaji.config.ts");
+        std::fs::write(
+            &config_path,
+            r#"
+export default defineConfig({
+    sources: {
+        "myorg/*": { type: "github", url: "https://github.example.com" },
+        "forge/lint": { type: "forgejo", url: "https://codeberg.org", token: "secret" },
+    },
+});
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_from_ts(&config_path).unwrap();
+        assert_eq!(config.sources.len(), 2);
+        let forge = &config.sources["forge/lint"];
+        assert_eq!(forge.source_type, "forgejo");
+        assert_eq!(forge.token.as_deref(), Some("secret"));
+
+        let issues = validate_ts_config(&serde_json::json!({
+            "sources": { "*": { "type": "gitlab" } },
+        }));
+        assert_eq!(
+            issues,
+            vec![
+                "`sources[\"*\"].type`: expected one of github, gitea, forgejo, dir, found a string (\"gitlab\")"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_toml_zero_concurrency() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join(".gaji.toml");
+        std::fs::write(&config_path, "[network]\nconcurrency = 0\n").unwrap();
+
+        let config =
+            Config::load_with_local(&config_path, &dir.path().join(".gaji.local.toml")).unwrap();
+        assert_eq!(config.network.concurrency, 1);
+    }
+
+    #[test]
+    fn test_load_from_ts_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("gaji.config.ts");
+        std::fs::write(
+            &config_path,
+            r#"
+export default defineConfig({
+    network: {
+        proxy: "http://proxy.corp:3128",
+        noProxy: ["localhost", ".corp"],
+        caCerts: ["certs/corp-root.pem"],
+        timeout: 0,
+        concurrency: 2,
+    },
+});
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_from_ts(&config_path).unwrap();
+        let network = &config.network;
+        assert_eq!(network.proxy.as_deref(), Some("http://proxy.corp:3128"));
+        assert_eq!(network.no_proxy, vec!["localhost", ".corp"]);
+        assert_eq!(network.ca_certs, vec!["certs/corp-root.pem"]);
+        assert_eq!(network.timeout_secs, 0);
+        assert_eq!(network.connect_timeout_secs, 10);
+        assert_eq!(network.concurrency, 2);
+        assert!(network.client_cert.is_none());
+
+        let issues = validate_ts_config(&serde_json::json!({
+            "network": { "caCerts": "corp.pem", "timeout": -1 },
+        }));
+        assert_eq!(
+            issues,
+            vec![
+                "`network.caCerts`: expected an array of strings, found a string (\"corp.pem\")",
+                "`network.timeout`: expected a non-negative integer, found a number (-1)",
+            ]
+        );
+    }
 }
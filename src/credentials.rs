@@ -0,0 +1,412 @@
+//! Where tokens come from.
+//!
+//! A [`CredentialChain`] asks each place a token may live in turn and stops at
+//! the first that has one for the host. Tokens that were handed out are
+//! remembered so [`redact`] can strip them from error output.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::GitHubConfig;
+
+/// Environment variables checked for the default GitHub host, in order.
+pub const TOKEN_ENV_VARS: &[&str] = &["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// Prefixes of GitHub tokens, redacted even when gaji did not hand them out.
+const TOKEN_PREFIXES: &[&str] = &["ghp_", "gho_", "ghu_", "ghs_", "ghr_", "github_pat_"];
+
+const REDACTED: &str = "***";
+
+/// Tokens handed out so far.
+static SECRETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Output of the credential helper per host, so it runs once per process.
+static HELPER_OUTPUT: Mutex<Option<HashMap<String, Option<String>>>> = Mutex::new(None);
+
+/// Where a token was found, as reported by `gaji doctor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenSource {
+    Env(&'static str),
+    /// `github.tokens` in the config, keyed by host.
+    HostConfig,
+    /// `github.token` in the config.
+    Config,
+    Netrc(PathBuf),
+    GhCli(PathBuf),
+    Helper,
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env(name) => write!(f, "{}", name),
+            Self::HostConfig => write!(f, "github.tokens in config"),
+            Self::Config => write!(f, "github.token in config"),
+            Self::Netrc(path) => write!(f, "{}", path.display()),
+            Self::GhCli(path) => write!(f, "gh CLI ({})", path.display()),
+            Self::Helper => write!(f, "github.credentialHelper"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub token: String,
+    pub source: TokenSource,
+}
+
+/// The places a token may come from, checked in this order:
+///
+/// 1. `GH_TOKEN`, then `GITHUB_TOKEN` (default GitHub host only)
+/// 2. `github.tokens["<host>"]`
+/// 3. `github.token` (default GitHub host only)
+/// 4. `~/.netrc`
+/// 5. the `gh` CLI hosts file
+/// 6. the `github.credentialHelper` command
+pub struct CredentialChain {
+    env: fn(&str) -> Option<String>,
+    host_tokens: HashMap<String, String>,
+    token: Option<String>,
+    netrc: Option<PathBuf>,
+    gh_hosts: Option<PathBuf>,
+    helper: Option<String>,
+}
+
+impl CredentialChain {
+    /// Chain over the process environment, the home directory and `github`.
+    pub fn new(github: &GitHubConfig) -> Self {
+        Self {
+            env: |name| std::env::var(name).ok(),
+            host_tokens: github.tokens.clone(),
+            token: github.token.clone(),
+            netrc: netrc_path(),
+            gh_hosts: gh_hosts_path(),
+            helper: github.credential_helper.clone(),
+        }
+    }
+
+    /// First token for `host`. The environment variables and `github.token`
+    /// are only sent to the default GitHub host, never to other hosts.
+    pub fn resolve(&self, host: &str, default_host: bool) -> Option<Credential> {
+        let credential = self.find(host, default_host)?;
+        remember_secret(&credential.token);
+        Some(credential)
+    }
+
+    fn find(&self, host: &str, default_host: bool) -> Option<Credential> {
+        let found = |token: String, source| Some(Credential { token, source });
+        let non_empty = |token: Option<String>| token.filter(|t| !t.trim().is_empty());
+
+        if default_host {
+            for name in TOKEN_ENV_VARS {
+                if let Some(token) = non_empty((self.env)(name)) {
+                    return found(token, TokenSource::Env(name));
+                }
+            }
+        }
+        if let Some(token) = non_empty(self.host_tokens.get(host).cloned()) {
+            return found(token, TokenSource::HostConfig);
+        }
+        if default_host {
+            if let Some(token) = non_empty(self.token.clone()) {
+                return found(token, TokenSource::Config);
+            }
+        }
+        if let Some(path) = &self.netrc {
+            if let Some(token) = read_netrc(path, host) {
+                return found(token, TokenSource::Netrc(path.clone()));
+            }
+        }
+        if let Some(path) = &self.gh_hosts {
+            if let Some(token) = read_gh_hosts(path, host) {
+                return found(token, TokenSource::GhCli(path.clone()));
+            }
+        }
+        if let Some(command) = &self.helper {
+            if let Some(token) = run_helper(command, host) {
+                return found(token, TokenSource::Helper);
+            }
+        }
+        None
+    }
+}
+
+/// Host name of a base URL, `github.com` when there is none.
+pub fn host_of(url: Option<&str>) -> String {
+    url.and_then(|url| reqwest::Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "github.com".to_string())
+}
+
+/// Replace every token gaji handed out, and anything shaped like a GitHub
+/// token, with `***`.
+pub fn redact(text: &str) -> String {
+    let mut text = text.to_string();
+    for secret in SECRETS.lock().unwrap().iter() {
+        text = text.replace(secret.as_str(), REDACTED);
+    }
+
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some((start, prefix)) = TOKEN_PREFIXES
+        .iter()
+        .filter_map(|prefix| rest.find(prefix).map(|i| (i, prefix)))
+        .min()
+    {
+        let body = &rest[start + prefix.len()..];
+        let len = body
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(body.len());
+        redacted.push_str(&rest[..start]);
+        if len == 0 {
+            redacted.push_str(prefix);
+        } else {
+            redacted.push_str(REDACTED);
+        }
+        rest = &body[len..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Also redact `token`, for tokens that do not come from a chain.
+pub fn remember_secret(token: &str) {
+    // Very short values would redact ordinary words
+    if token.len() < 8 {
+        return;
+    }
+    let mut secrets = SECRETS.lock().unwrap();
+    if !secrets.iter().any(|s| s == token) {
+        secrets.push(token.to_string());
+    }
+}
+
+fn netrc_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("NETRC") {
+        return Some(PathBuf::from(path));
+    }
+    let home = dirs_home()?;
+    let name = if cfg!(windows) { "_netrc" } else { ".netrc" };
+    Some(home.join(name))
+}
+
+fn gh_hosts_path() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GH_CONFIG_DIR") {
+        return Some(PathBuf::from(dir).join("hosts.yml"));
+    }
+    if cfg!(windows) {
+        let app_data = std::env::var_os("AppData")?;
+        return Some(PathBuf::from(app_data).join("GitHub CLI").join("hosts.yml"));
+    }
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs_home().map(|home| home.join(".config")))?;
+    Some(config.join("gh").join("hosts.yml"))
+}
+
+fn dirs_home() -> Option<PathBuf> {
+    std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from)
+}
+
+/// Password of the `machine` entry for `host` (or `api.<host>`), else of
+/// the `default` entry.
+fn read_netrc(path: &Path, host: &str) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let api_host = format!("api.{}", host);
+
+    let mut entries: Vec<(Option<String>, Option<String>)> = Vec::new();
+    let mut tokens = content.split_whitespace();
+    while let Some(word) = tokens.next() {
+        match word {
+            "machine" => entries.push((tokens.next().map(str::to_string), None)),
+            "default" => entries.push((None, None)),
+            "password" => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.1 = tokens.next().map(str::to_string);
+                }
+            }
+            // `macdef` bodies run until a blank line, which whitespace
+            // splitting cannot see; gaji never needs what follows them
+            "macdef" => break,
+            _ => {}
+        }
+    }
+
+    let password = |wanted: Option<&str>| {
+        entries
+            .iter()
+            .find(|(machine, _)| machine.as_deref() == wanted)
+            .and_then(|(_, password)| password.clone())
+    };
+    password(Some(host))
+        .or_else(|| password(Some(&api_host)))
+        .or_else(|| password(None))
+}
+
+/// `oauth_token` of `host` in the gh CLI hosts file. Tokens gh keeps in the
+/// system keyring are not visible here.
+fn read_gh_hosts(path: &Path, host: &str) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let hosts: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
+    let entry = &hosts[host];
+
+    if let Some(token) = entry["oauth_token"].as_str() {
+        return Some(token.to_string());
+    }
+    let user = entry["user"].as_str()?;
+    entry["users"][user]["oauth_token"]
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Run the credential helper through the shell with `GAJI_HOST` set. The
+/// first line it prints is the token.
+fn run_helper(command: &str, host: &str) -> Option<String> {
+    let mut cache = HELPER_OUTPUT.lock().unwrap();
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(token) = cache.get(host) {
+        return token.clone();
+    }
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let token = match shell.arg(command).env("GAJI_HOST", host).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty()),
+        Ok(output) => {
+            eprintln!(
+                "Warning: credential helper failed for {} ({})",
+                host, output.status
+            );
+            None
+        }
+        Err(e) => {
+            eprintln!("Warning: could not run credential helper: {}", e);
+            None
+        }
+    };
+
+    cache.insert(host.to_string(), token.clone());
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(env: fn(&str) -> Option<String>) -> CredentialChain {
+        CredentialChain {
+            env,
+            host_tokens: HashMap::new(),
+            token: None,
+            netrc: None,
+            gh_hosts: None,
+            helper: None,
+        }
+    }
+
+    #[test]
+    fn test_chain_order() {
+        let mut chain = chain(|name| (name == "GITHUB_TOKEN").then(|| "env_token".to_string()));
+        chain.token = Some("config_token".to_string());
+        chain
+            .host_tokens
+            .insert("ghe.corp.com".to_string(), "host_token".to_string());
+
+        let resolved = chain.resolve("github.com", true).unwrap();
+        assert_eq!(resolved.token, "env_token");
+        assert_eq!(resolved.source, TokenSource::Env("GITHUB_TOKEN"));
+
+        // Environment tokens never go to other hosts
+        let resolved = chain.resolve("ghe.corp.com", false).unwrap();
+        assert_eq!(resolved.source, TokenSource::HostConfig);
+        assert!(chain.resolve("codeberg.org", false).is_none());
+
+        chain.env = |_| None;
+        let resolved = chain.resolve("github.com", true).unwrap();
+        assert_eq!(resolved.source, TokenSource::Config);
+    }
+
+    #[test]
+    fn test_netrc_and_gh_hosts() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let netrc = temp.path().join("netrc");
+        std::fs::write(
+            &netrc,
+            "machine codeberg.org login me password netrc_codeberg\n\
+             machine api.github.com\n  login me\n  password netrc_github\n",
+        )
+        .unwrap();
+        let gh_hosts = temp.path().join("hosts.yml");
+        std::fs::write(
+            &gh_hosts,
+            "github.com:\n  user: me\n  users:\n    me:\n      oauth_token: gho_from_gh\n\
+             ghe.corp.com:\n  oauth_token: gho_enterprise\n",
+        )
+        .unwrap();
+
+        let mut chain = chain(|_| None);
+        chain.netrc = Some(netrc.clone());
+        chain.gh_hosts = Some(gh_hosts.clone());
+
+        let resolved = chain.resolve("codeberg.org", false).unwrap();
+        assert_eq!(resolved.token, "netrc_codeberg");
+        assert_eq!(resolved.source, TokenSource::Netrc(netrc));
+        // `api.<host>` entries count for the host
+        assert_eq!(
+            chain.resolve("github.com", true).unwrap().token,
+            "netrc_github"
+        );
+        let resolved = chain.resolve("ghe.corp.com", false).unwrap();
+        assert_eq!(resolved.token, "gho_enterprise");
+        assert_eq!(resolved.source, TokenSource::GhCli(gh_hosts.clone()));
+
+        chain.netrc = None;
+        assert_eq!(
+            chain.resolve("github.com", true).unwrap().token,
+            "gho_from_gh"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_credential_helper() {
+        let mut chain = chain(|_| None);
+        chain.helper = Some("echo \"helper-$GAJI_HOST\"".to_string());
+        let resolved = chain.resolve("git.example.com", false).unwrap();
+        assert_eq!(resolved.token, "helper-git.example.com");
+        assert_eq!(resolved.source, TokenSource::Helper);
+    }
+
+    #[test]
+    fn test_redact() {
+        remember_secret("s3cret-value-123");
+        assert_eq!(
+            redact("token s3cret-value-123 rejected"),
+            "token *** rejected"
+        );
+        assert_eq!(
+            redact("Authorization: token ghp_abcDEF123_x, then github_pat_11AB."),
+            "Authorization: token ***, then ***."
+        );
+        assert_eq!(redact("ghp_ alone"), "ghp_ alone");
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of(None), "github.com");
+        assert_eq!(host_of(Some("https://ghe.corp.com/")), "ghe.corp.com");
+    }
+}
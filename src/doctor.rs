@@ -0,0 +1,504 @@
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cache::{self, Cache, LEGACY_CACHE_FILE};
+use crate::config::{Config, TOML_CONFIG_FILE, TS_CONFIG_FILE, TS_LOCAL_CONFIG_FILE};
+use crate::credentials::{redact, Credential};
+use crate::fetcher::GitHubFetcher;
+use crate::init::{detect_project_state, ProjectState};
+
+/// Share of the rate limit below which `doctor` warns.
+const LOW_RATE_LIMIT_RATIO: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Result of a single diagnostic.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub message: String,
+    /// What to do about a warning or error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Error,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// All diagnostics for a project, in the order they ran.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub version: &'static str,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn has_errors(&self) -> bool {
+        self.checks.iter().any(|c| c.status == Status::Error)
+    }
+
+    pub fn print(&self) {
+        for check in &self.checks {
+            let icon = match check.status {
+                Status::Ok => "✓".green(),
+                Status::Warning => "⚠️ ".yellow(),
+                Status::Error => "✗".red(),
+            };
+            println!("{} {}: {}", icon, check.name.bold(), check.message);
+            if let Some(fix) = &check.fix {
+                println!("   {} {}", "→".dimmed(), fix);
+            }
+        }
+
+        let count = |status| self.checks.iter().filter(|c| c.status == status).count();
+        println!(
+            "\n{} error(s), {} warning(s)",
+            count(Status::Error),
+            count(Status::Warning)
+        );
+    }
+}
+
+/// Run every check against the project at `root`. `network` enables the
+/// GitHub connectivity and rate-limit probe.
+pub async fn run(root: &Path, network: bool) -> Report {
+    let environment = Environment {
+        cache_path: cache::default_path(),
+        credential: Config::resolve_credential,
+    };
+    run_in(root, network, &environment).await
+}
+
+/// Where the checks look outside the project, so tests can replace the
+/// global cache and the credential chain.
+struct Environment {
+    cache_path: PathBuf,
+    credential: fn(&Config) -> Option<Credential>,
+}
+
+async fn run_in(root: &Path, network: bool, environment: &Environment) -> Report {
+    let mut checks = vec![check_project(root)];
+
+    let (config, config_check) = check_config(root);
+    checks.push(config_check);
+    if let Some(check) = check_legacy_config(root) {
+        checks.push(check);
+    }
+
+    let config = config.unwrap_or_default();
+    checks.push(check_workflows_dir(root, &config));
+    checks.push(check_runtime(root, &config));
+    checks.push(check_tsconfig(root, &config));
+    checks.push(check_cache(root, &config, &environment.cache_path));
+    checks.push(check_token(&config, (environment.credential)(&config)));
+    if network {
+        checks.push(check_github(&config, &environment.cache_path).await);
+    }
+
+    Report {
+        version: env!("CARGO_PKG_VERSION"),
+        checks,
+    }
+}
+
+fn check_project(root: &Path) -> Check {
+    match detect_project_state(root) {
+        Ok(ProjectState::Empty) => Check::warning(
+            "project",
+            "No project files found in this directory",
+            "Run 'gaji init' from your repository root",
+        ),
+        Ok(ProjectState::ExistingProject) => Check::ok("project", "Project detected"),
+        Ok(ProjectState::HasWorkflows) => {
+            Check::ok("project", "Project with GitHub workflows or actions")
+        }
+        Err(e) => Check::error(
+            "project",
+            format!("Could not inspect the project: {}", e),
+            "Check the directory permissions",
+        ),
+    }
+}
+
+fn check_config(root: &Path) -> (Option<Config>, Check) {
+    let has_ts = root.join(TS_CONFIG_FILE).exists();
+    let has_toml = root.join(TOML_CONFIG_FILE).exists();
+
+    match Config::load_in(root) {
+        Ok(config) if has_ts || has_toml => {
+            let file = if has_ts {
+                TS_CONFIG_FILE
+            } else {
+                TOML_CONFIG_FILE
+            };
+            (
+                Some(config),
+                Check::ok("config", format!("{} loaded", file)),
+            )
+        }
+        Ok(config) => (
+            Some(config),
+            Check::warning(
+                "config",
+                "No gaji.config.ts found, using defaults",
+                "Run 'gaji init' to create one",
+            ),
+        ),
+        Err(e) => (
+            None,
+            Check::error(
+                "config",
+                format!("Failed to load config: {:#}", e),
+                format!(
+                    "Fix {} (or {}); it must export defineConfig({{ ... }})",
+                    TS_CONFIG_FILE, TS_LOCAL_CONFIG_FILE
+                ),
+            ),
+        ),
+    }
+}
+
+fn check_legacy_config(root: &Path) -> Option<Check> {
+    if !root.join(TOML_CONFIG_FILE).exists() {
+        return None;
+    }
+
+    Some(if root.join(TS_CONFIG_FILE).exists() {
+        Check::warning(
+            "legacy-config",
+            ".gaji.toml is ignored because gaji.config.ts exists",
+            "Move any remaining settings into gaji.config.ts and delete .gaji.toml",
+        )
+    } else {
+        Check::warning(
+            "legacy-config",
+            ".gaji.toml is deprecated",
+            "Run 'gaji init' to migrate it to gaji.config.ts",
+        )
+    })
+}
+
+fn check_workflows_dir(root: &Path, config: &Config) -> Check {
+    let dir = &config.project.workflows_dir;
+    if root.join(dir).is_dir() {
+        Check::ok("workflows", format!("{}/ exists", dir))
+    } else {
+        Check::warning(
+            "workflows",
+            format!("Workflows directory {}/ does not exist", dir),
+            format!("Create {}/ or set 'workflows' in gaji.config.ts", dir),
+        )
+    }
+}
+
+fn check_runtime(root: &Path, config: &Config) -> Check {
+    let runtime = config.generated_path().join("index.js");
+    if root.join(&runtime).is_file() {
+        Check::ok("runtime", format!("{} exists", runtime.display()))
+    } else {
+        Check::error(
+            "runtime",
+            format!(
+                "{} is missing, so builds fall back to npx tsx",
+                runtime.display()
+            ),
+            "Run 'gaji dev' to generate it",
+        )
+    }
+}
+
+fn check_tsconfig(root: &Path, config: &Config) -> Check {
+    let path = root.join("tsconfig.json");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Check::warning(
+            "tsconfig",
+            "No tsconfig.json, so editors won't type-check workflows",
+            "Run 'gaji init' to create one",
+        );
+    };
+    let Ok(tsconfig) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Check::ok(
+            "tsconfig",
+            "tsconfig.json found (not plain JSON, not inspected)",
+        );
+    };
+
+    // Without "include" TypeScript picks up every file in the project
+    let Some(include) = tsconfig["include"].as_array() else {
+        return Check::ok("tsconfig", "tsconfig.json includes all files");
+    };
+
+    let missing: Vec<&str> = [
+        config.project.workflows_dir.as_str(),
+        config.project.generated_dir.as_str(),
+    ]
+    .into_iter()
+    .filter(|dir| {
+        !include.iter().filter_map(|i| i.as_str()).any(|pattern| {
+            let pattern = pattern.trim_start_matches("./");
+            pattern.starts_with("**") || pattern.split('/').next() == Some(*dir)
+        })
+    })
+    .collect();
+
+    if missing.is_empty() {
+        Check::ok(
+            "tsconfig",
+            "tsconfig.json includes workflows and generated types",
+        )
+    } else {
+        let patterns: Vec<String> = missing.iter().map(|d| format!("\"{}/**/*\"", d)).collect();
+        Check::warning(
+            "tsconfig",
+            format!("tsconfig.json does not include {}", missing.join(", ")),
+            format!(
+                "Add {} to \"include\" in tsconfig.json",
+                patterns.join(", ")
+            ),
+        )
+    }
+}
+
+fn check_cache(root: &Path, config: &Config, path: &Path) -> Check {
+    let ttl = config.build.cache_ttl_days;
+    let cache = match Cache::load_from(path.to_path_buf()) {
+        Ok(cache) => cache,
+        Err(e) => {
+            return Check::error(
+                "cache",
+                format!("Failed to read {}: {}", path.display(), e),
+                "Run 'gaji clean --cache'",
+            )
+        }
+    };
+
+    if root.join(LEGACY_CACHE_FILE).exists() {
+        return Check::warning(
+            "cache",
+            format!("{} is no longer used", LEGACY_CACHE_FILE),
+            format!("Delete it, the cache now lives in {}", path.display()),
+        );
+    }
+
+    let entries = cache.list();
+    let stale = entries
+        .iter()
+        .filter(|action_ref| cache.is_expired(action_ref, ttl))
+        .count();
+
+    if entries.is_empty() {
+        Check::ok("cache", "Cache is empty")
+    } else if stale > 0 {
+        Check::warning(
+            "cache",
+            format!(
+                "{} of {} cached action(s) are older than {} days",
+                stale,
+                entries.len(),
+                ttl
+            ),
+            "Run 'gaji dev' to refresh them, or 'gaji clean --cache' to start over",
+        )
+    } else {
+        Check::ok("cache", format!("{} cached action(s)", entries.len()))
+    }
+}
+
+fn check_token(config: &Config, credential: Option<Credential>) -> Check {
+    let host = config.github_host();
+    match credential {
+        Some(credential) => Check::ok(
+            "token",
+            format!("Using {} for {}", credential.source, host),
+        ),
+        None => Check::warning(
+            "token",
+            format!(
+                "No token for {}, so requests are limited to 60 per hour",
+                host
+            ),
+            format!(
+                "Set GH_TOKEN or GITHUB_TOKEN, run 'gh auth login', or set github.token in {} (keep it out of git)",
+                TS_LOCAL_CONFIG_FILE
+            ),
+        ),
+    }
+}
+
+async fn check_github(config: &Config, cache_path: &Path) -> Check {
+    let cache = match Cache::load_from(cache_path.to_path_buf()) {
+        Ok(cache) => cache,
+        Err(e) => {
+            return Check::error(
+                "github",
+                format!("Skipped, the cache could not be read: {}", e),
+                "Run 'gaji clean --cache'",
+            )
+        }
+    };
+    let fetcher = match GitHubFetcher::new(
+        cache,
+        config.resolve_token(),
+        config.resolve_api_url(),
+        config.build.cache_ttl_days,
+    )
+    .and_then(|fetcher| fetcher.with_network(&config.network))
+    {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            return Check::error(
+                "github",
+                format!("Skipped, {}", redact(&format!("{:#}", e))),
+                "Fix the `network` section in gaji.config.ts",
+            )
+        }
+    };
+    let host = config
+        .resolve_api_url()
+        .unwrap_or_else(|| "https://api.github.com".to_string());
+
+    match fetcher.rate_limit().await {
+        Ok(limit) if limit.remaining == 0 => Check::error(
+            "github",
+            format!(
+                "Rate limit exhausted ({} requests), resets at unix time {}",
+                limit.limit, limit.reset
+            ),
+            "Wait for the reset or set a GitHub token",
+        ),
+        Ok(limit) if (limit.remaining as f64) < limit.limit as f64 * LOW_RATE_LIMIT_RATIO => {
+            Check::warning(
+                "github",
+                format!(
+                    "{} of {} requests left on {}",
+                    limit.remaining, limit.limit, host
+                ),
+                "Set a GitHub token or wait for the rate limit to reset",
+            )
+        }
+        Ok(limit) => Check::ok(
+            "github",
+            format!(
+                "Reached {} ({} of {} requests left)",
+                host, limit.remaining, limit.limit
+            ),
+        ),
+        Err(e) => Check::error(
+            "github",
+            redact(&format!("{:#}", e)),
+            "Check your network, proxy and github.apiUrl, and that the token is valid",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty cache and no token, whatever the machine running the tests has.
+    fn isolated(temp: &tempfile::TempDir) -> Environment {
+        Environment {
+            cache_path: temp.path().join("cache").join("metadata.json"),
+            credential: |_| None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_doctor_new_project() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let report = run_in(temp.path(), false, &isolated(&temp)).await;
+
+        let status = |name| {
+            report
+                .checks
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.status)
+        };
+        assert_eq!(status("project"), Some(Status::Warning));
+        assert_eq!(status("config"), Some(Status::Warning));
+        assert_eq!(status("runtime"), Some(Status::Error));
+        assert_eq!(status("cache"), Some(Status::Ok));
+        assert_eq!(status("token"), Some(Status::Warning));
+        assert_eq!(status("github"), None);
+        assert!(report.has_errors());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["name"], "project");
+        assert_eq!(json["checks"][0]["status"], "warning");
+    }
+
+    #[tokio::test]
+    async fn test_doctor_broken_config_and_legacy_toml() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join(TS_CONFIG_FILE), "export default {{{").unwrap();
+        std::fs::write(temp.path().join(TOML_CONFIG_FILE), "").unwrap();
+
+        let report = run_in(temp.path(), false, &isolated(&temp)).await;
+        let config = report.checks.iter().find(|c| c.name == "config").unwrap();
+        assert_eq!(config.status, Status::Error);
+        let legacy = report
+            .checks
+            .iter()
+            .find(|c| c.name == "legacy-config")
+            .unwrap();
+        assert!(legacy.message.contains("ignored"));
+    }
+
+    #[test]
+    fn test_check_tsconfig_include() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = Config::default();
+
+        std::fs::write(
+            temp.path().join("tsconfig.json"),
+            r#"{"include": ["workflows/**/*"]}"#,
+        )
+        .unwrap();
+        let check = check_tsconfig(temp.path(), &config);
+        assert_eq!(check.status, Status::Warning);
+        assert!(check.fix.unwrap().contains("\"generated/**/*\""));
+
+        std::fs::write(
+            temp.path().join("tsconfig.json"),
+            r#"{"include": ["workflows/**/*", "./generated/**/*"]}"#,
+        )
+        .unwrap();
+        assert_eq!(check_tsconfig(temp.path(), &config).status, Status::Ok);
+    }
+}
@@ -1,9 +1,11 @@
 use std::cell::RefCell;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use oxc_allocator::Allocator;
+use oxc_ast::ast::{Declaration, Expression, ImportDeclarationSpecifier, Statement};
 use oxc_codegen::Codegen;
 use oxc_parser::Parser;
 use oxc_semantic::SemanticBuilder;
@@ -66,9 +68,12 @@ pub fn execute_workflow(workflow_path: &Path, runtime_js_path: &Path) -> Result<
     let workflow_js = strip_typescript(&workflow_source, &filename)?;
 
     // Remove import/export statements from both sources for QuickJS script mode
-    // (QuickJS eval runs in script mode, not ES module mode)
+    // (QuickJS eval runs in script mode, not ES module mode). Local modules the
+    // workflow imports are inlined ahead of it.
     let runtime_js = remove_imports(&runtime_js);
-    let workflow_js = remove_imports(&workflow_js);
+    let workflow_js = bundle_local_modules(workflow_path, &workflow_js, &|path| {
+        std::fs::read_to_string(path).ok()
+    })?;
 
     // Bundle: runtime first, then workflow code
     let bundled = format!("{}\n\n{}", runtime_js, workflow_js);
@@ -108,6 +113,273 @@ pub fn remove_imports(source: &str) -> String {
     result.join("\n")
 }
 
+/// A relative import such as `import { a, b as c } from "./shared.js"`.
+struct LocalImport {
+    spec: String,
+    /// `(imported, local)` name pairs.
+    bindings: Vec<(String, String)>,
+    /// Local name of `import * as ns`.
+    namespace: Option<String>,
+}
+
+/// Relative imports of `source`, read from its import declarations so
+/// imports spanning several lines are found too. Type-only imports are skipped.
+fn local_imports(source: &str) -> Vec<LocalImport> {
+    let allocator = Allocator::default();
+    let program = Parser::new(&allocator, source, SourceType::ts())
+        .parse()
+        .program;
+
+    let mut imports = Vec::new();
+    for stmt in &program.body {
+        let Statement::ImportDeclaration(decl) = stmt else {
+            continue;
+        };
+        let spec = decl.source.value.as_str();
+        if decl.import_kind.is_type() || !(spec.starts_with("./") || spec.starts_with("../")) {
+            continue;
+        }
+
+        let mut import = LocalImport {
+            spec: spec.to_string(),
+            bindings: Vec::new(),
+            namespace: None,
+        };
+        for specifier in decl.specifiers.iter().flatten() {
+            match specifier {
+                ImportDeclarationSpecifier::ImportSpecifier(named)
+                    if !named.import_kind.is_type() =>
+                {
+                    import.bindings.push((
+                        named.imported.name().to_string(),
+                        named.local.name.to_string(),
+                    ));
+                }
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(ns) => {
+                    import.namespace = Some(ns.local.name.to_string());
+                }
+                _ => {}
+            }
+        }
+        imports.push(import);
+    }
+    imports
+}
+
+/// Resolve a module specifier against the importing file to a TypeScript
+/// source: `./shared.js`, `./shared.ts` and `./shared` all map to
+/// `shared.ts`. Specifiers without a `.ts` source (such as the generated
+/// runtime) do not resolve.
+fn resolve_module(importer: &Path, spec: &str, exists: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    let base = normalize_path(&importer.parent().unwrap_or(Path::new("")).join(spec));
+    let candidates = match base.extension().and_then(|e| e.to_str()) {
+        Some("ts") => vec![base.clone()],
+        Some("js") | Some("mjs") => vec![base.with_extension("ts")],
+        _ => {
+            let mut with_ext = base.clone().into_os_string();
+            with_ext.push(".ts");
+            vec![PathBuf::from(with_ext), base.join("index.ts")]
+        }
+    };
+    candidates.into_iter().find(|path| exists(path))
+}
+
+/// Lexically remove `.` and `..` components so the same file always maps to
+/// the same path.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Local `.ts` modules imported by `source`, resolved relative to `path`.
+/// Imports that do not resolve to an existing file are skipped.
+pub fn local_module_paths(path: &Path, source: &str) -> Vec<PathBuf> {
+    local_imports(source)
+        .into_iter()
+        .filter_map(|import| resolve_module(path, &import.spec, |p| p.is_file()))
+        .collect()
+}
+
+/// Whether `source` calls `.build(...)` at its top level, i.e. defines a
+/// workflow or action of its own rather than only helpers for others.
+pub fn calls_build(source: &str) -> bool {
+    let allocator = Allocator::default();
+    let program = Parser::new(&allocator, source, SourceType::ts())
+        .parse()
+        .program;
+
+    program.body.iter().any(|stmt| {
+        let Statement::ExpressionStatement(stmt) = stmt else {
+            return false;
+        };
+        let mut expr = stmt.expression.get_inner_expression();
+        if let Expression::AwaitExpression(await_expr) = expr {
+            expr = await_expr.argument.get_inner_expression();
+        }
+        matches!(
+            expr,
+            Expression::CallExpression(call)
+                if matches!(&call.callee, Expression::StaticMemberExpression(member) if member.property.name == "build")
+        )
+    })
+}
+
+/// Inline the local modules a workflow imports ahead of its code.
+///
+/// Each module runs in its own function scope so its top-level names (such
+/// as its own `getAction` constants) do not clash with the workflow's; the
+/// imported names are bound from the module's exports. `read` returns the
+/// TypeScript source of a module path, or `None` when it does not exist.
+pub fn bundle_local_modules(
+    entry: &Path,
+    entry_js: &str,
+    read: &dyn Fn(&Path) -> Option<String>,
+) -> Result<String> {
+    let mut bundler = ModuleBundler {
+        read,
+        modules: HashMap::new(),
+        loading: HashSet::new(),
+        output: String::new(),
+    };
+    let bindings = bundler.link(entry, entry_js)?;
+    Ok(format!(
+        "{}{}{}",
+        bundler.output,
+        bindings,
+        remove_imports(entry_js)
+    ))
+}
+
+struct ModuleBundler<'a> {
+    read: &'a dyn Fn(&Path) -> Option<String>,
+    /// Loaded modules and the variable holding their exports.
+    modules: HashMap<PathBuf, String>,
+    loading: HashSet<PathBuf>,
+    output: String,
+}
+
+impl ModuleBundler<'_> {
+    /// Load the local imports of `importer` and return the statements that
+    /// bind the imported names.
+    fn link(&mut self, importer: &Path, js: &str) -> Result<String> {
+        let mut bindings = String::new();
+        for import in local_imports(js) {
+            // Anything else comes from the runtime, which is already inlined
+            let Some(path) = resolve_module(importer, &import.spec, |p| (self.read)(p).is_some())
+            else {
+                continue;
+            };
+            let var = self.load(&path)?;
+
+            if let Some(ns) = &import.namespace {
+                bindings.push_str(&format!("const {} = {};\n", ns, var));
+            }
+            if !import.bindings.is_empty() {
+                let names: Vec<String> = import
+                    .bindings
+                    .iter()
+                    .map(|(imported, local)| {
+                        if imported == local {
+                            imported.clone()
+                        } else {
+                            format!("{}: {}", imported, local)
+                        }
+                    })
+                    .collect();
+                bindings.push_str(&format!("const {{ {} }} = {};\n", names.join(", "), var));
+            }
+        }
+        Ok(bindings)
+    }
+
+    fn load(&mut self, path: &Path) -> Result<String> {
+        if let Some(var) = self.modules.get(path) {
+            return Ok(var.clone());
+        }
+        if !self.loading.insert(path.to_path_buf()) {
+            bail!("Circular import of {}", path.display());
+        }
+
+        let source = (self.read)(path)
+            .ok_or_else(|| anyhow!("Failed to read module: {}", path.display()))?;
+        let filename = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let js = strip_typescript(&source, &filename)?;
+        let bindings = self.link(path, &js)?;
+
+        let exports: Vec<String> = module_exports(&js)
+            .into_iter()
+            .map(|(exported, local)| format!("{}: {}", exported, local))
+            .collect();
+        let var = format!("__gaji_module_{}", self.modules.len());
+        self.output.push_str(&format!(
+            "var {} = (function () {{\n{}{}\nreturn {{ {} }};\n}})();\n\n",
+            var,
+            bindings,
+            remove_imports(&js),
+            exports.join(", ")
+        ));
+
+        self.loading.remove(path);
+        self.modules.insert(path.to_path_buf(), var.clone());
+        Ok(var)
+    }
+}
+
+/// Top-level `(exported, local)` names of a module.
+fn module_exports(js: &str) -> Vec<(String, String)> {
+    let allocator = Allocator::default();
+    let program = Parser::new(&allocator, js, SourceType::mjs())
+        .parse()
+        .program;
+
+    let mut exports = Vec::new();
+    for stmt in &program.body {
+        // Re-exports from other modules are not bound in this one
+        let Statement::ExportNamedDeclaration(export) = stmt else {
+            continue;
+        };
+        if export.source.is_some() {
+            continue;
+        }
+
+        match &export.declaration {
+            Some(Declaration::VariableDeclaration(var)) => {
+                for declarator in &var.declarations {
+                    for id in declarator.id.get_binding_identifiers() {
+                        exports.push((id.name.to_string(), id.name.to_string()));
+                    }
+                }
+            }
+            Some(decl) => {
+                if let Some(id) = decl.id() {
+                    exports.push((id.name.to_string(), id.name.to_string()));
+                }
+            }
+            None => {}
+        }
+        for specifier in &export.specifiers {
+            exports.push((
+                specifier.exported.name().to_string(),
+                specifier.local.name().to_string(),
+            ));
+        }
+    }
+    exports
+}
+
 /// Register __gha_build host function and evaluate JavaScript with QuickJS.
 /// Uses Rc/RefCell pattern to capture build outputs from JS callbacks.
 pub fn execute_js(code: &str) -> Result<Vec<BuildOutput>> {
@@ -365,4 +637,165 @@ wf.build("typed-wf");
         assert_eq!(json["name"], "Typed");
         assert_eq!(json["jobs"]["job1"]["steps"][0]["run"], "echo hi");
     }
+
+    /// getWorkflow() → WorkflowCall with outputs registered in the JobBuilder context
+    #[test]
+    fn test_get_workflow_outputs_pipeline() {
+        use crate::generator::templates::{
+            GET_WORKFLOW_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE,
+        };
+
+        let runtime_js = format!(
+            "var __workflow_outputs = {{ 'org/repo/.github/workflows/deploy.yml@v1': ['url'] }};\n{}\n{}",
+            GET_WORKFLOW_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE
+        );
+
+        let workflow_js = r#"
+const deploy = getWorkflow("org/repo/.github/workflows/deploy.yml@v1");
+
+new Workflow({
+    name: "Release",
+    on: { push: {} },
+}).jobs(j => j
+    .add("deploy", deploy({ with: { environment: "prod" }, secrets: "inherit" }))
+    .add("notify", output =>
+        new Job("ubuntu-latest", { needs: ["deploy"] })
+            .steps(s => s.add({ run: "echo " + output.deploy.url }))
+    )
+).build("release");
+"#;
+
+        let bundled = format!("{}\n\n{}", remove_imports(&runtime_js), workflow_js);
+
+        let outputs = execute_js(&bundled).unwrap();
+        assert_eq!(outputs.len(), 1);
+
+        let json: serde_json::Value = serde_json::from_str(&outputs[0].json).unwrap();
+        let deploy = &json["jobs"]["deploy"];
+        assert_eq!(deploy["uses"], "org/repo/.github/workflows/deploy.yml@v1");
+        assert_eq!(deploy["with"]["environment"], "prod");
+        assert_eq!(deploy["secrets"], "inherit");
+        assert!(deploy.get("_outputs").is_none());
+        assert_eq!(
+            json["jobs"]["notify"]["steps"][0]["run"],
+            "echo ${{ needs.deploy.outputs.url }}"
+        );
+    }
+
+    /// new WorkflowCall() on a locally built workflow also exposes its outputs
+    #[test]
+    fn test_local_workflow_call_outputs() {
+        use crate::generator::templates::JOB_WORKFLOW_RUNTIME_TEMPLATE;
+
+        let runtime_js = format!(
+            "var __workflow_outputs = {{ './.github/workflows/deploy.yml': ['url'] }};\n{}",
+            JOB_WORKFLOW_RUNTIME_TEMPLATE
+        );
+
+        let workflow_js = r#"
+new Workflow({
+    name: "Release",
+    on: { push: {} },
+}).jobs(j => j
+    .add("deploy", new WorkflowCall("./.github/workflows/deploy.yml", { with: { environment: "prod" } }))
+    .add("notify", output =>
+        new Job("ubuntu-latest", { needs: ["deploy"] })
+            .steps(s => s.add({ run: "echo " + output.deploy.url }))
+    )
+).build("release");
+"#;
+
+        let bundled = format!("{}\n\n{}", remove_imports(&runtime_js), workflow_js);
+
+        let outputs = execute_js(&bundled).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&outputs[0].json).unwrap();
+        assert_eq!(
+            json["jobs"]["deploy"]["uses"],
+            "./.github/workflows/deploy.yml"
+        );
+        assert_eq!(
+            json["jobs"]["notify"]["steps"][0]["run"],
+            "echo ${{ needs.deploy.outputs.url }}"
+        );
+    }
+
+    #[test]
+    fn test_bundle_local_modules() {
+        use std::collections::HashMap;
+
+        let modules: HashMap<PathBuf, String> = HashMap::from([(
+            PathBuf::from("workflows/shared.ts"),
+            r#"
+const prefix: string = "shared";
+export function steps(name: string): string[] {
+    return [prefix + ":" + name];
+}
+"#
+            .to_string(),
+        )]);
+        let workflow_js = r#"import { getAction } from "../generated/index.js";
+import { steps as sharedSteps } from "./shared.js";
+const prefix = "workflow";
+__gha_build(prefix, JSON.stringify(sharedSteps("a")), "workflow");
+"#;
+
+        let bundled = bundle_local_modules(Path::new("workflows/ci.ts"), workflow_js, &|path| {
+            modules.get(path).cloned()
+        })
+        .unwrap();
+        let outputs = execute_js(&bundled).unwrap();
+        assert_eq!(outputs[0].id, "workflow");
+        assert_eq!(outputs[0].json, r#"["shared:a"]"#);
+
+        assert_eq!(
+            normalize_path(Path::new("./workflows/../workflows/./shared.ts")),
+            PathBuf::from("workflows/shared.ts")
+        );
+    }
+
+    #[test]
+    fn test_bundle_multiline_imports_and_exports() {
+        use std::collections::HashMap;
+
+        let modules: HashMap<PathBuf, String> = HashMap::from([(
+            PathBuf::from("workflows/shared.ts"),
+            r#"
+const first = "a";
+function second(): string {
+    return "b";
+}
+export {
+    first,
+    second as other,
+};
+export const [third] = ["c"];
+"#
+            .to_string(),
+        )]);
+        let workflow_ts = r#"import {
+    first,
+    other,
+    third,
+} from "./shared";
+import type {
+    Unused,
+} from "./types";
+__gha_build("workflow", JSON.stringify([first, other(), third]), "workflow");
+"#;
+        let entry = Path::new("workflows/ci.ts");
+        let exists = |path: &Path| modules.contains_key(path);
+        assert_eq!(
+            local_imports(workflow_ts)
+                .iter()
+                .filter_map(|import| resolve_module(entry, &import.spec, exists))
+                .collect::<Vec<_>>(),
+            vec![PathBuf::from("workflows/shared.ts")]
+        );
+
+        let workflow_js = strip_typescript(workflow_ts, "ci.ts").unwrap();
+        let bundled =
+            bundle_local_modules(entry, &workflow_js, &|path| modules.get(path).cloned()).unwrap();
+        let outputs = execute_js(&bundled).unwrap();
+        assert_eq!(outputs[0].json, r#"["a","b","c"]"#);
+    }
 }
@@ -1,10 +1,16 @@
 use anyhow::{Context, Result};
 use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::cache::Cache;
+use crate::cache::{is_commit_sha, Cache};
+use crate::config::NetworkConfig;
+use crate::source::{
+    encode_component, encode_path, http_client, http_get, GitHubSource, MetadataSource,
+    SourceRouter,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionMetadata {
@@ -41,6 +47,271 @@ pub struct ActionRuns {
     pub args: Option<Vec<String>>,
 }
 
+/// The `on.workflow_call` interface of a reusable workflow.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WorkflowCallMetadata {
+    pub name: Option<String>,
+    pub inputs: BTreeMap<String, WorkflowCallInput>,
+    pub secrets: BTreeMap<String, WorkflowCallSecret>,
+    pub outputs: BTreeMap<String, WorkflowCallOutput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowCallInput {
+    pub description: Option<String>,
+    pub required: Option<bool>,
+    pub default: Option<String>,
+    /// `string`, `boolean` or `number`
+    #[serde(rename = "type")]
+    pub input_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowCallSecret {
+    pub description: Option<String>,
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowCallOutput {
+    pub description: Option<String>,
+    pub value: Option<String>,
+}
+
+impl WorkflowCallMetadata {
+    /// Parse the `on.workflow_call` section of a workflow YAML file.
+    pub fn from_yaml(yaml_content: &str) -> Result<Self> {
+        let workflow: serde_yaml::Value =
+            serde_yaml::from_str(yaml_content).context("Failed to parse workflow YAML")?;
+
+        let name = workflow
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        // YAML 1.1 parsers may turn a bare `on:` key into boolean true
+        let on = workflow
+            .get("on")
+            .or_else(|| {
+                workflow
+                    .as_mapping()
+                    .and_then(|map| map.get(serde_yaml::Value::Bool(true)))
+            })
+            .ok_or_else(|| anyhow::anyhow!("Workflow has no 'on' triggers"))?;
+
+        let declares_call = match on {
+            serde_yaml::Value::String(s) => s == "workflow_call",
+            serde_yaml::Value::Sequence(seq) => {
+                seq.iter().any(|v| v.as_str() == Some("workflow_call"))
+            }
+            serde_yaml::Value::Mapping(map) => map.contains_key("workflow_call"),
+            _ => false,
+        };
+        if !declares_call {
+            return Err(anyhow::anyhow!(
+                "Workflow is not reusable: missing 'on.workflow_call'"
+            ));
+        }
+
+        let mut metadata = WorkflowCallMetadata {
+            name,
+            ..Default::default()
+        };
+
+        let Some(call) = on.get("workflow_call") else {
+            return Ok(metadata);
+        };
+
+        if let Some(inputs) = call.get("inputs").and_then(|v| v.as_mapping()) {
+            for (key, input) in inputs {
+                let Some(key) = key.as_str() else { continue };
+                metadata.inputs.insert(
+                    key.to_string(),
+                    WorkflowCallInput {
+                        description: yaml_str(input, "description"),
+                        required: input.get("required").and_then(|v| v.as_bool()),
+                        default: input.get("default").and_then(yaml_scalar_to_string),
+                        input_type: yaml_str(input, "type"),
+                    },
+                );
+            }
+        }
+
+        if let Some(secrets) = call.get("secrets").and_then(|v| v.as_mapping()) {
+            for (key, secret) in secrets {
+                let Some(key) = key.as_str() else { continue };
+                metadata.secrets.insert(
+                    key.to_string(),
+                    WorkflowCallSecret {
+                        description: yaml_str(secret, "description"),
+                        required: secret.get("required").and_then(|v| v.as_bool()),
+                    },
+                );
+            }
+        }
+
+        if let Some(outputs) = call.get("outputs").and_then(|v| v.as_mapping()) {
+            for (key, output) in outputs {
+                let Some(key) = key.as_str() else { continue };
+                metadata.outputs.insert(
+                    key.to_string(),
+                    WorkflowCallOutput {
+                        description: yaml_str(output, "description"),
+                        value: yaml_str(output, "value"),
+                    },
+                );
+            }
+        }
+
+        Ok(metadata)
+    }
+}
+
+fn yaml_str(value: &serde_yaml::Value, key: &str) -> Option<String> {
+    value.get(key).and_then(yaml_scalar_to_string)
+}
+
+fn yaml_scalar_to_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a ref points at a reusable workflow file
+/// (`owner/repo/.github/workflows/deploy.yml@ref`) rather than an action.
+pub fn is_reusable_workflow_ref(reference: &str) -> bool {
+    let Some((path, _)) = reference.split_once('@') else {
+        return false;
+    };
+    path.contains(".github/workflows/") && (path.ends_with(".yml") || path.ends_with(".yaml"))
+}
+
+/// Owners and repositories: letters, digits, `-`, `_` and `.`, but not `.`
+/// or `..` on their own.
+fn is_repo_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// A directory or file name within a repository.
+fn is_path_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && !segment
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == '\\')
+}
+
+/// Whether `name` is a valid git ref name, following `git check-ref-format`.
+fn is_git_ref(name: &str) -> bool {
+    !name.is_empty()
+        && name != "@"
+        && !name.contains("..")
+        && !name.contains("@{")
+        && !name.ends_with('.')
+        && !name
+            .chars()
+            .any(|c| c.is_ascii_control() || c.is_whitespace() || "~^:?*[\\".contains(c))
+        && name
+            .split('/')
+            .all(|part| !part.is_empty() && !part.starts_with('.') && !part.ends_with(".lock"))
+}
+
+/// Whether a `getAction`/`getWorkflow` argument refers to something in the
+/// project or a Docker image rather than to a repository.
+pub fn is_local_ref(reference: &str) -> bool {
+    reference.starts_with("./")
+        || reference.starts_with("../")
+        || reference.starts_with("docker://")
+}
+
+/// Directory of action definitions vendored by `gaji vendor`, relative to
+/// the project root.
+pub const VENDOR_DIR: &str = ".gaji/actions";
+
+/// Where `gaji vendor` stores `reference`: `owner/repo[/path]@ref/action.yml`,
+/// or `workflow.yml` for reusable workflows.
+pub fn vendored_path(vendor_dir: &Path, reference: &str) -> PathBuf {
+    let file = if is_reusable_workflow_ref(reference) {
+        "workflow.yml"
+    } else {
+        "action.yml"
+    };
+    vendor_dir.join(reference).join(file)
+}
+
+/// Core API rate limit as reported by `/rate_limit`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// Unix time when the limit resets.
+    pub reset: u64,
+}
+
+/// Why fetching a definition failed, with what to do about it on the second
+/// line of the message.
+#[derive(Debug, Clone, thiserror::Error, Serialize, Deserialize)]
+pub enum FetchError {
+    #[error("Ref '{ref_}' not found in {repo} ({host})\nCheck that the tag, branch or commit exists. Private repositories also answer 404 to requests without a token that can read them")]
+    RefNotFound {
+        repo: String,
+        ref_: String,
+        host: String,
+    },
+    #[error(
+        "No {file} in {reference} ({host})\nCheck the path of the action within the repository"
+    )]
+    FileNotFound {
+        reference: String,
+        file: String,
+        host: String,
+    },
+    #[error("Access denied (HTTP {status}) for {url}\nCheck that the token for this host is valid and can read the repository. Run 'gaji doctor' to see where it comes from")]
+    Auth { url: String, status: u16 },
+    #[error("Rate limited by {url}{}\nSet GITHUB_TOKEN to raise the limit, or use --offline with vendored or cached metadata", format_retry_in(.retry_in))]
+    RateLimited {
+        url: String,
+        /// Seconds until the limit resets, if the host said.
+        retry_in: Option<u64>,
+    },
+    #[error("Network error for {url}: {message}\nCheck your connection and proxy settings, or use --offline with vendored or cached metadata")]
+    Network { url: String, message: String },
+    #[error("HTTP {status} from {url}")]
+    Http { url: String, status: u16 },
+    #[error("Offline mode: {what} is not vendored or cached, and the network is disabled")]
+    Offline { what: String },
+}
+
+impl FetchError {
+    /// Whether the ref or file does not exist, as opposed to a failure that
+    /// may go away on retry.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::RefNotFound { .. } | Self::FileNotFound { .. })
+    }
+
+    /// Whether the network was needed in offline mode.
+    pub fn is_offline(&self) -> bool {
+        matches!(self, Self::Offline { .. })
+    }
+}
+
+fn format_retry_in(retry_in: &Option<u64>) -> String {
+    match retry_in {
+        Some(secs) if *secs >= 60 => format!(", resets in {} min", secs.div_ceil(60)),
+        Some(secs) => format!(", resets in {}s", secs),
+        None => String::new(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ActionRef {
     pub owner: String,
@@ -49,119 +320,97 @@ pub struct ActionRef {
     pub ref_: String,
 }
 
+impl std::fmt::Display for ActionRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.repo)?;
+        if let Some(path) = &self.path {
+            write!(f, "/{}", path)?;
+        }
+        write!(f, "@{}", self.ref_)
+    }
+}
+
 impl ActionRef {
     pub fn parse(action_ref: &str) -> Result<Self> {
         // Parse formats like:
         // - actions/checkout@v5
         // - owner/repo@tag
         // - owner/repo/path@ref
+        // - owner/repo@feature/x
+        //
+        // Paths cannot contain `@`, so the ref is everything after the first
+        // one and may contain `/` like any branch name.
 
-        let parts: Vec<&str> = action_ref.splitn(2, '@').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!(
-                "Invalid action reference format: {}. Expected format: owner/repo@ref",
-                action_ref
-            ));
-        }
-
-        let path_parts: Vec<&str> = parts[0].split('/').collect();
-        if path_parts.len() < 2 {
-            return Err(anyhow::anyhow!(
-                "Invalid action reference: {}. Expected at least owner/repo",
-                action_ref
-            ));
-        }
-
-        let owner = path_parts[0].to_string();
-        let repo = path_parts[1].to_string();
-        let path = if path_parts.len() > 2 {
-            Some(path_parts[2..].join("/"))
-        } else {
-            None
+        let invalid = |reason: &str| {
+            anyhow::anyhow!(
+                "Invalid action reference '{}': {}. Expected owner/repo[/path]@ref",
+                action_ref,
+                reason
+            )
         };
-        let ref_ = parts[1].to_string();
 
-        Ok(Self {
-            owner,
-            repo,
-            path,
-            ref_,
-        })
-    }
-
-    pub fn to_raw_url(&self) -> String {
-        self.to_raw_url_with_base(None)
-    }
-
-    pub fn to_raw_url_yaml(&self) -> String {
-        self.to_raw_url_yaml_with_base(None)
-    }
+        let Some((location, ref_)) = action_ref.split_once('@') else {
+            return Err(invalid("missing @ref"));
+        };
 
-    pub fn to_raw_url_with_base(&self, api_url: Option<&str>) -> String {
-        let base_path = if let Some(path) = &self.path {
-            format!("{}/{}/{}", self.owner, self.repo, path)
-        } else {
-            format!("{}/{}", self.owner, self.repo)
+        let mut segments = location.split('/');
+        let owner = segments.next().unwrap_or_default();
+        let Some(repo) = segments.next() else {
+            return Err(invalid("expected at least owner/repo"));
         };
+        let path: Vec<&str> = segments.collect();
 
-        match api_url {
-            Some(base) => {
-                let base = base.trim_end_matches('/');
-                format!(
-                    "{}/api/v3/repos/{}/{}/contents/{}action.yml?ref={}",
-                    base,
-                    self.owner,
-                    self.repo,
-                    self.path
-                        .as_ref()
-                        .map(|p| format!("{}/", p))
-                        .unwrap_or_default(),
-                    self.ref_
-                )
-            }
-            None => format!(
-                "https://raw.githubusercontent.com/{}/{}/action.yml",
-                base_path, self.ref_
-            ),
+        if !is_repo_name(owner) {
+            return Err(invalid(&format!("'{}' is not a valid owner", owner)));
+        }
+        if !is_repo_name(repo) {
+            return Err(invalid(&format!("'{}' is not a valid repository", repo)));
+        }
+        if let Some(segment) = path.iter().find(|s| !is_path_segment(s)) {
+            return Err(invalid(&format!(
+                "'{}' is not a valid path segment",
+                segment
+            )));
         }
+        if !is_git_ref(ref_) {
+            return Err(invalid(&format!("'{}' is not a valid git ref", ref_)));
+        }
+
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: (!path.is_empty()).then(|| path.join("/")),
+            ref_: ref_.to_string(),
+        })
     }
 
-    pub fn to_raw_url_yaml_with_base(&self, api_url: Option<&str>) -> String {
-        let base_path = if let Some(path) = &self.path {
-            format!("{}/{}/{}", self.owner, self.repo, path)
-        } else {
-            format!("{}/{}", self.owner, self.repo)
+    /// Contents API URL of `path` in this repository, which lists directories
+    /// and serves files.
+    pub fn to_contents_url_with_base(&self, path: &str, api_url: Option<&str>) -> String {
+        let base = match api_url {
+            Some(base) => format!("{}/api/v3", base.trim_end_matches('/')),
+            None => "https://api.github.com".to_string(),
         };
-
-        match api_url {
-            Some(base) => {
-                let base = base.trim_end_matches('/');
-                format!(
-                    "{}/api/v3/repos/{}/{}/contents/{}action.yaml?ref={}",
-                    base,
-                    self.owner,
-                    self.repo,
-                    self.path
-                        .as_ref()
-                        .map(|p| format!("{}/", p))
-                        .unwrap_or_default(),
-                    self.ref_
-                )
-            }
-            None => format!(
-                "https://raw.githubusercontent.com/{}/{}/action.yaml",
-                base_path, self.ref_
-            ),
-        }
+        format!(
+            "{}/repos/{}/{}/contents/{}?ref={}",
+            base,
+            self.owner,
+            self.repo,
+            encode_path(path.trim_matches('/')),
+            encode_component(&self.ref_)
+        )
     }
 }
 
 pub struct GitHubFetcher {
     client: reqwest::Client,
     cache: Cache,
+    sources: SourceRouter,
     token: Option<String>,
     api_url: Option<String>,
     cache_ttl_days: u64,
+    vendor_dir: PathBuf,
+    offline: bool,
 }
 
 impl GitHubFetcher {
@@ -170,37 +419,124 @@ impl GitHubFetcher {
         token: Option<String>,
         api_url: Option<String>,
         cache_ttl_days: u64,
-    ) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent("gaji")
-            .build()
-            .expect("Failed to create HTTP client");
-
-        Self {
+    ) -> Result<Self> {
+        let client = http_client(&NetworkConfig::default())?;
+        let default = GitHubSource::from_api_url(client.clone(), api_url.as_deref(), token.clone());
+
+        Ok(Self {
             client,
             cache,
+            sources: SourceRouter::new(Arc::new(default)),
             token,
             api_url,
             cache_ttl_days,
-        }
+            vendor_dir: PathBuf::from(VENDOR_DIR),
+            offline: false,
+        })
+    }
+
+    /// Fetch with the proxy, certificates and timeouts of `network`. This
+    /// replaces the default source, so call it before `with_sources`.
+    pub fn with_network(mut self, network: &NetworkConfig) -> Result<Self> {
+        self.client = http_client(network)?;
+        let default = GitHubSource::from_api_url(
+            self.client.clone(),
+            self.api_url.as_deref(),
+            self.token.clone(),
+        );
+        self.sources = SourceRouter::new(Arc::new(default));
+        Ok(self)
+    }
+
+    /// Fetch refs from the sources of `router` instead of the GitHub host
+    /// given to `new`.
+    pub fn with_sources(mut self, router: SourceRouter) -> Self {
+        self.sources = router;
+        self
+    }
+
+    /// Read vendored definitions from `dir` instead of `.gaji/actions`.
+    pub fn with_vendor_dir(mut self, dir: PathBuf) -> Self {
+        self.vendor_dir = dir;
+        self
+    }
+
+    /// Never access the network. Refs that are neither vendored nor cached
+    /// fail, and cached entries are used regardless of their age.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
     }
 
     pub async fn fetch_action_metadata(&self, action_ref_str: &str) -> Result<ActionMetadata> {
+        // Vendored definitions are committed with the project and win
+        if let Some(yaml_content) = self.read_vendored(action_ref_str)? {
+            return serde_yaml::from_str(&yaml_content).with_context(|| {
+                format!("Failed to parse vendored action.yml for {}", action_ref_str)
+            });
+        }
+
         // Check cache first (with TTL expiration)
-        if !self.cache.is_expired(action_ref_str, self.cache_ttl_days) {
+        if self.offline || !self.cache.is_expired(action_ref_str, self.cache_ttl_days) {
             if let Some(cached) = self.cache.get(action_ref_str) {
                 return Ok(cached);
             }
         }
 
-        let action_ref = ActionRef::parse(action_ref_str)?;
-        let yaml_content = self.fetch_action_yaml(&action_ref).await?;
+        if let Some(error) = self.cache.get_miss(action_ref_str) {
+            return Err(error.into());
+        }
+
+        let result = self.download_action_metadata(action_ref_str, false).await;
+        self.remember_miss(action_ref_str, result)
+    }
+
+    /// Fetch a cached ref again, ignoring the TTL and any entry already
+    /// cached for its commit.
+    pub async fn refresh(&self, reference: &str) -> Result<()> {
+        if is_reusable_workflow_ref(reference) {
+            let result = self.download_workflow_metadata(reference, true).await;
+            self.remember_miss(reference, result)?;
+        } else {
+            let result = self.download_action_metadata(reference, true).await;
+            self.remember_miss(reference, result)?;
+        }
+        Ok(())
+    }
+
+    async fn download_action_metadata(
+        &self,
+        action_ref_str: &str,
+        force: bool,
+    ) -> Result<ActionMetadata> {
+        // Entries are keyed by commit, so a ref that still points to a cached
+        // commit only needs its resolution refreshed
+        let mut action_ref = ActionRef::parse(action_ref_str)?;
+        let source = self.source_for(&action_ref)?;
+        let sha = resolve_commit_sha(source, &action_ref).await?;
+        if let Some(sha) = &sha {
+            if !force {
+                if let Some(cached) = self.cache.get_pinned(action_ref_str, sha) {
+                    self.cache.record_ref(action_ref_str, sha)?;
+                    return Ok(cached);
+                }
+            }
+            action_ref.ref_ = sha.clone();
+        }
+
+        let yaml_content = fetch_action_yaml(source, &action_ref)
+            .await?
+            .ok_or_else(|| action_not_found(action_ref_str, source))?;
         let metadata: ActionMetadata = serde_yaml::from_str(&yaml_content)
             .with_context(|| format!("Failed to parse action.yml for {}", action_ref_str))?;
 
         // Store in cache
-        self.cache.set(action_ref_str, &metadata, &yaml_content)?;
+        self.cache
+            .set(action_ref_str, sha.as_deref(), &metadata, &yaml_content)?;
 
         Ok(metadata)
     }
@@ -221,95 +557,304 @@ impl GitHubFetcher {
             .await
     }
 
-    async fn fetch_action_yaml(&self, action_ref: &ActionRef) -> Result<String> {
-        let api_url = self.api_url.as_deref();
+    pub async fn fetch_workflow_metadata(
+        &self,
+        workflow_ref_str: &str,
+    ) -> Result<WorkflowCallMetadata> {
+        if let Some(yaml_content) = self.read_vendored(workflow_ref_str)? {
+            return WorkflowCallMetadata::from_yaml(&yaml_content).with_context(|| {
+                format!(
+                    "Failed to read vendored workflow_call from {}",
+                    workflow_ref_str
+                )
+            });
+        }
 
-        // Try action.yml first
-        let url = action_ref.to_raw_url_with_base(api_url);
-        match self.fetch_with_retry(&url, api_url.is_some()).await {
-            Ok(content) => Ok(content),
-            Err(_) => {
-                // Try action.yaml as fallback
-                let url_yaml = action_ref.to_raw_url_yaml_with_base(api_url);
-                self.fetch_with_retry(&url_yaml, api_url.is_some()).await
+        if self.offline || !self.cache.is_expired(workflow_ref_str, self.cache_ttl_days) {
+            if let Some(cached) = self.cache.get_workflow(workflow_ref_str) {
+                return Ok(cached);
             }
         }
-    }
 
-    async fn fetch_with_retry(&self, url: &str, is_api: bool) -> Result<String> {
-        let mut retries = 0;
-        const MAX_RETRIES: u32 = 3;
+        if let Some(error) = self.cache.get_miss(workflow_ref_str) {
+            return Err(error.into());
+        }
 
-        loop {
-            let mut request = self.client.get(url);
+        let result = self
+            .download_workflow_metadata(workflow_ref_str, false)
+            .await;
+        self.remember_miss(workflow_ref_str, result)
+    }
 
-            if let Some(token) = &self.token {
-                request = request.header("Authorization", format!("token {}", token));
+    async fn download_workflow_metadata(
+        &self,
+        workflow_ref_str: &str,
+        force: bool,
+    ) -> Result<WorkflowCallMetadata> {
+        let mut workflow_ref = ActionRef::parse(workflow_ref_str)?;
+        let source = self.source_for(&workflow_ref)?;
+        let sha = resolve_commit_sha(source, &workflow_ref).await?;
+        if let Some(sha) = &sha {
+            if !force {
+                if let Some(cached) = self.cache.get_pinned_workflow(workflow_ref_str, sha) {
+                    self.cache.record_ref(workflow_ref_str, sha)?;
+                    return Ok(cached);
+                }
             }
+            workflow_ref.ref_ = sha.clone();
+        }
 
-            // For GitHub Enterprise API, request raw content
-            if is_api {
-                request = request.header("Accept", "application/vnd.github.raw+json");
-            }
+        let path = workflow_ref.path.as_deref().unwrap_or_default();
+        let yaml_content = source
+            .fetch_file(&workflow_ref, path)
+            .await?
+            .ok_or_else(|| FetchError::FileNotFound {
+                reference: workflow_ref_str.to_string(),
+                file: path.to_string(),
+                host: source.describe(),
+            })?;
+        let metadata = WorkflowCallMetadata::from_yaml(&yaml_content)
+            .with_context(|| format!("Failed to read workflow_call from {}", workflow_ref_str))?;
 
-            match request.send().await {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        return response
-                            .text()
-                            .await
-                            .with_context(|| format!("Failed to read response from {}", url));
-                    } else if response.status() == reqwest::StatusCode::NOT_FOUND {
-                        return Err(anyhow::anyhow!("Action not found: {}", url));
-                    } else if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                        if retries < MAX_RETRIES {
-                            retries += 1;
-                            let delay = Duration::from_secs(2_u64.pow(retries));
-                            eprintln!(
-                                "Rate limited, retrying in {} seconds ({}/{})",
-                                delay.as_secs(),
-                                retries,
-                                MAX_RETRIES
-                            );
-                            tokio::time::sleep(delay).await;
-                            continue;
-                        }
-                        return Err(anyhow::anyhow!(
-                            "Rate limited after {} retries",
-                            MAX_RETRIES
-                        ));
-                    } else {
-                        return Err(anyhow::anyhow!("HTTP error {}: {}", response.status(), url));
-                    }
-                }
-                Err(e) => {
-                    if retries < MAX_RETRIES {
-                        retries += 1;
-                        let delay = Duration::from_secs(2_u64.pow(retries));
-                        eprintln!(
-                            "Network error, retrying in {} seconds ({}/{}): {}",
-                            delay.as_secs(),
-                            retries,
-                            MAX_RETRIES,
-                            e
-                        );
-                        tokio::time::sleep(delay).await;
-                        continue;
-                    }
-                    return Err(anyhow::anyhow!(
-                        "Network error after {} retries: {}",
-                        MAX_RETRIES,
-                        e
-                    ));
+        self.cache
+            .set_workflow(workflow_ref_str, sha.as_deref(), &metadata, &yaml_content)?;
+
+        Ok(metadata)
+    }
+
+    /// Fetch multiple reusable workflow interfaces in parallel with concurrency limit
+    pub async fn fetch_workflow_metadata_batch(
+        &self,
+        workflow_refs: &HashSet<String>,
+        concurrency: usize,
+    ) -> Vec<(String, Result<WorkflowCallMetadata>)> {
+        stream::iter(workflow_refs.iter())
+            .map(|workflow_ref| async move {
+                let result = self.fetch_workflow_metadata(workflow_ref).await;
+                (workflow_ref.clone(), result)
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await
+    }
+
+    /// Fetch the raw action.yml of an action, or the file of a reusable
+    /// workflow, as `gaji vendor` stores it.
+    pub async fn fetch_definition(&self, reference: &str) -> Result<String> {
+        let action_ref = ActionRef::parse(reference)?;
+        let source = self.source_for(&action_ref)?;
+        if is_reusable_workflow_ref(reference) {
+            let path = action_ref.path.as_deref().unwrap_or_default();
+            let content = source.fetch_file(&action_ref, path).await?;
+            return Ok(content.ok_or_else(|| FetchError::FileNotFound {
+                reference: reference.to_string(),
+                file: path.to_string(),
+                host: source.describe(),
+            })?);
+        }
+        Ok(fetch_action_yaml(source, &action_ref)
+            .await?
+            .ok_or_else(|| action_not_found(reference, source))?)
+    }
+
+    /// Query the core API rate limit. This request does not count against it,
+    /// so it also serves as a connectivity and token check.
+    pub async fn rate_limit(&self) -> Result<RateLimit> {
+        self.ensure_online("the rate limit")?;
+        let url = match &self.api_url {
+            Some(base) => format!("{}/api/v3/rate_limit", base.trim_end_matches('/')),
+            None => "https://api.github.com/rate_limit".to_string(),
+        };
+
+        let mut request = self.client.get(&url);
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", url))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(anyhow::anyhow!("GitHub rejected the token (HTTP 401)"));
+        }
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("HTTP error {}: {}", status, url));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .with_context(|| format!("Invalid response from {}", url))?;
+        let core = &body["resources"]["core"];
+        Ok(RateLimit {
+            limit: core["limit"].as_u64().unwrap_or_default(),
+            remaining: core["remaining"].as_u64().unwrap_or_default(),
+            reset: core["reset"].as_u64().unwrap_or_default(),
+        })
+    }
+
+    /// List the files under `dir` in a repository at `repo.ref_`, recursively.
+    /// Paths are relative to the repository root.
+    pub async fn list_repo_files(&self, repo: &ActionRef, dir: &str) -> Result<Vec<String>> {
+        let mut files = Vec::new();
+        let mut pending = vec![dir.to_string()];
+
+        while let Some(dir) = pending.pop() {
+            let url = repo.to_contents_url_with_base(&dir, self.api_url.as_deref());
+            let listing = self.fetch_text(&url, false).await?;
+            let entries: Vec<serde_json::Value> = serde_json::from_str(&listing)
+                .with_context(|| format!("Expected a directory listing from {}", url))?;
+
+            for entry in entries {
+                let Some(path) = entry["path"].as_str() else {
+                    continue;
+                };
+                match entry["type"].as_str() {
+                    Some("file") => files.push(path.to_string()),
+                    Some("dir") => pending.push(path.to_string()),
+                    _ => {}
                 }
             }
         }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// Fetch the content of a file in a repository at `repo.ref_`.
+    pub async fn fetch_repo_file(&self, repo: &ActionRef, path: &str) -> Result<String> {
+        let url = repo.to_contents_url_with_base(path, self.api_url.as_deref());
+        self.fetch_text(&url, true).await
+    }
+
+    /// Vendored definition of `reference`, if `gaji vendor` stored one.
+    fn read_vendored(&self, reference: &str) -> Result<Option<String>> {
+        let path = vendored_path(&self.vendor_dir, reference);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        std::fs::read_to_string(&path)
+            .map(Some)
+            .with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    /// Remember a ref that does not exist, so it is not looked up again
+    /// until the miss expires.
+    fn remember_miss<T>(&self, reference: &str, result: Result<T>) -> Result<T> {
+        if let Some(error) = result
+            .as_ref()
+            .err()
+            .and_then(|e| e.downcast_ref::<FetchError>())
+            .filter(|e| e.is_not_found())
+        {
+            self.cache.record_miss(reference, error)?;
+        }
+        result
+    }
+
+    /// Source serving `repo`. Remote sources are refused in offline mode.
+    fn source_for(&self, repo: &ActionRef) -> Result<&dyn MetadataSource> {
+        let source = self.sources.source_for(repo);
+        if source.is_remote() {
+            self.ensure_online(&repo.to_string())?;
+        }
+        Ok(source)
+    }
+
+    fn ensure_online(&self, what: &str) -> Result<()> {
+        if self.offline {
+            return Err(FetchError::Offline {
+                what: what.to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// GET `url` with the configured token, failing on 404.
+    async fn fetch_text(&self, url: &str, is_api: bool) -> Result<String> {
+        self.ensure_online(url)?;
+
+        let mut headers = Vec::new();
+        if let Some(token) = &self.token {
+            headers.push(("Authorization", format!("token {}", token)));
+        }
+        // For GitHub Enterprise API, request raw content
+        if is_api {
+            headers.push(("Accept", "application/vnd.github.raw+json".to_string()));
+        }
+
+        http_get(&self.client, url, &headers)
+            .await?
+            .with_context(|| format!("Not found: {}", url))
+    }
+}
+
+/// Resolve the commit a ref points to. Returns `None` when the source cannot
+/// tell, in which case the cache falls back to the ref.
+async fn resolve_commit_sha(
+    source: &dyn MetadataSource,
+    action_ref: &ActionRef,
+) -> Result<Option<String>> {
+    if is_commit_sha(&action_ref.ref_) {
+        return Ok(Some(action_ref.ref_.clone()));
+    }
+    source.resolve_commit(action_ref).await
+}
+
+/// `action.yml` of an action, falling back to `action.yaml`. A directory
+/// with only a `Dockerfile` is a Docker action without inputs, described by
+/// a generated `action.yml`.
+async fn fetch_action_yaml(
+    source: &dyn MetadataSource,
+    action_ref: &ActionRef,
+) -> Result<Option<String>> {
+    let dir = action_ref
+        .path
+        .as_ref()
+        .map(|p| format!("{}/", p))
+        .unwrap_or_default();
+
+    for file in ["action.yml", "action.yaml"] {
+        if let Some(content) = source
+            .fetch_file(action_ref, &format!("{}{}", dir, file))
+            .await?
+        {
+            return Ok(Some(content));
+        }
+    }
+
+    if source
+        .fetch_file(action_ref, &format!("{}Dockerfile", dir))
+        .await?
+        .is_some()
+    {
+        let name = match &action_ref.path {
+            Some(path) => format!("{}/{}/{}", action_ref.owner, action_ref.repo, path),
+            None => format!("{}/{}", action_ref.owner, action_ref.repo),
+        };
+        return Ok(Some(format!(
+            "name: '{}'\nruns:\n  using: docker\n  image: Dockerfile\n",
+            name.replace('\'', "''")
+        )));
+    }
+    Ok(None)
+}
+
+fn action_not_found(reference: &str, source: &dyn MetadataSource) -> FetchError {
+    FetchError::FileNotFound {
+        reference: reference.to_string(),
+        file: "action.yml, action.yaml or Dockerfile".to_string(),
+        host: source.describe(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::source::MemorySource;
 
     #[test]
     fn test_parse_simple_action_ref() {
@@ -320,6 +865,43 @@ mod tests {
         assert!(action_ref.path.is_none());
     }
 
+    #[test]
+    fn test_parse_ref_with_slashes() {
+        let action_ref = ActionRef::parse("owner/repo@feature/x").unwrap();
+        assert_eq!(action_ref.repo, "repo");
+        assert!(action_ref.path.is_none());
+        assert_eq!(action_ref.ref_, "feature/x");
+
+        let action_ref = ActionRef::parse("owner/repo/packages/lint@release/v2.1").unwrap();
+        assert_eq!(action_ref.path.as_deref(), Some("packages/lint"));
+        assert_eq!(action_ref.ref_, "release/v2.1");
+        assert_eq!(
+            action_ref.to_contents_url_with_base("packages/lint", None),
+            "https://api.github.com/repos/owner/repo/contents/packages/lint?ref=release%2Fv2.1"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_refs() {
+        for reference in [
+            "actions/checkout",
+            "actions@v5",
+            "actions/checkout@",
+            "/checkout@v5",
+            "actions/check out@v5",
+            "owner/repo/../escape@v1",
+            "owner/repo//path@v1",
+            "owner/repo@feature//x",
+            "owner/repo@v1..v2",
+            "owner/repo@main.lock",
+            "owner/repo@has space",
+            "owner/repo@.hidden",
+            "owner/repo@@{1}",
+        ] {
+            assert!(ActionRef::parse(reference).is_err(), "{}", reference);
+        }
+    }
+
     #[test]
     fn test_parse_action_ref_with_path() {
         let action_ref = ActionRef::parse("owner/repo/path/to/action@main").unwrap();
@@ -329,20 +911,11 @@ mod tests {
         assert_eq!(action_ref.ref_, "main");
     }
 
-    #[test]
-    fn test_raw_url_generation() {
-        let action_ref = ActionRef::parse("actions/checkout@v5").unwrap();
-        assert_eq!(
-            action_ref.to_raw_url(),
-            "https://raw.githubusercontent.com/actions/checkout/v5/action.yml"
-        );
-    }
-
     #[test]
     fn test_invalid_action_ref() {
         assert!(ActionRef::parse("invalid").is_err());
         assert!(ActionRef::parse("no-at-sign").is_err());
-        assert!(ActionRef::parse("only/one@").is_ok()); // Empty ref is technically valid
+        assert!(ActionRef::parse("only/one@").is_err());
     }
 
     #[test]
@@ -357,15 +930,179 @@ mod tests {
     }
 
     #[test]
-    fn test_raw_url_with_path() {
-        let action_ref = ActionRef::parse("owner/repo/sub/path@main").unwrap();
+    fn test_is_reusable_workflow_ref() {
+        assert!(is_reusable_workflow_ref(
+            "org/repo/.github/workflows/deploy.yml@v1"
+        ));
+        assert!(is_reusable_workflow_ref(
+            "org/repo/.github/workflows/deploy.yaml@main"
+        ));
+        assert!(!is_reusable_workflow_ref("actions/checkout@v5"));
+        assert!(!is_reusable_workflow_ref("owner/repo/sub/path@main"));
+        assert!(!is_reusable_workflow_ref("./.github/workflows/deploy.yml"));
+    }
+
+    #[test]
+    fn test_workflow_call_metadata_from_yaml() {
+        let yaml = r#"
+name: Deploy
+on:
+  workflow_call:
+    inputs:
+      environment:
+        type: string
+        required: true
+      dry-run:
+        type: boolean
+        default: false
+    secrets:
+      token:
+        required: true
+    outputs:
+      url:
+        description: Deployed URL
+        value: ${{ jobs.deploy.outputs.url }}
+jobs: {}
+"#;
+        let metadata = WorkflowCallMetadata::from_yaml(yaml).unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Deploy"));
+        assert_eq!(metadata.inputs["environment"].required, Some(true));
+        assert_eq!(
+            metadata.inputs["dry-run"].input_type.as_deref(),
+            Some("boolean")
+        );
+        assert_eq!(metadata.inputs["dry-run"].default.as_deref(), Some("false"));
+        assert_eq!(metadata.secrets["token"].required, Some(true));
+        assert!(metadata.outputs.contains_key("url"));
+    }
+
+    #[test]
+    fn test_workflow_call_metadata_requires_workflow_call() {
+        let yaml = "on:\n  push: {}\njobs: {}\n";
+        let result = WorkflowCallMetadata::from_yaml(yaml);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("workflow_call"));
+
+        let bare = "on: workflow_call\njobs: {}\n";
+        let metadata = WorkflowCallMetadata::from_yaml(bare).unwrap();
+        assert!(metadata.inputs.is_empty());
+    }
+
+    #[test]
+    fn test_vendored_path() {
+        let dir = Path::new(".gaji/actions");
         assert_eq!(
-            action_ref.to_raw_url(),
-            "https://raw.githubusercontent.com/owner/repo/sub/path/main/action.yml"
+            vendored_path(dir, "actions/checkout@v5"),
+            dir.join("actions/checkout@v5/action.yml")
         );
         assert_eq!(
-            action_ref.to_raw_url_yaml(),
-            "https://raw.githubusercontent.com/owner/repo/sub/path/main/action.yaml"
+            vendored_path(dir, "org/repo/.github/workflows/deploy.yml@v1"),
+            dir.join("org/repo/.github/workflows/deploy.yml@v1/workflow.yml")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offline_fetch_uses_vendored_metadata() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let vendor_dir = temp.path().join("actions");
+        let path = vendored_path(&vendor_dir, "org/action@v1");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "name: Vendored\n").unwrap();
+
+        let cache = Cache::load_from(temp.path().join("cache.json")).unwrap();
+        let fetcher = GitHubFetcher::new(cache, None, None, 30)
+            .unwrap()
+            .with_vendor_dir(vendor_dir)
+            .with_offline(true);
+
+        let metadata = fetcher
+            .fetch_action_metadata("org/action@v1")
+            .await
+            .unwrap();
+        assert_eq!(metadata.name, "Vendored");
+
+        let err = fetcher
+            .fetch_action_metadata("org/missing@v1")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("org/missing@v1"));
+    }
+
+    #[test]
+    fn test_contents_url_generation() {
+        let repo = ActionRef::parse("org/templates/node@v1").unwrap();
+        assert_eq!(
+            repo.to_contents_url_with_base("node/workflows", None),
+            "https://api.github.com/repos/org/templates/contents/node/workflows?ref=v1"
+        );
+        assert_eq!(
+            repo.to_contents_url_with_base("node", Some("https://github.example.com/")),
+            "https://github.example.com/api/v3/repos/org/templates/contents/node?ref=v1"
+        );
+    }
+
+    #[test]
+    fn test_action_ref_display() {
+        for reference in ["actions/checkout@v5", "owner/repo/sub/path@main"] {
+            assert_eq!(ActionRef::parse(reference).unwrap().to_string(), reference);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_routed_source() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = Cache::load_from(temp.path().join("cache.json")).unwrap();
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let memory = MemorySource::new()
+            .with_commit("org/action@v1", sha)
+            .with_file(
+                &format!("org/action@{}", sha),
+                "action.yaml",
+                "name: Memory\n",
+            );
+        let fetcher = GitHubFetcher::new(cache, None, None, 30)
+            .unwrap()
+            .with_sources(SourceRouter::new(Arc::new(memory)))
+            .with_offline(true);
+
+        // Local sources are usable offline, files are read at the resolved
+        // commit, and action.yaml is the fallback
+        let metadata = fetcher
+            .fetch_action_metadata("org/action@v1")
+            .await
+            .unwrap();
+        assert_eq!(metadata.name, "Memory");
+        assert!(fetcher.cache.get_pinned("org/action@v1", sha).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_dockerfile_action() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = Cache::load_from(temp.path().join("cache.json")).unwrap();
+        let memory = MemorySource::new()
+            .with_file("org/tools@main", "docker/lint/Dockerfile", "FROM alpine\n")
+            .with_file("org/tools@main", "empty/README.md", "");
+        let fetcher = GitHubFetcher::new(cache, None, None, 30)
+            .unwrap()
+            .with_sources(SourceRouter::new(Arc::new(memory)));
+
+        let metadata = fetcher
+            .fetch_action_metadata("org/tools/docker/lint@main")
+            .await
+            .unwrap();
+        assert_eq!(metadata.name, "org/tools/docker/lint");
+        assert!(metadata.inputs.is_none());
+        let runs = metadata.runs.unwrap();
+        assert_eq!(runs.using, "docker");
+        assert_eq!(runs.image.as_deref(), Some("Dockerfile"));
+
+        let error = fetcher
+            .fetch_action_metadata("org/tools/empty@main")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("No action.yml, action.yaml or Dockerfile in org/tools/empty@main")
         );
     }
 }
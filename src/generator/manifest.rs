@@ -0,0 +1,77 @@
+//! Record of the types in the generated directory. Generating types for some
+//! refs merges them into it, and the index is rebuilt from the whole record,
+//! so refs generated earlier keep their `getAction` overloads.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use super::{ActionTypeInfo, WorkflowTypeInfo};
+
+/// Name of the manifest in the generated directory.
+const MANIFEST_FILE: &str = "manifest.json";
+
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct Manifest {
+    version: u32,
+    /// Keyed by action ref.
+    pub(super) actions: BTreeMap<String, ActionTypeInfo>,
+    /// Keyed by workflow ref, including local `./` workflows.
+    pub(super) workflows: BTreeMap<String, WorkflowTypeInfo>,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            actions: BTreeMap::new(),
+            workflows: BTreeMap::new(),
+        }
+    }
+}
+
+impl Manifest {
+    /// The manifest in `dir`, or `None` when it is missing, unreadable or
+    /// differently versioned. Generation then also regenerates the types
+    /// already in `dir`, so that the index keeps covering them.
+    pub(super) async fn read(dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(dir.join(MANIFEST_FILE)).await.ok()?;
+        serde_json::from_str::<Self>(&content)
+            .ok()
+            .filter(|manifest| manifest.version == MANIFEST_VERSION)
+    }
+
+    /// The manifest in `dir`, or an empty one when it cannot be read.
+    pub(super) async fn load(dir: &Path) -> Self {
+        Self::read(dir).await.unwrap_or_default()
+    }
+
+    pub(super) async fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(MANIFEST_FILE);
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json + "\n")
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub(super) fn add_action(&mut self, info: ActionTypeInfo) {
+        self.actions.insert(info.action_ref.clone(), info);
+    }
+
+    pub(super) fn add_workflow(&mut self, info: WorkflowTypeInfo) {
+        self.workflows.insert(info.workflow_ref.clone(), info);
+    }
+
+    /// Forget entries whose type file is gone, so the index never imports a
+    /// missing module.
+    pub(super) fn retain_existing(&mut self, dir: &Path) {
+        let exists = |module: &str| dir.join(format!("{}.d.ts", module)).exists();
+        self.actions.retain(|_, info| exists(&info.module_name));
+        self.workflows.retain(|_, info| exists(&info.module_name));
+    }
+}
@@ -1,3 +1,5 @@
+mod manifest;
+pub mod overrides;
 pub mod templates;
 pub mod types;
 
@@ -6,20 +8,32 @@ use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
 use tokio::fs;
 
 use crate::cache::Cache;
-use crate::fetcher::GitHubFetcher;
+use crate::config::{NetworkConfig, TypesConfig};
+use crate::credentials::redact;
+use crate::fetcher::{
+    is_reusable_workflow_ref, ActionRef, FetchError, GitHubFetcher, WorkflowCallMetadata,
+    VENDOR_DIR,
+};
+use crate::source::SourceRouter;
 
+use self::manifest::Manifest;
 use self::templates::{
     BASE_TYPES_TEMPLATE, CLASS_DECLARATIONS_TEMPLATE, GET_ACTION_FALLBACK_DECL_TEMPLATE,
-    GET_ACTION_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE,
+    GET_ACTION_RUNTIME_TEMPLATE, GET_WORKFLOW_FALLBACK_DECL_TEMPLATE,
+    GET_WORKFLOW_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE,
 };
-use self::types::generate_type_definition;
+use self::types::{generate_type_definition, generate_workflow_type_definition};
 
 pub struct TypeGenerator {
     fetcher: GitHubFetcher,
     output_dir: PathBuf,
+    local_workflows_dir: Option<PathBuf>,
+    types: TypesConfig,
+    concurrency: usize,
 }
 
 impl TypeGenerator {
@@ -28,7 +42,7 @@ impl TypeGenerator {
         output_dir: PathBuf,
         token: Option<String>,
         api_url: Option<String>,
-    ) -> Self {
+    ) -> Result<Self> {
         Self::with_cache_ttl(cache, output_dir, token, api_url, 30)
     }
 
@@ -38,11 +52,49 @@ impl TypeGenerator {
         token: Option<String>,
         api_url: Option<String>,
         cache_ttl_days: u64,
-    ) -> Self {
-        Self {
-            fetcher: GitHubFetcher::new(cache, token, api_url, cache_ttl_days),
+    ) -> Result<Self> {
+        Ok(Self {
+            fetcher: GitHubFetcher::new(cache, token, api_url, cache_ttl_days)?,
             output_dir,
-        }
+            local_workflows_dir: None,
+            types: TypesConfig::default(),
+            concurrency: NetworkConfig::default().concurrency,
+        })
+    }
+
+    /// Apply the proxy, certificate, timeout and concurrency settings of the
+    /// `network` config section. Call it before `with_sources`.
+    pub fn with_network(mut self, network: &NetworkConfig) -> Result<Self> {
+        self.fetcher = self.fetcher.with_network(network)?;
+        self.concurrency = network.concurrency;
+        Ok(self)
+    }
+
+    /// Apply hand-written type overrides from the `types` config section.
+    pub fn with_type_overrides(mut self, types: TypesConfig) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Use only vendored and cached metadata. Refs missing from both fail
+    /// the generation instead of being skipped.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.fetcher = self.fetcher.with_offline(offline);
+        self
+    }
+
+    /// Fetch refs from the sources configured in `sources` of gaji.config.ts.
+    pub fn with_sources(mut self, router: SourceRouter) -> Self {
+        self.fetcher = self.fetcher.with_sources(router);
+        self
+    }
+
+    /// Also generate types for reusable workflows that gaji built into `dir`
+    /// (usually `.github/workflows`), so `new WorkflowCall("./.github/workflows/x.yml")`
+    /// is type-checked against the workflow's `on.workflow_call` definition.
+    pub fn with_local_workflows(mut self, dir: PathBuf) -> Self {
+        self.local_workflows_dir = Some(dir);
+        self
     }
 
     pub async fn generate_types_for_refs(
@@ -51,15 +103,36 @@ impl TypeGenerator {
     ) -> Result<Vec<PathBuf>> {
         fs::create_dir_all(&self.output_dir).await?;
 
+        // Without a manifest the index would only cover these refs, so the
+        // types an older gaji generated are regenerated along with them
+        let mut action_refs = action_refs.clone();
+        if Manifest::read(&self.output_dir).await.is_none() {
+            action_refs.extend(
+                self.unrecorded_types()
+                    .await?
+                    .into_iter()
+                    .map(|(reference, _)| reference)
+                    .filter(|reference| !reference.starts_with("./")),
+            );
+        }
+
         let mut generated_files = Vec::new();
 
         // Generate base types first
         let base_path = self.generate_base_types().await?;
         generated_files.push(base_path);
 
+        // Reusable workflow refs (getWorkflow) are fetched and typed separately
+        let (workflow_refs, action_refs): (HashSet<String>, HashSet<String>) = action_refs
+            .iter()
+            .cloned()
+            .partition(|r| is_reusable_workflow_ref(r));
+
         let mut action_infos = Vec::new();
+        let mut workflow_infos = Vec::new();
+        let mut missing = Vec::new();
 
-        let pb = ProgressBar::new(action_refs.len() as u64);
+        let pb = ProgressBar::new((action_refs.len() + workflow_refs.len()) as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("   {spinner:.green} [{bar:30.cyan/dim}] {pos}/{len} {msg}")
@@ -71,7 +144,7 @@ impl TypeGenerator {
         // Fetch all action metadata in parallel (max 10 concurrent requests)
         let fetch_results = self
             .fetcher
-            .fetch_action_metadata_batch(action_refs, 10)
+            .fetch_action_metadata_batch(&action_refs, self.concurrency)
             .await;
 
         pb.set_message("generating types...");
@@ -89,14 +162,64 @@ impl TypeGenerator {
                         }
                         Err(e) => {
                             pb.suspend(|| {
-                                eprintln!("Failed to generate types for {}: {}", action_ref, e);
+                                eprintln!(
+                                    "Failed to generate types for {}: {}",
+                                    action_ref,
+                                    redact(&e.to_string())
+                                );
                             });
                         }
                     }
                 }
+                Err(e) if is_offline_miss(&e) => missing.push(action_ref),
                 Err(e) => {
                     pb.suspend(|| {
-                        eprintln!("Failed to fetch metadata for {}: {}", action_ref, e);
+                        eprintln!(
+                            "Failed to fetch metadata for {}: {}",
+                            action_ref,
+                            redact(&e.to_string())
+                        );
+                    });
+                }
+            }
+            pb.inc(1);
+        }
+
+        let workflow_results = self
+            .fetcher
+            .fetch_workflow_metadata_batch(&workflow_refs, self.concurrency)
+            .await;
+
+        for (workflow_ref, result) in workflow_results {
+            match result {
+                Ok(metadata) => {
+                    match self
+                        .generate_type_from_workflow(&workflow_ref, &metadata)
+                        .await
+                    {
+                        Ok((path, info)) => {
+                            generated_files.push(path);
+                            workflow_infos.push(info);
+                        }
+                        Err(e) => {
+                            pb.suspend(|| {
+                                eprintln!(
+                                    "Failed to generate types for {}: {}",
+                                    workflow_ref,
+                                    redact(&e.to_string())
+                                );
+                            });
+                        }
+                    }
+                }
+                Err(e) if is_offline_miss(&e) => missing.push(workflow_ref),
+                Err(e) => {
+                    pb.suspend(|| {
+                        eprintln!(
+                            "Failed to fetch workflow {}: {}",
+                            workflow_ref,
+                            redact(&e.to_string())
+                        );
                     });
                 }
             }
@@ -105,6 +228,141 @@ impl TypeGenerator {
 
         pb.finish_and_clear();
 
+        if !missing.is_empty() {
+            missing.sort();
+            anyhow::bail!(
+                "Offline mode: no vendored or cached metadata for {} ref(s):\n  - {}\nRun 'gaji vendor' with network access and commit {}",
+                missing.len(),
+                missing.join("\n  - "),
+                VENDOR_DIR
+            );
+        }
+
+        // Locally built reusable workflows (./.github/workflows/*.yml)
+        if let Some(dir) = &self.local_workflows_dir {
+            for (workflow_ref, metadata) in discover_local_workflows(dir).await? {
+                let (path, info) = self
+                    .generate_type_from_workflow(&workflow_ref, &metadata)
+                    .await?;
+                generated_files.push(path);
+                workflow_infos.push(info);
+            }
+        }
+
+        // The index covers every ref generated so far, not only these
+        let mut manifest = Manifest::load(&self.output_dir).await;
+        for info in action_infos {
+            manifest.add_action(info);
+        }
+        for info in workflow_infos {
+            manifest.add_workflow(info);
+        }
+        self.write_index(manifest).await?;
+
+        Ok(generated_files)
+    }
+
+    /// Remove the types of refs that no `.ts` file under `workflows_dir`
+    /// uses. Fails without removing anything when a file cannot be parsed,
+    /// since its refs would count as unused.
+    pub async fn remove_types_unused_by(&self, workflows_dir: &Path) -> Result<Vec<String>> {
+        if !workflows_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let used = crate::parser::used_refs(workflows_dir).await?;
+        self.remove_unused_types(&used).await
+    }
+
+    /// Delete the types of actions and workflows not in `used`, then rebuild
+    /// the index. Locally built workflows count as used. Besides manifest
+    /// entries, only files that gaji generated for a ref before the manifest
+    /// existed are deleted; other `.d.ts` files are left alone. Returns the
+    /// removed refs.
+    pub async fn remove_unused_types(&self, used: &HashSet<String>) -> Result<Vec<String>> {
+        if !self.output_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut used = used.clone();
+        if let Some(dir) = &self.local_workflows_dir {
+            used.extend(
+                discover_local_workflows(dir)
+                    .await?
+                    .into_iter()
+                    .map(|(workflow_ref, _)| workflow_ref),
+            );
+        }
+
+        let mut manifest = Manifest::load(&self.output_dir).await;
+        let mut removed: Vec<String> = manifest
+            .actions
+            .keys()
+            .chain(manifest.workflows.keys())
+            .filter(|reference| !used.contains(*reference))
+            .cloned()
+            .collect();
+        manifest
+            .actions
+            .retain(|reference, _| used.contains(reference));
+        manifest
+            .workflows
+            .retain(|reference, _| used.contains(reference));
+        for reference in &removed {
+            let path = self.output_dir.join(type_file_name(reference));
+            if path.exists() {
+                fs::remove_file(&path).await?;
+            }
+        }
+
+        for (reference, path) in self.unrecorded_types().await? {
+            if !used.contains(&reference) {
+                fs::remove_file(path).await?;
+                removed.push(reference);
+            }
+        }
+
+        if !removed.is_empty() {
+            self.write_index(manifest).await?;
+        }
+        removed.sort();
+        Ok(removed)
+    }
+
+    /// Refs and paths of the type files generated before the manifest
+    /// existed, recognized by gaji's header naming the ref the file is named
+    /// after. Files without such a header are not gaji's and are skipped.
+    async fn unrecorded_types(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut found = Vec::new();
+        if !self.output_dir.is_dir() {
+            return Ok(found);
+        }
+        let mut entries = fs::read_dir(&self.output_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".d.ts") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(entry.path()).await else {
+                continue;
+            };
+            let Some(reference) = generated_type_ref(&content) else {
+                continue;
+            };
+            if type_file_name(reference) == name {
+                found.push((reference.to_string(), entry.path()));
+            }
+        }
+        Ok(found)
+    }
+
+    /// Save `manifest` and rebuild index.d.ts and index.js from it.
+    async fn write_index(&self, mut manifest: Manifest) -> Result<()> {
+        manifest.retain_existing(&self.output_dir);
+        manifest.save(&self.output_dir).await?;
+
+        let action_infos: Vec<ActionTypeInfo> = manifest.actions.into_values().collect();
+        let workflow_infos: Vec<WorkflowTypeInfo> = manifest.workflows.into_values().collect();
+
         // Remove old index.ts if it exists (replaced by index.d.ts + index.js)
         let old_index_ts = self.output_dir.join("index.ts");
         if old_index_ts.exists() {
@@ -112,10 +370,12 @@ impl TypeGenerator {
         }
 
         // Generate index.d.ts (type declarations) and index.js (runtime)
-        self.generate_index_dts(&action_infos).await?;
-        self.generate_index_js(&action_infos).await?;
+        self.generate_index_dts(&action_infos, &workflow_infos)
+            .await?;
+        self.generate_index_js(&action_infos, &workflow_infos)
+            .await?;
 
-        Ok(generated_files)
+        Ok(())
     }
 
     async fn generate_base_types(&self) -> Result<PathBuf> {
@@ -132,7 +392,8 @@ impl TypeGenerator {
         action_ref: &str,
         metadata: &crate::fetcher::ActionMetadata,
     ) -> Result<(PathBuf, ActionTypeInfo)> {
-        let type_def = generate_type_definition(action_ref, metadata);
+        let type_def =
+            generate_type_definition(action_ref, metadata, self.types.override_for(action_ref));
 
         let interface_name = action_ref_to_interface_name(action_ref);
         let module_name = action_ref_to_module_name(action_ref);
@@ -161,16 +422,53 @@ impl TypeGenerator {
         ))
     }
 
+    async fn generate_type_from_workflow(
+        &self,
+        workflow_ref: &str,
+        metadata: &WorkflowCallMetadata,
+    ) -> Result<(PathBuf, WorkflowTypeInfo)> {
+        let type_def = generate_workflow_type_definition(workflow_ref, metadata);
+
+        let filename = workflow_ref_to_filename(workflow_ref);
+        let file_path = self.output_dir.join(&filename);
+
+        fs::write(&file_path, type_def).await?;
+
+        Ok((
+            file_path,
+            WorkflowTypeInfo {
+                workflow_ref: workflow_ref.to_string(),
+                interface_name: action_ref_to_interface_name(workflow_ref),
+                module_name: filename.trim_end_matches(".d.ts").to_string(),
+                has_required_inputs: metadata
+                    .inputs
+                    .values()
+                    .any(|input| input.required.unwrap_or(false)),
+                has_required_secrets: metadata
+                    .secrets
+                    .values()
+                    .any(|secret| secret.required.unwrap_or(false)),
+                output_names: metadata.outputs.keys().cloned().collect(),
+            },
+        ))
+    }
+
     /// Generate index.d.ts - type declarations only
-    async fn generate_index_dts(&self, action_infos: &[ActionTypeInfo]) -> Result<()> {
+    async fn generate_index_dts(
+        &self,
+        action_infos: &[ActionTypeInfo],
+        workflow_infos: &[WorkflowTypeInfo],
+    ) -> Result<()> {
         let mut sorted_infos = action_infos.to_vec();
         sorted_infos.sort_by(|left, right| left.action_ref.cmp(&right.action_ref));
+        let mut sorted_workflows = workflow_infos.to_vec();
+        sorted_workflows.sort_by(|left, right| left.workflow_ref.cmp(&right.workflow_ref));
 
         let mut content = String::new();
         content.push_str("// Auto-generated by gaji\n// Do not edit manually\n\n");
 
         // Type imports
-        content.push_str("import type { JobStep, ActionStep, JobDefinition, WorkflowConfig, WorkflowDefinition, Step, Permissions, Service, Container, WorkflowTrigger, ScheduleTrigger, WorkflowDispatchInput, WorkflowOn, ActionInputDefinition, ActionOutputDefinition, NodeActionConfig, NodeActionRuns, DockerActionConfig, DockerActionRuns, JobOutputs, GajiConfig } from './base';\n");
+        content.push_str("import type { JobStep, ActionStep, JobDefinition, RunsOn, WorkflowConfig, WorkflowDefinition, Step, Permissions, Service, Container, WorkflowTrigger, ScheduleTrigger, WorkflowDispatchInput, WorkflowOn, ActionInputDefinition, ActionOutputDefinition, NodeActionConfig, NodeActionRuns, DockerActionConfig, DockerActionRuns, JobOutputs, GajiConfig } from './base';\n");
 
         // Per-action type imports
         for info in &sorted_infos {
@@ -187,6 +485,12 @@ impl TypeGenerator {
             }
             content.push('\n');
         }
+        for info in &sorted_workflows {
+            content.push_str(&format!(
+                "import type {{ {0}Inputs, {0}Secrets, {0}Outputs }} from './{1}';\n",
+                info.interface_name, info.module_name
+            ));
+        }
 
         content.push('\n');
 
@@ -198,13 +502,13 @@ impl TypeGenerator {
                 // Second overload has optional id → returns JobStep
                 content.push_str(&format!(
                     r#"export declare function getAction(
-    ref: '{}'
+    ref: {}
 ): {{
-    <Id extends string>(config: {{ id: Id; name?: string; with?: {}Inputs; if?: string; env?: Record<string, string> }}): ActionStep<{}Outputs, Id>;
-    (config?: {{ name?: string; with?: {}Inputs; id?: string; if?: string; env?: Record<string, string> }}): JobStep;
+    <Id extends string>(config: {{ id: Id; name?: string; with?: {}Inputs; if?: string; env?: Record<string, string>; 'continue-on-error'?: boolean; 'timeout-minutes'?: number }}): ActionStep<{}Outputs, Id>;
+    (config?: {{ name?: string; with?: {}Inputs; id?: string; if?: string; env?: Record<string, string>; 'continue-on-error'?: boolean; 'timeout-minutes'?: number }}): JobStep;
 }};
 "#,
-                    info.action_ref,
+                    quote_str(&info.action_ref),
                     info.interface_name,
                     info.interface_name,
                     info.interface_name
@@ -213,16 +517,19 @@ impl TypeGenerator {
                 // Actions WITHOUT outputs: simple signature → JobStep
                 content.push_str(&format!(
                     r#"export declare function getAction(
-    ref: '{}'
+    ref: {}
 ): (config?: {{
     name?: string;
     with?: {}Inputs;
     id?: string;
     if?: string;
     env?: Record<string, string>;
+    'continue-on-error'?: boolean;
+    'timeout-minutes'?: number;
 }}) => JobStep;
 "#,
-                    info.action_ref, info.interface_name
+                    quote_str(&info.action_ref),
+                    info.interface_name
                 ));
             }
         }
@@ -230,11 +537,41 @@ impl TypeGenerator {
         // Base getAction overload (from template)
         content.push_str(GET_ACTION_FALLBACK_DECL_TEMPLATE);
 
+        // getWorkflow overloads — config is required when the workflow has required inputs/secrets
+        for info in &sorted_workflows {
+            let config_marker = if info.has_required_inputs || info.has_required_secrets {
+                ""
+            } else {
+                "?"
+            };
+            content.push_str(&format!(
+                "export declare function getWorkflow(\n    ref: {ref}\n): (config{config_marker}: {config}) => WorkflowCall<{ref}, {name}Outputs>;\n",
+                ref = quote_str(&info.workflow_ref),
+                config = workflow_config_type(info),
+                name = info.interface_name,
+            ));
+        }
+
+        // Base getWorkflow overload (from template)
+        content.push_str(GET_WORKFLOW_FALLBACK_DECL_TEMPLATE);
+
+        // Typed reusable workflows, looked up by `new WorkflowCall(uses, ...)`
+        content.push_str("\nexport interface WorkflowCallRegistry {\n");
+        for info in &sorted_workflows {
+            content.push_str(&format!(
+                "    {}: {{ config: {}; outputs: {}Outputs }};\n",
+                quote_str(&info.workflow_ref),
+                workflow_config_type(info),
+                info.interface_name
+            ));
+        }
+        content.push_str("}\n");
+
         // Class declarations (from template)
         content.push_str(CLASS_DECLARATIONS_TEMPLATE);
 
         // Type re-exports
-        content.push_str("\nexport type { JobStep, ActionStep, Step, JobDefinition, Service, Container, Permissions, WorkflowTrigger, ScheduleTrigger, WorkflowDispatchInput, WorkflowOn, WorkflowConfig, WorkflowDefinition, ActionInputDefinition, ActionOutputDefinition, NodeActionConfig, NodeActionRuns, DockerActionConfig, DockerActionRuns, JobOutputs, GajiConfig } from './base';\n");
+        content.push_str("\nexport type { JobStep, ActionStep, Step, JobDefinition, RunsOn, Service, Container, Permissions, WorkflowTrigger, ScheduleTrigger, WorkflowDispatchInput, WorkflowOn, WorkflowConfig, WorkflowDefinition, ActionInputDefinition, ActionOutputDefinition, NodeActionConfig, NodeActionRuns, DockerActionConfig, DockerActionRuns, JobOutputs, GajiConfig } from './base';\n");
         for info in &sorted_infos {
             if info.has_outputs {
                 content.push_str(&format!(
@@ -249,6 +586,12 @@ impl TypeGenerator {
             }
             content.push('\n');
         }
+        for info in &sorted_workflows {
+            content.push_str(&format!(
+                "export type {{ {0}Inputs, {0}Secrets, {0}Outputs }} from './{1}';\n",
+                info.interface_name, info.module_name
+            ));
+        }
 
         let path = self.output_dir.join("index.d.ts");
         fs::write(path, content).await?;
@@ -257,9 +600,15 @@ impl TypeGenerator {
     }
 
     /// Generate index.js - runtime implementation
-    async fn generate_index_js(&self, action_infos: &[ActionTypeInfo]) -> Result<()> {
+    async fn generate_index_js(
+        &self,
+        action_infos: &[ActionTypeInfo],
+        workflow_infos: &[WorkflowTypeInfo],
+    ) -> Result<()> {
         let mut sorted_infos = action_infos.to_vec();
         sorted_infos.sort_by(|left, right| left.action_ref.cmp(&right.action_ref));
+        let mut sorted_workflows = workflow_infos.to_vec();
+        sorted_workflows.sort_by(|left, right| left.workflow_ref.cmp(&right.workflow_ref));
 
         let mut content = String::new();
         content.push_str("// Auto-generated by gaji\n// Do not edit manually\n\n");
@@ -268,14 +617,24 @@ impl TypeGenerator {
         content.push_str("var __action_outputs = {\n");
         for info in &sorted_infos {
             if !info.output_names.is_empty() {
-                let names: Vec<String> = info
-                    .output_names
-                    .iter()
-                    .map(|n| format!("'{}'", n))
-                    .collect();
+                let names: Vec<String> = info.output_names.iter().map(|n| quote_str(n)).collect();
+                content.push_str(&format!(
+                    "    {}: [{}],\n",
+                    quote_str(&info.action_ref),
+                    names.join(", ")
+                ));
+            }
+        }
+        content.push_str("};\n");
+
+        // Reusable workflow output registry
+        content.push_str("var __workflow_outputs = {\n");
+        for info in &sorted_workflows {
+            if !info.output_names.is_empty() {
+                let names: Vec<String> = info.output_names.iter().map(|n| quote_str(n)).collect();
                 content.push_str(&format!(
-                    "    '{}': [{}],\n",
-                    info.action_ref,
+                    "    {}: [{}],\n",
+                    quote_str(&info.workflow_ref),
                     names.join(", ")
                 ));
             }
@@ -285,6 +644,9 @@ impl TypeGenerator {
         // getAction runtime (from template)
         content.push_str(GET_ACTION_RUNTIME_TEMPLATE);
 
+        // getWorkflow runtime (from template)
+        content.push_str(GET_WORKFLOW_RUNTIME_TEMPLATE);
+
         // Job/Workflow/Action/WorkflowCall/ActionRef/NodeAction runtime classes (from template)
         content.push_str(JOB_WORKFLOW_RUNTIME_TEMPLATE);
         content.push('\n');
@@ -296,7 +658,7 @@ impl TypeGenerator {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct ActionTypeInfo {
     action_ref: String,
     interface_name: String,
@@ -305,13 +667,113 @@ struct ActionTypeInfo {
     output_names: Vec<String>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+struct WorkflowTypeInfo {
+    workflow_ref: String,
+    interface_name: String,
+    module_name: String,
+    has_required_inputs: bool,
+    has_required_secrets: bool,
+    output_names: Vec<String>,
+}
+
+/// Config object accepted by a typed reusable workflow call.
+fn workflow_config_type(info: &WorkflowTypeInfo) -> String {
+    format!(
+        "{{ with{}: {name}Inputs; secrets{}: {name}Secrets | 'inherit'; needs?: string[]; if?: string; permissions?: Permissions }}",
+        if info.has_required_inputs { "" } else { "?" },
+        if info.has_required_secrets { "" } else { "?" },
+        name = info.interface_name,
+    )
+}
+
+/// Find reusable workflows that gaji itself built into `dir`.
+///
+/// Only files carrying gaji's header are considered, and workflows without
+/// `on.workflow_call` are skipped. Refs are returned in the local form
+/// `./.github/workflows/<file>` that `WorkflowCall` uses.
+pub async fn discover_local_workflows(dir: &Path) -> Result<Vec<(String, WorkflowCallMetadata)>> {
+    let mut workflows = Vec::new();
+    if !dir.is_dir() {
+        return Ok(workflows);
+    }
+
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yml" || ext == "yaml");
+        if !is_yaml {
+            continue;
+        }
+
+        // One unreadable file must not stop type generation for the rest
+        let content = match fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if !content.starts_with("# Auto-generated by gaji") {
+            continue;
+        }
+        let Ok(metadata) = WorkflowCallMetadata::from_yaml(&content) else {
+            continue;
+        };
+
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        workflows.push((format!("./.github/workflows/{}", file_name), metadata));
+    }
+
+    workflows.sort_by(|left, right| left.0.cmp(&right.0));
+    Ok(workflows)
+}
+
+/// Whether `error` only means that offline mode had no vendored or cached
+/// metadata, rather than that the metadata found is broken.
+fn is_offline_miss(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<FetchError>()
+        .is_some_and(FetchError::is_offline)
+}
+
+fn workflow_ref_to_filename(workflow_ref: &str) -> String {
+    // "./.github/workflows/deploy.yml" -> "github-workflows-deploy-yml.d.ts"
+    action_ref_to_filename(workflow_ref)
+        .trim_start_matches('-')
+        .to_string()
+}
+
+/// The ref named in the header of a type file gaji generated for it, if
+/// `content` is one.
+fn generated_type_ref(content: &str) -> Option<&str> {
+    let reference = content
+        .lines()
+        .next()?
+        .strip_prefix("// Auto-generated from ")?
+        .trim();
+    let is_ref = reference.starts_with("./") || ActionRef::parse(reference).is_ok();
+    is_ref.then_some(reference)
+}
+
+/// Name of the generated `.d.ts` file of an action or workflow ref.
+fn type_file_name(reference: &str) -> String {
+    if reference.starts_with("./") || is_reusable_workflow_ref(reference) {
+        workflow_ref_to_filename(reference)
+    } else {
+        action_ref_to_filename(reference)
+    }
+}
+
 pub fn action_ref_to_filename(action_ref: &str) -> String {
-    action_ref.replace(['/', '@', '.'], "-") + ".d.ts"
+    escape_ref(action_ref).replace(['/', '@', '.'], "-") + ".d.ts"
 }
 
 pub fn action_ref_to_interface_name(action_ref: &str) -> String {
     // "actions/checkout@v5" -> "ActionsCheckoutV5"
-    action_ref
+    let name: String = escape_ref(action_ref)
         .split(['/', '@', '-', '.'])
         .filter(|s| !s.is_empty())
         .map(|s| {
@@ -321,7 +783,49 @@ pub fn action_ref_to_interface_name(action_ref: &str) -> String {
                 Some(first) => first.to_uppercase().chain(chars).collect(),
             }
         })
-        .collect()
+        .collect();
+    // `$` is escaped everywhere else, so the prefix cannot collide
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("${}", name)
+    } else {
+        name
+    }
+}
+
+/// `reference` with every character other than ASCII letters, digits and
+/// the `/`, `@`, `-` and `.` separators hex-encoded as `_xx`, so it can be
+/// used in file names and identifiers. A `/` in the git ref is encoded too,
+/// keeping `owner/repo/feature@x` and `owner/repo@feature/x` apart.
+fn escape_ref(reference: &str) -> String {
+    let (location, git_ref) = match reference.split_once('@') {
+        Some((location, git_ref)) => (location, Some(git_ref)),
+        None => (reference, None),
+    };
+
+    let mut escaped = String::with_capacity(reference.len());
+    let mut push = |text: &str, separators: &[char]| {
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() || separators.contains(&c) {
+                escaped.push(c);
+            } else {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    escaped.push_str(&format!("_{:02x}", byte));
+                }
+            }
+        }
+    };
+    push(location, &['/', '-', '.']);
+    if let Some(git_ref) = git_ref {
+        push("@", &['@']);
+        push(git_ref, &['-', '.']);
+    }
+    escaped
+}
+
+/// `value` as a single-quoted JavaScript string literal.
+fn quote_str(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
 }
 
 fn action_ref_to_module_name(action_ref: &str) -> String {
@@ -352,6 +856,296 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_workflow_ref_to_filename() {
+        assert_eq!(
+            workflow_ref_to_filename("./.github/workflows/deploy.yml"),
+            "github-workflows-deploy-yml.d.ts"
+        );
+        assert_eq!(
+            workflow_ref_to_filename("org/repo/.github/workflows/deploy.yml@v1"),
+            "org-repo--github-workflows-deploy-yml-v1.d.ts"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_workflow_types() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let workflows_dir = temp.path().join("workflows");
+        std::fs::create_dir_all(&workflows_dir).unwrap();
+        std::fs::write(
+            workflows_dir.join("deploy.yml"),
+            r#"# Auto-generated by gaji
+# Do not edit manually - Edit workflows/deploy.ts instead
+
+name: Deploy
+on:
+  workflow_call:
+    inputs:
+      environment:
+        type: string
+        required: true
+    outputs:
+      url:
+        value: ${{ jobs.deploy.outputs.url }}
+jobs: {}
+"#,
+        )
+        .unwrap();
+        // Hand-written workflows and non-reusable ones are ignored
+        std::fs::write(
+            workflows_dir.join("manual.yml"),
+            "on:\n  workflow_call: {}\njobs: {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            workflows_dir.join("ci.yml"),
+            "# Auto-generated by gaji\non: push\njobs: {}\n",
+        )
+        .unwrap();
+        // Files that are not UTF-8 are skipped too
+        std::fs::write(workflows_dir.join("binary.yml"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let discovered = discover_local_workflows(&workflows_dir).await.unwrap();
+        assert_eq!(discovered.len(), 1);
+        assert_eq!(discovered[0].0, "./.github/workflows/deploy.yml");
+
+        let generated_dir = temp.path().join("generated");
+        let generator = TypeGenerator::new(
+            Cache::load_from(temp.path().join("cache.json")).unwrap(),
+            generated_dir.clone(),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_local_workflows(workflows_dir);
+        generator
+            .generate_types_for_refs(&HashSet::new())
+            .await
+            .unwrap();
+
+        assert!(generated_dir
+            .join("github-workflows-deploy-yml.d.ts")
+            .exists());
+        let index_dts = std::fs::read_to_string(generated_dir.join("index.d.ts")).unwrap();
+        assert!(index_dts.contains(
+            "'./.github/workflows/deploy.yml': { config: { with: GithubWorkflowsDeployYmlInputs;"
+        ));
+        let index_js = std::fs::read_to_string(generated_dir.join("index.js")).unwrap();
+        assert!(index_js.contains("'./.github/workflows/deploy.yml': ['url']"));
+    }
+
+    #[tokio::test]
+    async fn test_offline_generation_lists_missing_refs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = Cache::load_from(temp.path().join("cache.json")).unwrap();
+        // A cached "not found" is reported as such, not as missing metadata
+        cache
+            .record_miss(
+                "org/gone@v1",
+                &FetchError::RefNotFound {
+                    repo: "org/gone".to_string(),
+                    ref_: "v1".to_string(),
+                    host: "https://api.github.com".to_string(),
+                },
+            )
+            .unwrap();
+        let generator = TypeGenerator::new(
+            Cache::load_from(temp.path().join("cache.json")).unwrap(),
+            temp.path().join("generated"),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_offline(true);
+
+        let refs = HashSet::from([
+            "org/first@v1".to_string(),
+            "org/gone@v1".to_string(),
+            "org/repo/.github/workflows/deploy.yml@v1".to_string(),
+        ]);
+        let err = generator
+            .generate_types_for_refs(&refs)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("2 ref(s)"));
+        assert!(!err.contains("org/gone@v1"));
+        assert!(err.contains("  - org/first@v1"));
+        assert!(err.contains("  - org/repo/.github/workflows/deploy.yml@v1"));
+    }
+
+    #[tokio::test]
+    async fn test_index_keeps_previously_generated_refs() {
+        use crate::source::MemorySource;
+        use std::sync::Arc;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let generated_dir = temp.path().join("generated");
+        let memory = MemorySource::new()
+            .with_file(
+                "org/build@v1",
+                "action.yml",
+                "name: Build\noutputs:\n  artifact:\n    description: Path\n",
+            )
+            .with_file("org/lint@v2", "action.yml", "name: Lint\n");
+        let generator = TypeGenerator::new(
+            Cache::load_from(temp.path().join("cache.json")).unwrap(),
+            generated_dir.clone(),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_sources(SourceRouter::new(Arc::new(memory)));
+
+        for reference in ["org/build@v1", "org/lint@v2"] {
+            generator
+                .generate_types_for_refs(&HashSet::from([reference.to_string()]))
+                .await
+                .unwrap();
+        }
+
+        let index_dts = std::fs::read_to_string(generated_dir.join("index.d.ts")).unwrap();
+        assert!(index_dts.contains("ref: 'org/build@v1'"));
+        assert!(index_dts.contains("ref: 'org/lint@v2'"));
+        let index_js = std::fs::read_to_string(generated_dir.join("index.js")).unwrap();
+        assert!(index_js.contains("'org/build@v1': ['artifact']"));
+
+        // Entries whose type file was deleted are dropped from the index
+        std::fs::remove_file(generated_dir.join("org-build-v1.d.ts")).unwrap();
+        generator
+            .generate_types_for_refs(&HashSet::new())
+            .await
+            .unwrap();
+        let index_dts = std::fs::read_to_string(generated_dir.join("index.d.ts")).unwrap();
+        assert!(!index_dts.contains("org/build@v1"));
+        assert!(index_dts.contains("ref: 'org/lint@v2'"));
+    }
+
+    #[tokio::test]
+    async fn test_add_without_manifest_keeps_existing_types() {
+        use crate::source::MemorySource;
+        use std::sync::Arc;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let generated_dir = temp.path().join("generated");
+        let memory = MemorySource::new()
+            .with_file(
+                "org/build@v1",
+                "action.yml",
+                "name: Build\noutputs:\n  artifact:\n    description: Path\n",
+            )
+            .with_file("org/lint@v2", "action.yml", "name: Lint\n");
+        let generator = TypeGenerator::new(
+            Cache::load_from(temp.path().join("cache.json")).unwrap(),
+            generated_dir.clone(),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_sources(SourceRouter::new(Arc::new(memory)));
+
+        // Types generated by a gaji version without the manifest
+        generator
+            .generate_types_for_refs(&HashSet::from(["org/build@v1".to_string()]))
+            .await
+            .unwrap();
+        std::fs::remove_file(generated_dir.join("manifest.json")).unwrap();
+        std::fs::write(generated_dir.join("custom.d.ts"), "export {};\n").unwrap();
+
+        generator
+            .generate_types_for_refs(&HashSet::from(["org/lint@v2".to_string()]))
+            .await
+            .unwrap();
+
+        let index_dts = std::fs::read_to_string(generated_dir.join("index.d.ts")).unwrap();
+        assert!(index_dts.contains("ref: 'org/build@v1'"));
+        assert!(index_dts.contains("ref: 'org/lint@v2'"));
+        let index_js = std::fs::read_to_string(generated_dir.join("index.js")).unwrap();
+        assert!(index_js.contains("'org/build@v1': ['artifact']"));
+        assert!(generated_dir.join("manifest.json").exists());
+        assert!(generated_dir.join("custom.d.ts").exists());
+    }
+
+    #[tokio::test]
+    async fn test_unusual_refs_generate_valid_typescript() {
+        use crate::source::MemorySource;
+        use oxc_allocator::Allocator;
+        use oxc_parser::Parser;
+        use oxc_semantic::SemanticBuilder;
+        use oxc_span::SourceType;
+        use std::sync::Arc;
+
+        let refs = [
+            "org/tool/lint+fix@feature/x+y",
+            "org/tool/it's@v1",
+            "org/tool/feature@x",
+            "org/tool@feature/x",
+            "0rg/tool@v1",
+        ];
+        let mut memory = MemorySource::new();
+        for reference in refs {
+            let action = ActionRef::parse(reference).unwrap();
+            let file = match &action.path {
+                Some(path) => format!("{}/action.yml", path),
+                None => "action.yml".to_string(),
+            };
+            memory = memory.with_file(
+                &format!("{}/{}@{}", action.owner, action.repo, action.ref_),
+                &file,
+                "name: Tool\noutputs:\n  result:\n    description: Result\n",
+            );
+        }
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let generated_dir = temp.path().join("generated");
+        let generator = TypeGenerator::new(
+            Cache::load_from(temp.path().join("cache.json")).unwrap(),
+            generated_dir.clone(),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_sources(SourceRouter::new(Arc::new(memory)));
+        generator
+            .generate_types_for_refs(&refs.iter().map(|r| r.to_string()).collect())
+            .await
+            .unwrap();
+        // No two refs share a type file or interface name
+        let files: HashSet<String> = refs.iter().map(|r| action_ref_to_filename(r)).collect();
+        assert_eq!(files.len(), refs.len());
+        let names: HashSet<String> = refs
+            .iter()
+            .map(|r| action_ref_to_interface_name(r))
+            .collect();
+        assert_eq!(names.len(), refs.len());
+        assert_eq!(
+            action_ref_to_filename("org/tool/lint+fix@feature/x+y"),
+            "org-tool-lint_2bfix-feature_2fx_2by.d.ts"
+        );
+
+        let assert_valid = |file: &str, source_type: SourceType| {
+            let source = std::fs::read_to_string(generated_dir.join(file)).unwrap();
+            let allocator = Allocator::default();
+            let parsed = Parser::new(&allocator, &source, source_type).parse();
+            assert!(parsed.errors.is_empty(), "{}: {:?}", file, parsed.errors);
+            let semantic = SemanticBuilder::new()
+                .with_check_syntax_error(true)
+                .build(&parsed.program);
+            assert!(
+                semantic.errors.is_empty(),
+                "{}: {:?}",
+                file,
+                semantic.errors
+            );
+        };
+        assert_valid("index.d.ts", SourceType::d_ts());
+        assert_valid("index.js", SourceType::mjs());
+        for reference in refs {
+            assert_valid(&action_ref_to_filename(reference), SourceType::d_ts());
+        }
+    }
+
     #[test]
     fn test_action_ref_to_interface_name() {
         assert_eq!(
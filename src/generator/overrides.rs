@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use crate::config::TypeOverride;
+
+/// Curated type overrides for popular actions, compiled into the binary.
+const BUNDLED_OVERRIDES_JSON: &str = include_str!("overrides.json");
+
+#[derive(Debug, Deserialize)]
+struct BundledOverrides {
+    version: u32,
+    actions: HashMap<String, TypeOverride>,
+}
+
+static BUNDLED: LazyLock<BundledOverrides> = LazyLock::new(|| {
+    serde_json::from_str(BUNDLED_OVERRIDES_JSON).expect("bundled overrides.json is valid")
+});
+
+/// Version of the bundled overrides dataset.
+pub fn bundled_version() -> u32 {
+    BUNDLED.version
+}
+
+/// Action refs covered by the bundled dataset.
+pub fn bundled_refs() -> Vec<&'static str> {
+    let mut refs: Vec<&str> = BUNDLED.actions.keys().map(String::as_str).collect();
+    refs.sort();
+    refs
+}
+
+/// Find the bundled override for an action ref.
+///
+/// Entries are keyed by major version, so `actions/cache@v4.2.0` uses the
+/// `actions/cache@v4` entry. Commit SHAs and branches have no bundled types.
+pub fn bundled_override(action_ref: &str) -> Option<&'static TypeOverride> {
+    if let Some(entry) = BUNDLED.actions.get(action_ref) {
+        return Some(entry);
+    }
+
+    let (name, version) = action_ref.split_once('@')?;
+    let major = version.split('.').next()?;
+    BUNDLED.actions.get(&format!("{}@{}", name, major))
+}
+
+/// Merge the bundled override with a project-local one; local entries win.
+pub fn resolve_override(action_ref: &str, local: Option<&TypeOverride>) -> Option<TypeOverride> {
+    match (bundled_override(action_ref), local) {
+        (None, None) => None,
+        (bundled, local) => {
+            let mut merged = bundled.cloned().unwrap_or_default();
+            if let Some(local) = local {
+                merged.merge(local);
+            }
+            Some(merged)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_overrides_parse() {
+        assert_eq!(bundled_version(), 1);
+        assert!(bundled_refs().contains(&"actions/setup-node@v4"));
+    }
+
+    #[test]
+    fn test_bundled_override_matches_major_version() {
+        let exact = bundled_override("actions/cache@v4").unwrap();
+        let minor = bundled_override("actions/cache@v4.2.0").unwrap();
+        assert_eq!(exact, minor);
+        assert!(bundled_override("actions/cache@main").is_none());
+        assert!(bundled_override("someone/else@v1").is_none());
+    }
+
+    #[test]
+    fn test_local_override_takes_precedence() {
+        let local = TypeOverride {
+            inputs: HashMap::from([("cache".to_string(), "'npm'".to_string())]),
+            ..Default::default()
+        };
+        let merged = resolve_override("actions/setup-node@v4", Some(&local)).unwrap();
+        assert_eq!(merged.inputs["cache"], "'npm'");
+        // Bundled entries not touched by the local override are kept
+        assert_eq!(merged.inputs["node-version"], "string");
+        assert_eq!(merged.outputs["cache-hit"], "'true' | 'false'");
+
+        assert!(resolve_override("someone/else@v1", None).is_none());
+    }
+}
@@ -26,8 +26,11 @@ export type JobOutputs<T extends Record<string, string>> = {
     readonly [K in keyof T]: string;
 };
 
+export type RunsOn = string | string[] | { group?: string; labels?: string | string[] };
+
 export interface JobDefinition {
-    'runs-on': string | string[];
+    name?: string;
+    'runs-on': RunsOn;
     needs?: string | string[];
     if?: string;
     steps: JobStep[];
@@ -132,6 +135,7 @@ export interface WorkflowOn {
 
 export interface WorkflowConfig {
     name?: string;
+    'run-name'?: string;
     on: WorkflowOn;
     env?: Record<string, string>;
     defaults?: {
@@ -214,27 +218,68 @@ export interface GajiConfig {
     github?: {
         token?: string;
         apiUrl?: string;
+        /** Tokens keyed by host name, e.g. `{ "codeberg.org": "..." }` */
+        tokens?: Record<string, string>;
+        /** Shell command that prints a token for the host in `GAJI_HOST` */
+        credentialHelper?: string;
+    };
+    types?: {
+        /** Per-action type corrections, keyed by `owner/repo@ref` or `owner/repo` */
+        overrides?: Record<string, { inputs?: Record<string, string>; outputs?: Record<string, string> }>;
+    };
+    /** Hosts to fetch actions from, keyed by `owner/repo`, `owner/*` or `*` */
+    sources?: Record<string, {
+        type: "github" | "gitea" | "forgejo" | "dir";
+        url?: string;
+        path?: string;
+        token?: string;
+    }>;
+    /** HTTP settings for fetching action metadata */
+    network?: {
+        proxy?: string;
+        noProxy?: string[];
+        /** PEM files with extra CA certificates */
+        caCerts?: string[];
+        clientCert?: string;
+        clientKey?: string;
+        /** Seconds, `0` for no limit */
+        timeout?: number;
+        connectTimeout?: number;
+        concurrency?: number;
     };
 }
 "#;
 
 pub const GET_ACTION_FALLBACK_DECL_TEMPLATE: &str = r#"
 export declare function getAction<T extends string>(ref: T): {
-    <Id extends string>(config: { id: Id; name?: string; with?: Record<string, unknown>; if?: string; env?: Record<string, string> }): ActionStep<Record<string, string>, Id>;
-    (config?: { name?: string; with?: Record<string, unknown>; id?: string; if?: string; env?: Record<string, string> }): JobStep;
+    <Id extends string>(config: { id: Id; name?: string; with?: Record<string, unknown>; if?: string; env?: Record<string, string>; 'continue-on-error'?: boolean; 'timeout-minutes'?: number }): ActionStep<Record<string, string>, Id>;
+    (config?: { name?: string; with?: Record<string, unknown>; id?: string; if?: string; env?: Record<string, string>; 'continue-on-error'?: boolean; 'timeout-minutes'?: number }): JobStep;
 };
 "#;
 
+pub const GET_WORKFLOW_FALLBACK_DECL_TEMPLATE: &str = r#"
+export declare function getWorkflow<T extends string>(ref: T): (config?: WorkflowCallConfig) => WorkflowCall;
+"#;
+
 pub const GET_ACTION_RUNTIME_TEMPLATE: &str = r#"
 export function getAction(ref) {
     return function(config) {
         if (config === undefined) config = {};
         var step = { uses: ref };
         if (config.name !== undefined) step.name = config.name;
-        if (config.with !== undefined) step.with = config.with;
+        if (config.with !== undefined) {
+            // List inputs are passed to actions as newline-separated strings
+            step.with = {};
+            for (var name in config.with) {
+                var value = config.with[name];
+                step.with[name] = Array.isArray(value) ? value.join("\n") : value;
+            }
+        }
         if (config.id !== undefined) step.id = config.id;
         if (config["if"] !== undefined) step["if"] = config["if"];
         if (config.env !== undefined) step.env = config.env;
+        if (config["continue-on-error"] !== undefined) step["continue-on-error"] = config["continue-on-error"];
+        if (config["timeout-minutes"] !== undefined) step["timeout-minutes"] = config["timeout-minutes"];
         step.outputs = {};
         var outputNames = __action_outputs[ref];
         if (outputNames && config.id) {
@@ -257,10 +302,19 @@ export function getAction(ref) {
 }
 "#;
 
+pub const GET_WORKFLOW_RUNTIME_TEMPLATE: &str = r#"
+export function getWorkflow(ref) {
+    return function(config) {
+        return new WorkflowCall(ref, config);
+    };
+}
+"#;
+
 pub const CLASS_DECLARATIONS_TEMPLATE: &str = r#"
 export interface JobConfig {
+    name?: string;
     permissions?: Permissions;
-    needs?: string[];
+    needs?: string | string[];
     strategy?: { matrix?: Record<string, unknown>; 'fail-fast'?: boolean; 'max-parallel'?: number };
     if?: string;
     environment?: string | { name: string; url?: string };
@@ -276,6 +330,7 @@ export interface JobConfig {
 type NotIn<Id extends string, Cx> = Id extends keyof Cx ? never : Id;
 
 export declare class StepBuilder<Cx = {}> {
+    add(steps: JobStep[]): StepBuilder<Cx>;
     add<Id extends string, StepO>(step: ActionStep<StepO, Id>): StepBuilder<Cx & Record<Id, StepO>>;
     add(step: JobStep): StepBuilder<Cx>;
     add<Id extends string, StepO>(stepFn: (output: Cx) => ActionStep<StepO, Id>): StepBuilder<Cx & Record<Id, StepO>>;
@@ -283,7 +338,7 @@ export declare class StepBuilder<Cx = {}> {
 }
 
 export declare class Job<Cx = {}, O extends Record<string, string> = {}> {
-    constructor(runsOn: string | string[], config?: JobConfig);
+    constructor(runsOn: RunsOn, config?: JobConfig);
     steps<NewCx>(callback: (s: StepBuilder<{}>) => StepBuilder<NewCx>): Job<NewCx, O>;
     outputs<T extends Record<string, string>>(outputs: T | ((output: Cx) => T)): Job<Cx, T>;
     toJSON(): JobDefinition;
@@ -291,11 +346,11 @@ export declare class Job<Cx = {}, O extends Record<string, string> = {}> {
 
 export declare class JobBuilder<Cx = {}> {
     add<Id extends string, O extends Record<string, string>>(
-        id: NotIn<Id, Cx>, job: Job<any, O>
+        id: NotIn<Id, Cx>, job: Job<any, O> | WorkflowCall<any, O>
     ): JobBuilder<Cx & Record<Id, O>>;
     add<Id extends string>(id: NotIn<Id, Cx>, job: Job | WorkflowCall): JobBuilder<Cx>;
     add<Id extends string, O extends Record<string, string>>(
-        id: NotIn<Id, Cx>, jobFn: (output: Cx) => Job<any, O>
+        id: NotIn<Id, Cx>, jobFn: (output: Cx) => Job<any, O> | WorkflowCall<any, O>
     ): JobBuilder<Cx & Record<Id, O>>;
     add<Id extends string>(id: NotIn<Id, Cx>, jobFn: (output: Cx) => Job | WorkflowCall): JobBuilder<Cx>;
 }
@@ -328,8 +383,28 @@ export declare class DockerAction {
     build(id?: string): void;
 }
 
-export declare class WorkflowCall {
-    constructor(uses: string, config?: { with?: Record<string, unknown>; secrets?: Record<string, unknown> | 'inherit'; needs?: string[]; if?: string; permissions?: Permissions });
+export interface WorkflowCallConfig {
+    name?: string;
+    with?: Record<string, unknown>;
+    secrets?: Record<string, unknown> | 'inherit';
+    needs?: string | string[];
+    if?: string;
+    permissions?: Permissions;
+    strategy?: { matrix?: Record<string, unknown>; 'fail-fast'?: boolean; 'max-parallel'?: number };
+    concurrency?: { group: string; 'cancel-in-progress'?: boolean } | string;
+}
+
+type WorkflowCallConfigOf<U extends string> = U extends keyof WorkflowCallRegistry
+    ? WorkflowCallRegistry[U] extends { config: infer C } ? C : never
+    : WorkflowCallConfig;
+
+type WorkflowCallOutputsOf<U extends string> = U extends keyof WorkflowCallRegistry
+    ? WorkflowCallRegistry[U] extends { outputs: infer O } ? O : {}
+    : {};
+
+export declare class WorkflowCall<U extends string = string, O = WorkflowCallOutputsOf<U>> {
+    constructor(uses: U, config?: WorkflowCallConfigOf<U>);
+    readonly _outputs: O;
     toJSON(): object;
 }
 
@@ -361,6 +436,12 @@ export class StepBuilder {
         } else {
             step = stepOrFn;
         }
+        if (Array.isArray(step)) {
+            for (var i = 0; i < step.length; i++) {
+                this.add(step[i]);
+            }
+            return this;
+        }
         this._steps.push(step);
         if (step.id && step.outputs) {
             var outputs = step.outputs;
@@ -377,6 +458,7 @@ export class StepBuilder {
 export class Job {
     constructor(runsOn, config) {
         if (config === undefined) config = {};
+        this._name = config.name;
         this._runsOn = runsOn;
         this._steps = [];
         this._ctx = {};
@@ -413,10 +495,10 @@ export class Job {
     }
 
     toJSON() {
-        var obj = {
-            "runs-on": this._runsOn,
-            steps: this._steps,
-        };
+        var obj = {};
+        if (this._name !== undefined) obj.name = this._name;
+        obj["runs-on"] = this._runsOn;
+        obj.steps = this._steps;
         if (this._needs !== undefined) obj.needs = this._needs;
         if (this._env !== undefined) obj.env = this._env;
         if (this._if !== undefined) obj["if"] = this._if;
@@ -463,6 +545,7 @@ export class JobBuilder {
 export class Workflow {
     constructor(config) {
         this._name = config.name;
+        this._runName = config["run-name"];
         this._on = config.on;
         this._env = config.env;
         this._defaults = config.defaults;
@@ -488,6 +571,7 @@ export class Workflow {
         if (this.__rawDef) return this.__rawDef;
         var obj = {};
         if (this._name !== undefined) obj.name = this._name;
+        if (this._runName !== undefined) obj["run-name"] = this._runName;
         obj.on = this._on;
         if (this._env !== undefined) obj.env = this._env;
         if (this._defaults !== undefined) obj.defaults = this._defaults;
@@ -559,21 +643,35 @@ export class Action {
 export class WorkflowCall {
     constructor(uses, config) {
         if (config === undefined) config = {};
+        this._name = config.name;
         this._uses = uses;
         this._with = config["with"];
         this._secrets = config.secrets;
         this._needs = config.needs;
         this._if = config["if"];
         this._permissions = config.permissions;
+        this._strategy = config.strategy;
+        this._concurrency = config.concurrency;
+        var outputNames = typeof __workflow_outputs !== "undefined" ? __workflow_outputs[uses] : undefined;
+        if (outputNames) {
+            this._outputs = {};
+            for (var i = 0; i < outputNames.length; i++) {
+                this._outputs[outputNames[i]] = outputNames[i];
+            }
+        }
     }
 
     toJSON() {
-        var obj = { uses: this._uses };
+        var obj = {};
+        if (this._name !== undefined) obj.name = this._name;
+        obj.uses = this._uses;
         if (this._with !== undefined) obj["with"] = this._with;
         if (this._secrets !== undefined) obj.secrets = this._secrets;
         if (this._needs !== undefined) obj.needs = this._needs;
         if (this._if !== undefined) obj["if"] = this._if;
         if (this._permissions !== undefined) obj.permissions = this._permissions;
+        if (this._strategy !== undefined) obj.strategy = this._strategy;
+        if (this._concurrency !== undefined) obj.concurrency = this._concurrency;
         return obj;
     }
 }
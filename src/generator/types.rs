@@ -1,9 +1,22 @@
 use super::action_ref_to_interface_name;
-use crate::fetcher::{ActionInput, ActionMetadata};
+use super::overrides::resolve_override;
+use crate::config::TypeOverride;
+use crate::fetcher::{ActionInput, ActionMetadata, WorkflowCallMetadata};
 
-pub fn generate_type_definition(action_ref: &str, metadata: &ActionMetadata) -> String {
+/// Accepts any `${{ ... }}` expression in place of a narrower input type
+const EXPRESSION_TYPE: &str = r"`\${{${string}}}`";
+
+pub fn generate_type_definition(
+    action_ref: &str,
+    metadata: &ActionMetadata,
+    overrides: Option<&TypeOverride>,
+) -> String {
     let interface_name = action_ref_to_interface_name(action_ref);
 
+    // Bundled community overrides, with project-local ones layered on top
+    let merged_overrides = resolve_override(action_ref, overrides);
+    let overrides = merged_overrides.as_ref();
+
     let mut output = String::new();
 
     // Header comment
@@ -16,14 +29,14 @@ pub fn generate_type_definition(action_ref: &str, metadata: &ActionMetadata) ->
     output.push_str("import type { JobStep } from './base';\n\n");
 
     // Generate interface for inputs
-    let inputs_interface = generate_inputs_interface(&interface_name, metadata);
+    let inputs_interface = generate_inputs_interface(&interface_name, metadata, overrides);
     output.push_str(&inputs_interface);
     output.push_str("\n\n");
 
     // Generate outputs interface if present
     if let Some(outputs) = &metadata.outputs {
         if !outputs.is_empty() {
-            let outputs_interface = generate_outputs_interface(&interface_name, outputs);
+            let outputs_interface = generate_outputs_interface(&interface_name, outputs, overrides);
             output.push_str(&outputs_interface);
             output.push_str("\n\n");
         }
@@ -32,7 +45,111 @@ pub fn generate_type_definition(action_ref: &str, metadata: &ActionMetadata) ->
     output
 }
 
-fn generate_inputs_interface(interface_name: &str, metadata: &ActionMetadata) -> String {
+pub fn generate_workflow_type_definition(
+    workflow_ref: &str,
+    metadata: &WorkflowCallMetadata,
+) -> String {
+    let interface_name = action_ref_to_interface_name(workflow_ref);
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "// Auto-generated from {}\n// Do not edit manually\n\n",
+        workflow_ref
+    ));
+
+    // Inputs
+    output.push_str(&format!(
+        "/**\n * Inputs of reusable workflow {}\n */\n",
+        metadata.name.as_deref().unwrap_or(workflow_ref)
+    ));
+    output.push_str(&format!("export interface {}Inputs {{\n", interface_name));
+    for (name, input) in &metadata.inputs {
+        output.push_str(&generate_jsdoc(
+            input.description.as_deref(),
+            input.default.as_deref(),
+        ));
+        let optional_marker = if input.required.unwrap_or(false) {
+            ""
+        } else {
+            "?"
+        };
+        let field_type = match input.input_type.as_deref() {
+            Some("boolean") => "boolean",
+            Some("number") => "number",
+            _ => "string",
+        };
+        output.push_str(&format!(
+            "    {}{}: {};\n",
+            quote_field_name(name),
+            optional_marker,
+            field_type
+        ));
+    }
+    output.push_str("}\n\n");
+
+    // Secrets
+    output.push_str(&format!("export interface {}Secrets {{\n", interface_name));
+    for (name, secret) in &metadata.secrets {
+        output.push_str(&generate_jsdoc(secret.description.as_deref(), None));
+        let optional_marker = if secret.required.unwrap_or(false) {
+            ""
+        } else {
+            "?"
+        };
+        output.push_str(&format!(
+            "    {}{}: string;\n",
+            quote_field_name(name),
+            optional_marker
+        ));
+    }
+    output.push_str("}\n\n");
+
+    // Outputs use a type alias so they satisfy `Record<string, string>`
+    output.push_str(&format!("export type {}Outputs = {{\n", interface_name));
+    for (name, workflow_output) in &metadata.outputs {
+        output.push_str(&generate_jsdoc(
+            workflow_output.description.as_deref(),
+            None,
+        ));
+        output.push_str(&format!("    {}: string;\n", quote_field_name(name)));
+    }
+    output.push_str("};\n\n");
+
+    output
+}
+
+fn generate_jsdoc(description: Option<&str>, default: Option<&str>) -> String {
+    if description.is_none() && default.is_none() {
+        return String::new();
+    }
+
+    let mut output = String::from("    /**\n");
+    if let Some(desc) = description {
+        for line in desc.lines() {
+            output.push_str(&format!("     * {}\n", line.trim()));
+        }
+    }
+    if let Some(default) = default {
+        output.push_str(&format!("     * @default {}\n", default));
+    }
+    output.push_str("     */\n");
+    output
+}
+
+fn quote_field_name(name: &str) -> String {
+    if name.contains('-') || name.contains('.') {
+        format!("'{}'", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn generate_inputs_interface(
+    interface_name: &str,
+    metadata: &ActionMetadata,
+    overrides: Option<&TypeOverride>,
+) -> String {
     let mut output = String::new();
 
     // JSDoc for the interface
@@ -48,8 +165,13 @@ fn generate_inputs_interface(interface_name: &str, metadata: &ActionMetadata) ->
     output.push_str(&format!("export interface {}Inputs {{\n", interface_name));
 
     if let Some(inputs) = &metadata.inputs {
-        for (name, input) in inputs {
-            output.push_str(&generate_input_field(name, input));
+        let mut names: Vec<&String> = inputs.keys().collect();
+        names.sort();
+
+        for name in names {
+            let input = &inputs[name];
+            let override_type = overrides.and_then(|o| o.inputs.get(name));
+            output.push_str(&generate_input_field(name, input, override_type));
         }
     }
 
@@ -58,7 +180,7 @@ fn generate_inputs_interface(interface_name: &str, metadata: &ActionMetadata) ->
     output
 }
 
-fn generate_input_field(name: &str, input: &ActionInput) -> String {
+fn generate_input_field(name: &str, input: &ActionInput, override_type: Option<&String>) -> String {
     let mut output = String::new();
 
     // JSDoc for the field
@@ -79,18 +201,16 @@ fn generate_input_field(name: &str, input: &ActionInput) -> String {
     // Field declaration
     let is_required = input.required.unwrap_or(false);
     let optional_marker = if is_required { "" } else { "?" };
-    let field_type = infer_type_from_input(input);
-
-    // Handle field names with special characters
-    let field_name = if name.contains('-') || name.contains('.') {
-        format!("'{}'", name)
-    } else {
-        name.to_string()
+    let field_type = match override_type {
+        Some(field_type) => field_type.clone(),
+        None => infer_type_from_input(input),
     };
 
     output.push_str(&format!(
         "    {}{}: {};\n",
-        field_name, optional_marker, field_type
+        quote_field_name(name),
+        optional_marker,
+        field_type
     ));
 
     output
@@ -99,12 +219,18 @@ fn generate_input_field(name: &str, input: &ActionInput) -> String {
 fn generate_outputs_interface(
     interface_name: &str,
     outputs: &std::collections::HashMap<String, crate::fetcher::ActionOutput>,
+    overrides: Option<&TypeOverride>,
 ) -> String {
     let mut output = String::new();
 
     output.push_str(&format!("export interface {}Outputs {{\n", interface_name));
 
-    for (name, action_output) in outputs {
+    let mut names: Vec<&String> = outputs.keys().collect();
+    names.sort();
+
+    for name in names {
+        let action_output = &outputs[name];
+
         // JSDoc
         if let Some(desc) = &action_output.description {
             output.push_str("    /**\n");
@@ -114,14 +240,16 @@ fn generate_outputs_interface(
             output.push_str("     */\n");
         }
 
-        // Handle field names with special characters
-        let field_name = if name.contains('-') || name.contains('.') {
-            format!("'{}'", name)
-        } else {
-            name.to_string()
-        };
+        let field_type = overrides
+            .and_then(|o| o.outputs.get(name))
+            .map(String::as_str)
+            .unwrap_or("string");
 
-        output.push_str(&format!("    {}: string;\n", field_name));
+        output.push_str(&format!(
+            "    {}: {};\n",
+            quote_field_name(name),
+            field_type
+        ));
     }
 
     output.push('}');
@@ -129,27 +257,176 @@ fn generate_outputs_interface(
     output
 }
 
-fn infer_type_from_input(input: &ActionInput) -> &'static str {
-    // Try to infer type from default value
+/// Infer a TypeScript type for an action input.
+///
+/// action.yml carries no type information, so this looks at the default value
+/// and the description. Non-string types always stay assignable from `string`
+/// (or a `${{ }}` expression for literal unions) so expressions keep working.
+fn infer_type_from_input(input: &ActionInput) -> String {
+    let description = input.description.as_deref().unwrap_or_default();
+
     if let Some(default) = &input.default {
-        let default_lower = default.to_lowercase();
+        let default_lower = default.trim().to_lowercase();
 
         // Boolean detection
         if default_lower == "true" || default_lower == "false" {
-            return "boolean";
+            return "boolean | string".to_string();
         }
 
-        // Number detection (simple check)
-        if default.parse::<i64>().is_ok() || default.parse::<f64>().is_ok() {
-            // But if it looks like a version, keep as string
-            if !default.contains('.') || default.parse::<f64>().is_ok() {
-                return "number";
-            }
+        // Number detection: plain integers only, so versions like "1.10" stay strings
+        if is_integer_literal(default.trim()) {
+            return "number | string".to_string();
+        }
+    }
+
+    if let Some(choices) = detect_choices(description) {
+        // A default outside the detected choices means the guess is wrong
+        let default_matches = input
+            .default
+            .as_deref()
+            .is_none_or(|d| d.is_empty() || choices.iter().any(|c| c == d));
+        if default_matches {
+            let mut variants: Vec<String> = choices.iter().map(|c| format!("'{}'", c)).collect();
+            variants.push(EXPRESSION_TYPE.to_string());
+            return variants.join(" | ");
         }
     }
 
+    if is_list_input(description) {
+        return "string | string[]".to_string();
+    }
+
     // Default to string
-    "string"
+    "string".to_string()
+}
+
+fn is_integer_literal(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+}
+
+/// Phrases that introduce an enumerated set of values in action descriptions
+const CHOICE_MARKERS: &[&str] = &[
+    "one of:",
+    "one of",
+    "supported values are",
+    "supported values:",
+    "possible values are",
+    "possible values:",
+    "valid values are",
+    "valid values:",
+    "allowed values are",
+    "allowed values:",
+    "options are",
+    "options:",
+    "either",
+];
+
+/// Detect an enumerated list of values such as "one of: npm, yarn, pnpm" or
+/// "Either `auto` or `manual`". Returns `None` unless at least two values are found.
+fn detect_choices(description: &str) -> Option<Vec<String>> {
+    for marker in CHOICE_MARKERS {
+        let Some(start) = find_marker(description, marker) else {
+            continue;
+        };
+        // Only the rest of the sentence/line after the marker
+        let rest = &description[start + marker.len()..];
+        let end = rest
+            .char_indices()
+            .find(|&(i, c)| c == '\n' || (c == '.' && !next_is_word_char(rest, i)))
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let clause = &rest[..end];
+
+        let quoted = extract_quoted(clause);
+        let candidates = if quoted.len() >= 2 {
+            quoted
+        } else {
+            clause
+                .split([',', '|', '/'])
+                .flat_map(|part| part.split(" or "))
+                .map(|part| {
+                    part.trim()
+                        .trim_start_matches("and ")
+                        .trim_start_matches("or ")
+                        .trim_matches(|c: char| c == '"' || c == '\'' || c == '(' || c == ')')
+                        .trim()
+                        .to_string()
+                })
+                .filter(|part| !part.is_empty())
+                .collect()
+        };
+
+        if candidates.len() >= 2 && candidates.iter().all(|c| is_choice_token(c)) {
+            let mut choices = Vec::new();
+            for candidate in candidates {
+                if !choices.contains(&candidate) {
+                    choices.push(candidate);
+                }
+            }
+            return Some(choices);
+        }
+    }
+
+    None
+}
+
+/// Byte offset of `marker` in `text`, matched case-insensitively as whole
+/// words, so "one of" does not match inside "none of".
+fn find_marker(text: &str, marker: &str) -> Option<usize> {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    text.char_indices().map(|(i, _)| i).find(|&i| {
+        let Some(candidate) = text.get(i..i + marker.len()) else {
+            return false;
+        };
+        candidate.eq_ignore_ascii_case(marker)
+            && !is_word(text[..i].chars().next_back())
+            && !(marker.ends_with(|c: char| c.is_alphanumeric())
+                && is_word(text[i + marker.len()..].chars().next()))
+    })
+}
+
+fn next_is_word_char(text: &str, index: usize) -> bool {
+    text[index + 1..]
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric())
+}
+
+/// Values wrapped in backticks or double quotes
+fn extract_quoted(text: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    for quote in ['`', '"'] {
+        let parts: Vec<&str> = text.split(quote).collect();
+        if parts.len() >= 5 {
+            values.extend(parts.iter().skip(1).step_by(2).map(|v| v.to_string()));
+            break;
+        }
+    }
+    values
+}
+
+fn is_choice_token(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 40
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+}
+
+fn is_list_input(description: &str) -> bool {
+    let lower = description.to_lowercase();
+    [
+        "multi-line",
+        "multiline",
+        "newline-separated",
+        "newline separated",
+        "new line",
+    ]
+    .iter()
+    .any(|marker| lower.contains(marker))
 }
 
 #[cfg(test)]
@@ -178,13 +455,54 @@ mod tests {
             runs: None,
         };
 
-        let result = generate_type_definition("actions/checkout@v5", &metadata);
+        let result = generate_type_definition("actions/checkout@v5", &metadata, None);
 
         assert!(result.contains("ActionsCheckoutV5Inputs"));
         assert!(result.contains("repository?:"));
         assert!(result.contains("@default"));
     }
 
+    #[test]
+    fn test_generate_workflow_type_definition() {
+        let yaml = r#"
+name: Deploy
+on:
+  workflow_call:
+    inputs:
+      environment:
+        type: string
+        required: true
+      dry-run:
+        type: boolean
+      replicas:
+        type: number
+    secrets:
+      token:
+        required: true
+      extra:
+        description: Optional secret
+    outputs:
+      url:
+        value: ${{ jobs.deploy.outputs.url }}
+jobs: {}
+"#;
+        let metadata = WorkflowCallMetadata::from_yaml(yaml).unwrap();
+        let result = generate_workflow_type_definition(
+            "org/repo/.github/workflows/deploy.yml@v1",
+            &metadata,
+        );
+
+        assert!(result.contains("export interface OrgRepoGithubWorkflowsDeployYmlV1Inputs {"));
+        assert!(result.contains("    environment: string;"));
+        assert!(result.contains("    'dry-run'?: boolean;"));
+        assert!(result.contains("    replicas?: number;"));
+        assert!(result.contains("export interface OrgRepoGithubWorkflowsDeployYmlV1Secrets {"));
+        assert!(result.contains("    token: string;"));
+        assert!(result.contains("    extra?: string;"));
+        assert!(result.contains("export type OrgRepoGithubWorkflowsDeployYmlV1Outputs = {"));
+        assert!(result.contains("    url: string;"));
+    }
+
     #[test]
     fn test_infer_boolean_type() {
         let input = ActionInput {
@@ -193,7 +511,7 @@ mod tests {
             default: Some("true".to_string()),
             deprecation_message: None,
         };
-        assert_eq!(infer_type_from_input(&input), "boolean");
+        assert_eq!(infer_type_from_input(&input), "boolean | string");
     }
 
     #[test]
@@ -204,6 +522,112 @@ mod tests {
             default: Some("42".to_string()),
             deprecation_message: None,
         };
-        assert_eq!(infer_type_from_input(&input), "number");
+        assert_eq!(infer_type_from_input(&input), "number | string");
+    }
+
+    fn input_with(description: Option<&str>, default: Option<&str>) -> ActionInput {
+        ActionInput {
+            description: description.map(str::to_string),
+            required: None,
+            default: default.map(str::to_string),
+            deprecation_message: None,
+        }
+    }
+
+    #[test]
+    fn test_infer_version_stays_string() {
+        assert_eq!(
+            infer_type_from_input(&input_with(None, Some("1.10"))),
+            "string"
+        );
+        assert_eq!(
+            infer_type_from_input(&input_with(None, Some("3.0"))),
+            "string"
+        );
+        assert_eq!(
+            infer_type_from_input(&input_with(None, Some("020"))),
+            "string"
+        );
+        assert_eq!(
+            infer_type_from_input(&input_with(None, Some("-1"))),
+            "number | string"
+        );
+    }
+
+    #[test]
+    fn test_infer_choices_from_description() {
+        let input = input_with(
+            Some("Used to specify a package manager. Supported values: npm, yarn, pnpm."),
+            None,
+        );
+        assert_eq!(
+            infer_type_from_input(&input),
+            "'npm' | 'yarn' | 'pnpm' | `\\${{${string}}}`"
+        );
+
+        let input = input_with(Some("Either `auto` or `manual`"), Some("auto"));
+        assert_eq!(
+            infer_type_from_input(&input),
+            "'auto' | 'manual' | `\\${{${string}}}`"
+        );
+    }
+
+    #[test]
+    fn test_infer_choices_rejects_mismatched_default() {
+        // The default is not one of the detected values, so the guess is discarded
+        let input = input_with(Some("One of: low, high"), Some("medium"));
+        assert_eq!(infer_type_from_input(&input), "string");
+
+        // Free-form prose after the marker is not a list of values
+        let input = input_with(Some("Either the path to a file or a glob pattern"), None);
+        assert_eq!(infer_type_from_input(&input), "string");
+
+        // Markers only match whole words
+        let input = input_with(Some("None of: low, high"), None);
+        assert_eq!(infer_type_from_input(&input), "string");
+        let input = input_with(Some("Neither `auto` or `manual`"), None);
+        assert_eq!(infer_type_from_input(&input), "string");
+    }
+
+    #[test]
+    fn test_infer_choices_after_non_ascii_text() {
+        // Lowercasing "İ" changes its length; the match must not be offset by it
+        let input = input_with(Some("İİİ ONE OF: low, high"), None);
+        assert_eq!(
+            infer_type_from_input(&input),
+            "'low' | 'high' | `\\${{${string}}}`"
+        );
+    }
+
+    #[test]
+    fn test_infer_multiline_list() {
+        let input = input_with(Some("A multi-line list of paths to cache"), None);
+        assert_eq!(infer_type_from_input(&input), "string | string[]");
+    }
+
+    #[test]
+    fn test_type_override_wins() {
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "tool-version".to_string(),
+            input_with(Some("Version Spec of the version to use."), Some("20")),
+        );
+        let metadata = ActionMetadata {
+            name: "Setup Tool".to_string(),
+            description: None,
+            inputs: Some(inputs),
+            outputs: None,
+            runs: None,
+        };
+        let overrides = TypeOverride {
+            inputs: HashMap::from([("tool-version".to_string(), "string".to_string())]),
+            ..Default::default()
+        };
+
+        let inferred = generate_type_definition("example/setup-tool@v1", &metadata, None);
+        assert!(inferred.contains("'tool-version'?: number | string;"));
+
+        let result = generate_type_definition("example/setup-tool@v1", &metadata, Some(&overrides));
+        assert!(result.contains("'tool-version'?: string;"));
     }
 }
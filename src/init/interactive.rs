@@ -2,22 +2,29 @@ use std::path::Path;
 
 use anyhow::Result;
 use colored::Colorize;
-use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
 
 use super::migration;
-use super::{detect_project_state, InitOptions, ProjectState};
+use super::package_manager::{self, PackageManager, PACKAGE_MANAGER_NAMES};
+use super::template_source::TemplateSource;
+use super::templates;
+use super::{detect_project_state, suggest_template, InitOptions, ProjectState};
 use crate::config::{Config, ProjectConfig};
 
 pub struct InteractiveConfig {
     pub workflows_dir: String,
     pub output_dir: String,
     pub generated_dir: String,
-    pub create_example: bool,
-    pub update_package_json: bool,
+    /// Starter workflow to create, if any.
+    pub template: Option<TemplateSource>,
+    /// Package manager whose manifest gets the gaji scripts, if any.
+    pub package_manager: Option<PackageManager>,
     pub update_tsconfig: bool,
 }
 
-pub async fn interactive_init(root: &Path) -> Result<()> {
+/// Run the interactive setup. `--template` and `--package-manager` in
+/// `options` skip their prompts.
+pub async fn interactive_init(root: &Path, options: &InitOptions) -> Result<()> {
     println!("{} gaji Interactive Setup\n", "🚀".green());
 
     let project_state = detect_project_state(root)?;
@@ -139,60 +146,103 @@ pub async fn interactive_init(root: &Path) -> Result<()> {
         .default("generated".to_string())
         .interact_text()?;
 
-    let create_example = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Create example workflow?")
-        .default(true)
-        .interact()?;
+    let template = match options.template.as_deref() {
+        Some(spec) => Some(TemplateSource::parse(spec)?),
+        None => {
+            let suggested = suggest_template(root);
+            let mut items: Vec<String> = templates::WORKFLOW_TEMPLATES
+                .iter()
+                .map(|t| format!("{} - {}", t.name, t.description))
+                .collect();
+            items.push("none - skip the starter workflow".to_string());
+            let default = templates::WORKFLOW_TEMPLATES
+                .iter()
+                .position(|t| t.name == suggested)
+                .unwrap_or(0);
+
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Starter workflow")
+                .items(&items)
+                .default(default)
+                .interact()?;
+            templates::WORKFLOW_TEMPLATES
+                .get(selection)
+                .map(TemplateSource::Builtin)
+        }
+    };
 
-    let has_package_json = root.join("package.json").exists();
-    let update_package_json = if has_package_json {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Update package.json with gaji scripts?")
-            .default(true)
-            .interact()?
-    } else {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Create package.json with gaji scripts?")
-            .default(false)
-            .interact()?
+    let package_manager = match options.package_manager.as_deref() {
+        Some(name) => PackageManager::from_name(name)?,
+        None => match PackageManager::detect(root) {
+            Some(manager) => {
+                let manifest = if manager == PackageManager::Deno {
+                    "deno.json"
+                } else {
+                    "package.json"
+                };
+                let update = Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!(
+                        "Add gaji scripts to {} ({})?",
+                        manifest,
+                        manager.name()
+                    ))
+                    .default(true)
+                    .interact()?;
+                update.then_some(manager)
+            }
+            None => {
+                // "none" is last in the list; put it first as the default
+                let mut items = vec!["none - not a JavaScript project"];
+                items.extend(&PACKAGE_MANAGER_NAMES[..PACKAGE_MANAGER_NAMES.len() - 1]);
+                let selection = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Package manager for gaji scripts")
+                    .items(&items)
+                    .default(0)
+                    .interact()?;
+                match selection {
+                    0 => None,
+                    i => PackageManager::from_name(items[i])?,
+                }
+            }
+        },
     };
 
     let has_tsconfig = root.join("tsconfig.json").exists();
-    let update_tsconfig = if has_tsconfig {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Update tsconfig.json for TypeScript workflows?")
-            .default(false)
-            .interact()?
-    } else if update_package_json {
-        // Only ask about creating tsconfig if we're also creating/updating package.json
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Create tsconfig.json for TypeScript workflows?")
-            .default(true)
-            .interact()?
-    } else {
-        false
-    };
+    let update_tsconfig = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(if has_tsconfig {
+            "Update tsconfig.json for TypeScript workflows?"
+        } else {
+            "Create tsconfig.json for TypeScript workflows?"
+        })
+        .default(!has_tsconfig)
+        .interact()?;
 
     let interactive_config = InteractiveConfig {
         workflows_dir: workflows_dir.clone(),
         output_dir: output_dir.clone(),
         generated_dir: generated_dir.clone(),
-        create_example,
-        update_package_json,
+        template,
+        package_manager,
         update_tsconfig,
     };
 
     println!();
 
     // Execute migration if requested
+    let migrate_options = migration::MigrateOptions {
+        overwrite: true,
+        ..migration::MigrateOptions::new(root.join("workflows"))
+    };
+    let mut reports = Vec::new();
     if let Some(wfs) = &selected_workflows {
-        migration::migrate_workflows(root, wfs).await?;
+        reports.extend(migration::migrate_workflows(wfs, &migrate_options).await?);
     }
 
     // Execute action migration if requested
     if let Some(acts) = &selected_actions {
-        migration::migrate_actions(root, acts).await?;
+        reports.extend(migration::migrate_actions(acts, &migrate_options).await?);
     }
+    migration::print_migration_reports(&reports);
 
     // Create custom config with user-specified directories
     let config = Config {
@@ -229,75 +279,37 @@ export default defineConfig({{
     tokio::fs::create_dir_all(root.join(&interactive_config.output_dir).join("workflows")).await?;
     println!("{} Created project directories", "✓".green());
 
-    // Handle package.json if requested
-    if interactive_config.update_package_json {
-        if !root.join("package.json").exists() {
-            // Create new package.json
-            let package_json = serde_json::json!({
-                "name": root.file_name().unwrap_or_default().to_string_lossy(),
-                "version": "0.0.0",
-                "private": true,
-                "scripts": {
-                    "gha:dev": "gaji dev",
-                    "gha:build": "gaji build"
-                },
-                "devDependencies": {
-                    "tsx": "^4.0.0",
-                    "typescript": "^5.0.0"
-                }
-            });
-            tokio::fs::write(
-                root.join("package.json"),
-                serde_json::to_string_pretty(&package_json)? + "\n",
-            )
-            .await?;
-            println!("{} Created package.json", "✓".green());
-        } else {
-            // Update existing package.json
-            super::update_package_json(root).await?;
-        }
+    // Add the gaji scripts for the chosen package manager
+    if let Some(manager) = interactive_config.package_manager {
+        package_manager::update_manifest(root, manager).await?;
     }
 
     // Handle tsconfig.json if requested (separately from package.json)
     if interactive_config.update_tsconfig {
-        super::handle_tsconfig(
-            root,
-            &InitOptions {
-                force: false,
-                skip_examples: false,
-                migrate: false,
-                interactive: true,
-            },
-        )
-        .await?;
+        super::handle_tsconfig(root, options).await?;
     }
 
     // Handle gitignore
     super::ensure_gitignore(root, project_state != ProjectState::Empty).await?;
 
-    // Create example workflow if requested
-    if interactive_config.create_example {
-        let example_path = root.join(&interactive_config.workflows_dir).join("ci.ts");
-        if !example_path.exists() {
-            tokio::fs::write(&example_path, super::templates::EXAMPLE_WORKFLOW_TEMPLATE).await?;
-            println!(
-                "{} Created example workflow ({}/ci.ts)",
-                "✓".green(),
-                interactive_config.workflows_dir
-            );
-        } else {
-            println!(
-                "{} Example workflow already exists, skipping",
-                "⏭️ ".dimmed()
-            );
-        }
+    // Create the starter workflow if one was picked. The template's own
+    // gaji.config.ts is skipped since the directories were chosen above.
+    if let Some(template) = &interactive_config.template {
+        super::apply_template(
+            root,
+            template,
+            &root.join(&interactive_config.workflows_dir),
+            false,
+            false,
+        )
+        .await?;
     }
 
     // Try to generate types
-    super::try_generate_initial_types(root).await;
+    super::try_generate_initial_types(root, options.offline).await;
 
     println!("\n{} Project initialized!\n", "✨".green());
-    super::print_next_steps();
+    super::print_next_steps(interactive_config.package_manager);
 
     Ok(())
 }
@@ -1,9 +1,18 @@
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use colored::Colorize;
 
+use super::output_refs::{indent_js, OutputRefs};
+use super::shared_steps::{is_param_marker, SharedSteps};
+use super::verify::verify_migrated_workflow;
+use super::yaml_source::{step_path, YamlSource};
+
+/// First line of every file written by a migration.
+const MIGRATED_STAMP: &str = "// Migrated from YAML by gaji\n";
+
 /// Find all .yml/.yaml files in .github/workflows/
 pub fn discover_workflows(root: &Path) -> Result<Vec<PathBuf>> {
     let workflows_dir = root.join(".github/workflows");
@@ -24,6 +33,38 @@ pub fn discover_workflows(root: &Path) -> Result<Vec<PathBuf>> {
     Ok(workflows)
 }
 
+/// Whether a path names an action definition (`action.yml`/`action.yaml`).
+pub fn is_action_file(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|s| s.to_str()),
+        Some("action.yml" | "action.yaml")
+    )
+}
+
+/// Module specifier for `generated/index.js` as seen from files in `out_dir`.
+/// Both paths are relative to the project root.
+pub fn import_path(out_dir: &Path, generated_dir: &Path) -> String {
+    let normal = |path: &Path| -> Vec<String> {
+        path.components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect()
+    };
+    let from = normal(out_dir);
+    let to = normal(generated_dir);
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    if parts.is_empty() {
+        parts.push(".".to_string());
+    }
+    parts.extend(to[common..].iter().cloned());
+    parts.push("index.js".to_string());
+    parts.join("/")
+}
+
 /// Find all action.yml/.yaml files in .github/actions/*/
 pub fn discover_actions(root: &Path) -> Result<Vec<PathBuf>> {
     let actions_dir = root.join(".github/actions");
@@ -76,56 +117,199 @@ fn classify_action(yaml: &serde_yaml::Value) -> ActionType {
     }
 }
 
+/// Module specifier migrated files use to import gaji when they live in
+/// `workflows/` next to `generated/`.
+const DEFAULT_IMPORT_PATH: &str = "../generated/index.js";
+
+/// Module that `--dedupe` writes shared step helpers to, next to the
+/// migrated workflows.
+const SHARED_MODULE: &str = "shared";
+
+/// Options controlling where migrated files go and what happens to originals.
+#[derive(Debug, Clone)]
+pub struct MigrateOptions {
+    /// Directory the TypeScript files are written to.
+    pub out_dir: PathBuf,
+    /// Module specifier the migrated files import gaji from.
+    pub import_path: String,
+    /// Print the TypeScript instead of writing files.
+    pub dry_run: bool,
+    /// Rename originals to `.yml.backup` once they are migrated.
+    pub backup: bool,
+    /// Replace TypeScript files that already exist in `out_dir`.
+    pub overwrite: bool,
+    /// Extract step sequences repeated across workflows into `shared.ts`.
+    pub dedupe: bool,
+}
+
+impl MigrateOptions {
+    pub fn new(out_dir: PathBuf) -> Self {
+        Self {
+            out_dir,
+            import_path: DEFAULT_IMPORT_PATH.to_string(),
+            dry_run: false,
+            backup: true,
+            overwrite: false,
+            dedupe: false,
+        }
+    }
+}
+
+/// Constructs in one migrated file that need manual review.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub path: PathBuf,
+    pub notes: Vec<String>,
+}
+
 /// Migrate a list of workflow files to TypeScript.
-/// Creates .ts files in workflows/ and backs up originals.
-pub async fn migrate_workflows(root: &Path, workflows: &[PathBuf]) -> Result<()> {
+///
+/// Each migrated workflow is built in memory and compared with its YAML; the
+/// original is only backed up when both match.
+pub async fn migrate_workflows(
+    workflows: &[PathBuf],
+    options: &MigrateOptions,
+) -> Result<Vec<MigrationReport>> {
     println!("{} Migrating workflows to TypeScript...\n", "🔄".cyan());
 
-    let ts_dir = root.join("workflows");
-    tokio::fs::create_dir_all(&ts_dir).await?;
+    if !options.dry_run {
+        tokio::fs::create_dir_all(&options.out_dir).await?;
+    }
 
+    let mut sources = Vec::new();
     for workflow_path in workflows {
         let workflow_name = workflow_path
             .file_stem()
             .and_then(|s| s.to_str())
             .ok_or_else(|| anyhow!("Invalid workflow path: {}", workflow_path.display()))?;
+        let yaml_content = tokio::fs::read_to_string(workflow_path).await?;
+        sources.push((workflow_path, workflow_name, yaml_content));
+    }
 
-        println!("  Migrating {}...", workflow_name);
+    // Repeated step sequences go to shared.ts before any workflow imports it
+    let shared = if options.dedupe {
+        let parsed: Vec<(String, serde_yaml::Value)> = sources
+            .iter()
+            .filter_map(|(_, name, yaml)| {
+                Some((name.to_string(), serde_yaml::from_str(yaml).ok()?))
+            })
+            .collect();
+        SharedSteps::find(&parsed)
+    } else {
+        SharedSteps::default()
+    };
+    let mut modules = HashMap::new();
+    if !shared.is_empty() {
+        let shared_path = options.out_dir.join(format!("{}.ts", SHARED_MODULE));
+        if shared_path.exists() && !options.overwrite && !options.dry_run {
+            bail!(
+                "{} already exists; use --overwrite to replace it",
+                shared_path.display()
+            );
+        }
+        let shared_ts = generate_shared_steps_ts(&shared);
+        println!(
+            "  Extracted {} shared step sequence(s) into {}",
+            shared.helpers.len(),
+            shared_path.display()
+        );
+        write_migrated(&shared_path, &shared_ts, options).await?;
+        modules.insert(PathBuf::from(format!("{}.ts", SHARED_MODULE)), shared_ts);
+    }
 
-        let yaml_content = tokio::fs::read_to_string(workflow_path).await?;
+    let mut reports = Vec::new();
+    for (workflow_path, workflow_name, yaml_content) in sources {
+        println!("  Migrating {}...", workflow_name);
 
-        match generate_typescript_from_yaml(&yaml_content, workflow_name) {
-            Ok(ts_content) => {
-                let ts_path = ts_dir.join(format!("{}.ts", workflow_name));
-                tokio::fs::write(&ts_path, ts_content).await?;
-                println!("  {} Created {}", "✓".green(), ts_path.display());
-
-                // Backup original
-                let backup_path = workflow_path.with_extension("yml.backup");
-                tokio::fs::rename(workflow_path, &backup_path).await?;
-                println!("  {} Backed up to {}", "✓".green(), backup_path.display());
-            }
-            Err(e) => {
-                eprintln!("  {} Failed to migrate {}: {}", "✗".red(), workflow_name, e);
+        let ts_content =
+            match generate_typescript_from_yaml(&yaml_content, workflow_name, Some(&shared)) {
+                Ok(ts_content) => ts_content,
+                Err(e) => {
+                    eprintln!("  {} Failed to migrate {}: {}", "✗".red(), workflow_name, e);
+                    reports.push(MigrationReport {
+                        path: workflow_path.clone(),
+                        notes: vec![format!("not migrated: {}", e)],
+                    });
+                    continue;
+                }
+            };
+
+        let mut notes = serde_yaml::from_str(&yaml_content)
+            .map(|workflow| find_unsupported(&workflow))
+            .unwrap_or_default();
+
+        // Only retire the original once the migration reproduces it
+        let diffs = match verify_migrated_workflow(&ts_content, &yaml_content, &modules) {
+            Ok(diffs) => diffs,
+            Err(e) => vec![format!("{:#}", e)],
+        };
+
+        // A workflow that does not reproduce the original is saved as a
+        // draft, which `gaji build` ignores, so it cannot replace the
+        // original YAML before someone reviews it
+        let ts_path = if diffs.is_empty() {
+            options.out_dir.join(format!("{}.ts", workflow_name))
+        } else {
+            options.out_dir.join(format!("{}.ts.draft", workflow_name))
+        };
+        let written = write_migrated(&ts_path, &ts_content, options).await?;
+
+        if !diffs.is_empty() {
+            println!(
+                "  {} Migrated workflow differs from {}; original kept, migration saved as a draft:",
+                "⚠".yellow(),
+                workflow_path.display()
+            );
+            for diff in &diffs {
+                println!("      {}", diff);
             }
+            // Unsupported keys already explain their own differences
+            let covered: Vec<String> = notes
+                .iter()
+                .filter_map(|n| n.split_once(": ").map(|(path, _)| path.to_string()))
+                .collect();
+            notes.extend(
+                diffs
+                    .iter()
+                    .filter(|d| !covered.iter().any(|path| d.starts_with(path.as_str())))
+                    .map(|d| format!("differs from original: {}", d)),
+            );
+        } else if written && options.backup {
+            backup_original(workflow_path).await?;
+        }
+
+        if !notes.is_empty() {
+            reports.push(MigrationReport {
+                path: workflow_path.clone(),
+                notes,
+            });
         }
     }
 
-    println!("\n{} Migration complete!", "✨".green());
-    println!("   Review the generated TypeScript files in workflows/");
-    println!("   Run 'gaji build' to regenerate YAML files\n");
+    if !options.dry_run {
+        println!("\n{} Migration complete!", "✨".green());
+        println!(
+            "   Review the generated TypeScript files in {}/",
+            options.out_dir.display()
+        );
+        println!("   Run 'gaji build' to regenerate YAML files\n");
+    }
 
-    Ok(())
+    Ok(reports)
 }
 
 /// Migrate a list of action files to TypeScript.
-/// Creates .ts files in workflows/ and backs up originals.
-pub async fn migrate_actions(root: &Path, actions: &[PathBuf]) -> Result<()> {
+pub async fn migrate_actions(
+    actions: &[PathBuf],
+    options: &MigrateOptions,
+) -> Result<Vec<MigrationReport>> {
     println!("{} Migrating actions to TypeScript...\n", "🔄".cyan());
 
-    let ts_dir = root.join("workflows");
-    tokio::fs::create_dir_all(&ts_dir).await?;
+    if !options.dry_run {
+        tokio::fs::create_dir_all(&options.out_dir).await?;
+    }
 
+    let mut reports = Vec::new();
     for action_path in actions {
         let action_id = action_path
             .parent()
@@ -139,42 +323,180 @@ pub async fn migrate_actions(root: &Path, actions: &[PathBuf]) -> Result<()> {
 
         match generate_typescript_from_action_yaml(&yaml_content, action_id) {
             Ok(ts_content) => {
-                let ts_path = ts_dir.join(format!("action-{}.ts", action_id));
-                tokio::fs::write(&ts_path, ts_content).await?;
-                println!("  {} Created {}", "✓".green(), ts_path.display());
-
-                // Backup original
-                let backup_path = action_path.with_extension("yml.backup");
-                tokio::fs::rename(action_path, &backup_path).await?;
-                println!("  {} Backed up to {}", "✓".green(), backup_path.display());
+                let ts_path = options.out_dir.join(format!("action-{}.ts", action_id));
+                if write_migrated(&ts_path, &ts_content, options).await? && options.backup {
+                    backup_original(action_path).await?;
+                }
             }
             Err(e) => {
                 eprintln!("  {} Failed to migrate {}: {}", "✗".red(), action_id, e);
+                reports.push(MigrationReport {
+                    path: action_path.clone(),
+                    notes: vec![format!("not migrated: {}", e)],
+                });
             }
         }
     }
 
-    println!("\n{} Action migration complete!", "✨".green());
-    println!("   Review the generated TypeScript files in workflows/");
-    println!("   Run 'gaji build' to regenerate action YAML files\n");
+    if !options.dry_run {
+        println!("\n{} Action migration complete!", "✨".green());
+        println!(
+            "   Review the generated TypeScript files in {}/",
+            options.out_dir.display()
+        );
+        println!("   Run 'gaji build' to regenerate action YAML files\n");
+    }
+
+    Ok(reports)
+}
+
+/// Write (or, with `--dry-run`, print) a migrated file.
+/// Returns whether the file was written.
+async fn write_migrated(
+    ts_path: &Path,
+    ts_content: &str,
+    options: &MigrateOptions,
+) -> Result<bool> {
+    let ts_content = ts_content.replace(DEFAULT_IMPORT_PATH, &options.import_path);
+
+    if options.dry_run {
+        println!("  {} {}\n", "---".dimmed(), ts_path.display());
+        println!("{}", ts_content);
+        return Ok(false);
+    }
+
+    if ts_path.exists() && !options.overwrite {
+        println!(
+            "  {} {} already exists, skipping (use --overwrite to replace it)",
+            "⚠".yellow(),
+            ts_path.display()
+        );
+        return Ok(false);
+    }
 
+    tokio::fs::write(ts_path, ts_content).await?;
+    println!("  {} Created {}", "✓".green(), ts_path.display());
+    Ok(true)
+}
+
+async fn backup_original(path: &Path) -> Result<()> {
+    let backup_path = path.with_extension("yml.backup");
+    tokio::fs::rename(path, &backup_path).await?;
+    println!("  {} Backed up to {}", "✓".green(), backup_path.display());
     Ok(())
 }
 
-/// Convert a single YAML workflow to TypeScript source code.
-fn generate_typescript_from_yaml(yaml_content: &str, workflow_id: &str) -> Result<String> {
+/// Print the constructs that need manual review, grouped by file.
+pub fn print_migration_reports(reports: &[MigrationReport]) {
+    if reports.is_empty() {
+        return;
+    }
+
+    println!("{} Needs manual review:\n", "📋".yellow());
+    for report in reports {
+        println!("  {}", report.path.display());
+        for note in &report.notes {
+            println!("    - {}", note);
+        }
+    }
+    println!();
+}
+
+/// Workflow-level keys carried into `WorkflowConfig` (besides `name` and `on`).
+const WORKFLOW_CONFIG_KEYS: &[&str] =
+    &["run-name", "permissions", "env", "defaults", "concurrency"];
+
+/// Job-level keys carried into `JobConfig`. `runs-on`, `steps` and `outputs`
+/// are handled separately.
+const JOB_CONFIG_KEYS: &[&str] = &[
+    "name",
+    "needs",
+    "if",
+    "permissions",
+    "environment",
+    "concurrency",
+    "strategy",
+    "timeout-minutes",
+    "continue-on-error",
+    "env",
+    "defaults",
+    "services",
+    "container",
+];
+
+/// Keys of a reusable-workflow job (`uses:`) carried into `WorkflowCall`.
+const WORKFLOW_CALL_KEYS: &[&str] = &[
+    "name",
+    "needs",
+    "if",
+    "permissions",
+    "strategy",
+    "concurrency",
+    "with",
+    "secrets",
+];
+
+/// Convert a single YAML workflow to TypeScript source code, replacing the
+/// step sequences in `shared` with calls to their helpers.
+fn generate_typescript_from_yaml(
+    yaml_content: &str,
+    workflow_id: &str,
+    shared: Option<&SharedSteps>,
+) -> Result<String> {
     let workflow: serde_yaml::Value =
         serde_yaml::from_str(yaml_content).map_err(|e| anyhow!("Failed to parse YAML: {}", e))?;
 
+    let jobs = workflow.get("jobs").and_then(|j| j.as_mapping());
+    let has_workflow_calls =
+        jobs.is_some_and(|jobs| jobs.values().any(|job| job.get("uses").is_some()));
+
+    let mut source = YamlSource::parse(yaml_content);
     let mut ts = String::new();
 
+    // Extract actions used, leaving out those only called inside shared helpers
+    let mut actions = extract_actions_from_yaml(&workflow);
+    if let (Some(shared), Some(jobs)) = (shared, jobs) {
+        let remaining: HashSet<&str> = jobs
+            .iter()
+            .filter_map(|(id, job)| Some((id.as_str()?, job.get("steps")?.as_sequence()?)))
+            .flat_map(|(id, steps)| {
+                steps
+                    .iter()
+                    .enumerate()
+                    .filter(move |(i, _)| !shared.covers(workflow_id, id, *i))
+                    .filter_map(|(_, step)| step.get("uses").and_then(|v| v.as_str()))
+            })
+            .collect();
+        actions.retain(|action| remaining.contains(action.as_str()));
+    }
+
     // Imports
-    ts.push_str("// Migrated from YAML by gaji init --migrate\n");
+    ts.push_str(MIGRATED_STAMP);
     ts.push_str("// NOTE: This is a basic conversion. Please review and adjust as needed.\n");
-    ts.push_str("import { getAction, Job, Workflow } from \"../generated/index.js\";\n\n");
+    push_comments(&mut ts, source.header_comments(), "");
+    // `getAction` is left out when every action moved into shared helpers
+    let mut imports = vec!["getAction", "Job", "Workflow"];
+    if actions.is_empty() {
+        imports.remove(0);
+    }
+    if has_workflow_calls {
+        imports.push("WorkflowCall");
+    }
+    ts.push_str(&format!(
+        "import {{ {} }} from \"../generated/index.js\";\n\n",
+        imports.join(", ")
+    ));
+    let helpers = shared.map(|s| s.used_by(workflow_id)).unwrap_or_default();
+    if !helpers.is_empty() {
+        // Keep a blank line between the imports and the action constants
+        ts.pop();
+        ts.push_str(&format!(
+            "import {{ {} }} from \"./{}.js\";\n\n",
+            helpers.join(", "),
+            SHARED_MODULE
+        ));
+    }
 
-    // Extract actions used
-    let actions = extract_actions_from_yaml(&workflow);
     for action in &actions {
         let var_name = action_to_var_name(action);
         ts.push_str(&format!(
@@ -186,64 +508,123 @@ fn generate_typescript_from_yaml(yaml_content: &str, workflow_id: &str) -> Resul
         ts.push('\n');
     }
 
-    // Extract workflow name
-    let name = workflow
-        .get("name")
-        .and_then(|v| v.as_str())
-        .unwrap_or(workflow_id);
-
-    // Jobs
-    if let Some(jobs) = workflow.get("jobs").and_then(|j| j.as_mapping()) {
+    // Anchored values that are aliased elsewhere become shared constants
+    let mut taken: HashSet<String> = actions.iter().map(|a| action_to_var_name(a)).collect();
+    taken.extend(
+        jobs.into_iter()
+            .flat_map(|jobs| jobs.keys())
+            .filter_map(|k| k.as_str())
+            .map(|id| id.replace('-', "_")),
+    );
+    taken.insert("workflow".to_string());
+    taken.extend(helpers.iter().map(|h| h.to_string()));
+    source.name_anchors(&taken);
+
+    // Jobs and the workflow are generated first so only the anchors they
+    // reference are declared.
+    let header = std::mem::take(&mut ts);
+
+    // Jobs. A job that reads another job's outputs is added inline as a
+    // `j.add(id, output => ...)` callback; the rest become constants.
+    let mut refs = OutputRefs::new();
+    let mut inline_jobs: HashMap<String, String> = HashMap::new();
+    if let Some(jobs) = jobs {
         for (job_id, job_def) in jobs {
             let job_id_str = job_id.as_str().unwrap_or("job");
-            let runs_on = job_def
-                .get("runs-on")
-                .and_then(|v| v.as_str())
-                .unwrap_or("ubuntu-latest");
-
             let var = job_id_str.replace('-', "_");
-            ts.push_str(&format!("const {} = new Job(\"{}\")\n", var, runs_on));
+            let is_call = job_def.get("uses").is_some();
+            let job = JobSource {
+                id: job_id_str,
+                workflow: workflow_id,
+                source: &source,
+                shared,
+            };
+
+            refs.enter_job(Some(OutputRefs::OUTPUT), Some(OutputRefs::OUTPUT));
+            let mut expr = generate_job_expr(job_def, &actions, &mut refs, &job);
+            let is_callback = refs.take_used_jobs();
+            if is_callback {
+                // Step callbacks must not shadow the job-level `output`
+                refs.enter_job(Some(OutputRefs::STEPS), Some(OutputRefs::OUTPUT));
+                expr = generate_job_expr(job_def, &actions, &mut refs, &job);
+            }
 
-            // Steps
-            if let Some(steps) = job_def.get("steps").and_then(|s| s.as_sequence()) {
-                ts.push_str("    .steps(s => s\n");
-                for step in steps {
-                    generate_step(&mut ts, step, &actions);
-                }
-                ts.push_str("    )");
+            if is_callback {
+                inline_jobs.insert(job_id_str.to_string(), expr);
+            } else {
+                push_comments(&mut ts, source.job_comments(job_id_str), "");
+                ts.push_str(&format!("const {} = {};\n", var, expr));
+            }
+            let before_todos = ts.len();
+            if is_call {
+                push_unsupported_keys(&mut ts, job_def, WORKFLOW_CALL_KEYS, &["uses"]);
+            } else {
+                push_unsupported_keys(
+                    &mut ts,
+                    job_def,
+                    JOB_CONFIG_KEYS,
+                    &["runs-on", "steps", "outputs"],
+                );
+            }
+            if !is_callback || ts.len() > before_todos {
+                ts.push('\n');
             }
 
-            ts.push_str(";\n\n");
+            if let (false, Some(outputs)) =
+                (is_call, job_def.get("outputs").and_then(|o| o.as_mapping()))
+            {
+                let names = outputs.keys().filter_map(|k| k.as_str()).map(String::from);
+                refs.add_job(job_id_str, names);
+            }
         }
     }
 
     // Workflow definition
     let safe_id = workflow_id.replace('-', "_");
+    push_comments(&mut ts, source.workflow_comments(), "");
     ts.push_str("const workflow = new Workflow({\n");
-    ts.push_str(&format!("    name: \"{}\",\n", escape_js_string(name)));
+    if let Some(name) = workflow.get("name").and_then(|v| v.as_str()) {
+        ts.push_str(&format!("    name: \"{}\",\n", escape_js_string(name)));
+    }
 
     // On triggers
-    // YAML parses bare `on:` as boolean true, so also check for the boolean key
-    let on_value = workflow.get("on").or_else(|| {
-        workflow
-            .as_mapping()
-            .and_then(|map| map.get(serde_yaml::Value::Bool(true)))
-    });
-    if let Some(on) = on_value {
+    if let Some(on) = workflow_triggers(&workflow) {
         ts.push_str("    on: ");
-        ts.push_str(&yaml_value_to_js(on, 4));
+        ts.push_str(&yaml_value_to_js(&normalize_triggers(on), 4));
         ts.push_str(",\n");
     }
 
+    for key in WORKFLOW_CONFIG_KEYS {
+        if let Some(value) = workflow.get(*key) {
+            let value_js = match source.anchor_at(key) {
+                Some(var) => var.to_string(),
+                None => yaml_value_to_js(value, 4),
+            };
+            ts.push_str(&format!("    {}: {},\n", js_object_key(key), value_js));
+        }
+    }
+
     ts.push_str("})");
 
     // Add jobs
-    if let Some(jobs) = workflow.get("jobs").and_then(|j| j.as_mapping()) {
+    if let Some(jobs) = jobs {
         ts.push_str("\n    .jobs(j => j\n");
         for (job_id, _) in jobs {
             let job_id_str = job_id.as_str().unwrap_or("job");
-            let var = job_id_str.replace('-', "_");
-            ts.push_str(&format!("        .add(\"{}\", {})\n", job_id_str, var));
+            match inline_jobs.get(job_id_str) {
+                Some(expr) => {
+                    push_comments(&mut ts, source.job_comments(job_id_str), "        ");
+                    ts.push_str(&format!(
+                        "        .add(\"{}\", output => {})\n",
+                        job_id_str,
+                        indent_js(expr, "        ")
+                    ));
+                }
+                None => {
+                    let var = job_id_str.replace('-', "_");
+                    ts.push_str(&format!("        .add(\"{}\", {})\n", job_id_str, var));
+                }
+            }
         }
         ts.push_str("    )");
     }
@@ -251,7 +632,443 @@ fn generate_typescript_from_yaml(yaml_content: &str, workflow_id: &str) -> Resul
     ts.push_str(";\n\n");
     ts.push_str(&format!("workflow.build(\"{}\");\n", safe_id));
 
-    Ok(ts)
+    let mut anchors = String::new();
+    for (var, value) in source.used_anchors(&workflow) {
+        anchors.push_str(&format!(
+            "const {} = {};\n",
+            var,
+            yaml_value_to_js(value, 0)
+        ));
+    }
+    if !anchors.is_empty() {
+        anchors.push('\n');
+    }
+
+    Ok(format!("{}{}{}", header, anchors, ts))
+}
+
+/// Generate `shared.ts`, exporting one function per shared step sequence
+/// that returns its steps for `StepBuilder.add()`.
+fn generate_shared_steps_ts(shared: &SharedSteps) -> String {
+    let actions = shared.actions();
+    let mut ts = String::new();
+
+    ts.push_str(
+        "// Step sequences shared by several workflows, extracted by gaji migrate --dedupe\n",
+    );
+    if actions.is_empty() {
+        ts.push_str("import type { JobStep } from \"../generated/index.js\";\n\n");
+    } else {
+        ts.push_str("import { getAction } from \"../generated/index.js\";\n");
+        ts.push_str("import type { JobStep } from \"../generated/index.js\";\n\n");
+    }
+
+    if shared.helpers.iter().any(|h| !h.params.is_empty()) {
+        ts.push_str("/** The `with` inputs accepted by an action. */\n");
+        ts.push_str(
+            "type Inputs<A extends (...args: any[]) => unknown> = NonNullable<NonNullable<Parameters<A>[0]>[\"with\"]>;\n\n",
+        );
+    }
+
+    for action in &actions {
+        ts.push_str(&format!(
+            "const {} = getAction(\"{}\");\n",
+            action_to_var_name(action),
+            action
+        ));
+    }
+    if !actions.is_empty() {
+        ts.push('\n');
+    }
+
+    for helper in &shared.helpers {
+        let used_in: Vec<String> = helper
+            .occurrences
+            .iter()
+            .map(|o| {
+                if o.workflow.is_empty() {
+                    o.job.clone()
+                } else {
+                    format!("{}.{}", o.workflow, o.job)
+                }
+            })
+            .collect();
+        ts.push_str(&format!("// Used by {}\n", used_in.join(", ")));
+
+        let params: Vec<String> = helper
+            .params
+            .iter()
+            .map(|param| {
+                let uses = helper.steps[param.step]
+                    .get("uses")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                format!(
+                    "{}: Inputs<typeof {}>[\"{}\"]",
+                    param.name,
+                    action_to_var_name(uses),
+                    escape_js_string(&param.input)
+                )
+            })
+            .collect();
+        ts.push_str(&format!(
+            "export function {}({}): JobStep[] {{\n    return [\n",
+            helper.name,
+            params.join(", ")
+        ));
+
+        let mut index = 0;
+        while index < helper.steps.len() {
+            // A shorter helper called from this one, with this helper's
+            // parameters passed through
+            if let Some((nested, occurrence)) = shared.nested_at(&helper.name, index) {
+                let args: Vec<String> = nested
+                    .params
+                    .iter()
+                    .zip(&occurrence.args)
+                    .map(|(param, arg)| {
+                        let passed = helper
+                            .params
+                            .iter()
+                            .find(|p| p.step == index + param.step && p.input == param.input)
+                            .filter(|_| is_param_marker(arg));
+                        match passed {
+                            Some(p) => p.name.clone(),
+                            None => yaml_value_to_js(arg, 8),
+                        }
+                    })
+                    .collect();
+                ts.push_str(&format!(
+                    "        ...{}({}),\n",
+                    nested.name,
+                    args.join(", ")
+                ));
+                index += nested.steps.len();
+                continue;
+            }
+
+            let step = &helper.steps[index];
+            let step_params: HashMap<String, String> = helper
+                .params
+                .iter()
+                .filter(|p| p.step == index)
+                .map(|p| (p.input.clone(), p.name.clone()))
+                .collect();
+            let mut step_ts = String::new();
+            generate_step_inner(
+                &mut step_ts,
+                step,
+                &actions,
+                &StepGenOptions {
+                    require_shell: false,
+                    refs: None,
+                    anchors: None,
+                    params: Some(&step_params),
+                },
+            );
+            // `        .add(step)\n` becomes an array element
+            let element = step_ts
+                .strip_prefix("        .add(")
+                .and_then(|s| s.strip_suffix(")\n"))
+                .unwrap_or(&step_ts);
+            ts.push_str(&format!("        {},\n", element));
+            index += 1;
+        }
+        ts.push_str("    ];\n}\n\n");
+    }
+
+    ts.truncate(ts.trim_end().len());
+    ts.push('\n');
+    ts
+}
+
+/// A job being migrated, with the comments and anchors of its YAML source
+/// and the shared step helpers it may call.
+struct JobSource<'a> {
+    id: &'a str,
+    workflow: &'a str,
+    source: &'a YamlSource,
+    shared: Option<&'a SharedSteps>,
+}
+
+impl JobSource<'_> {
+    /// Shared constant for the value at `key` of this job, if it is anchored.
+    fn anchor_at(&self, key: &str) -> Option<&str> {
+        self.source.anchor_at(&format!("jobs.{}.{}", self.id, key))
+    }
+}
+
+/// Emit YAML comments as `//` lines.
+fn push_comments(ts: &mut String, comments: &[String], indent: &str) {
+    for comment in comments {
+        if comment.is_empty() {
+            ts.push_str(&format!("{}//\n", indent));
+        } else {
+            ts.push_str(&format!("{}// {}\n", indent, comment));
+        }
+    }
+}
+
+/// The `on:` value of a workflow. YAML 1.1 parsers read a bare `on:` key as
+/// boolean true, so the boolean key is checked as well.
+pub(crate) fn workflow_triggers(workflow: &serde_yaml::Value) -> Option<&serde_yaml::Value> {
+    workflow.get("on").or_else(|| {
+        workflow
+            .as_mapping()
+            .and_then(|map| map.get(serde_yaml::Value::Bool(true)))
+    })
+}
+
+/// Normalize `on:` to the mapping form accepted by `WorkflowOn`:
+/// `on: push` and `on: [push, pull_request]` become `{ push: {} }` maps, and
+/// triggers without configuration (`workflow_dispatch:`) become `{}`.
+pub(crate) fn normalize_triggers(on: &serde_yaml::Value) -> serde_yaml::Value {
+    let empty = || serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    let mut triggers = serde_yaml::Mapping::new();
+
+    match on {
+        serde_yaml::Value::String(event) => {
+            triggers.insert(serde_yaml::Value::String(event.clone()), empty());
+        }
+        serde_yaml::Value::Sequence(events) => {
+            for event in events {
+                triggers.insert(event.clone(), empty());
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for (event, config) in map {
+                let config = if config.is_null() {
+                    empty()
+                } else {
+                    config.clone()
+                };
+                triggers.insert(event.clone(), config);
+            }
+        }
+        other => return other.clone(),
+    }
+
+    serde_yaml::Value::Mapping(triggers)
+}
+
+/// Generate the `new Job(...)` / `new WorkflowCall(...)` expression for a job.
+fn generate_job_expr(
+    job_def: &serde_yaml::Value,
+    actions: &[String],
+    refs: &mut OutputRefs,
+    job: &JobSource,
+) -> String {
+    let mut ts = String::new();
+    let step_param = refs.step_param();
+
+    // Only job outputs are in scope at the job level
+    refs.set_scope(None, Some(OutputRefs::OUTPUT));
+
+    if let Some(uses) = job_def.get("uses").and_then(|v| v.as_str()) {
+        // Reusable workflow call
+        ts.push_str(&format!("new WorkflowCall(\"{}\"", escape_js_string(uses)));
+        push_config_object(&mut ts, job_def, WORKFLOW_CALL_KEYS, Some(refs), job);
+        ts.push(')');
+        return ts;
+    }
+
+    let runs_on = match (job_def.get("runs-on"), job.anchor_at("runs-on")) {
+        (Some(_), Some(var)) => var.to_string(),
+        (Some(v), None) => yaml_value_to_js_with(v, 0, Some(refs)),
+        (None, _) => "\"ubuntu-latest\"".to_string(),
+    };
+    ts.push_str(&format!("new Job({}", runs_on));
+    push_config_object(&mut ts, job_def, JOB_CONFIG_KEYS, Some(refs), job);
+    ts.push(')');
+
+    refs.set_scope(step_param, Some(OutputRefs::OUTPUT));
+
+    // Steps
+    if let Some(steps) = job_def.get("steps").and_then(|s| s.as_sequence()) {
+        ts.push_str("\n    .steps(s => s\n");
+        let mut index = 0;
+        while index < steps.len() {
+            let shared = job
+                .shared
+                .and_then(|shared| shared.at(job.workflow, job.id, index));
+            if let Some((helper, occurrence)) = shared {
+                for i in index..index + helper.steps.len() {
+                    push_comments(&mut ts, job.source.step_comments(job.id, i), "        ");
+                }
+                let args: Vec<String> = occurrence
+                    .args
+                    .iter()
+                    .map(|arg| yaml_value_to_js(arg, 8))
+                    .collect();
+                ts.push_str(&format!(
+                    "        .add({}({}))\n",
+                    helper.name,
+                    args.join(", ")
+                ));
+                index += helper.steps.len();
+                continue;
+            }
+            generate_step(&mut ts, &steps[index], actions, refs, job, index);
+            index += 1;
+        }
+        ts.push_str("    )");
+    }
+
+    // Job outputs
+    if let Some(outputs) = job_def.get("outputs") {
+        refs.take_used_steps();
+        let outputs_js = match job.anchor_at("outputs") {
+            Some(var) => var.to_string(),
+            None => yaml_value_to_js_with(outputs, 4, Some(refs)),
+        };
+        match (refs.take_used_steps(), step_param) {
+            (true, Some(param)) => {
+                ts.push_str(&format!("\n    .outputs({} => ({}))", param, outputs_js))
+            }
+            _ => ts.push_str(&format!("\n    .outputs({})", outputs_js)),
+        }
+    }
+
+    ts
+}
+
+/// Append `, { key: value, ... }` for the given keys present in `source`.
+fn push_config_object(
+    ts: &mut String,
+    source: &serde_yaml::Value,
+    keys: &[&str],
+    refs: Option<&OutputRefs>,
+    job: &JobSource,
+) {
+    let present: Vec<(&str, &serde_yaml::Value)> = keys
+        .iter()
+        .filter_map(|key| source.get(*key).map(|value| (*key, value)))
+        .collect();
+    if present.is_empty() {
+        return;
+    }
+
+    ts.push_str(", {\n");
+    for (key, value) in present {
+        let value_js = match job.anchor_at(key) {
+            Some(var) => var.to_string(),
+            None => yaml_value_to_js_with(value, 4, refs),
+        };
+        ts.push_str(&format!("    {}: {},\n", js_object_key(key), value_js));
+    }
+    ts.push('}');
+}
+
+/// Leave a TODO for keys that have no TypeScript equivalent yet.
+fn push_unsupported_keys(
+    ts: &mut String,
+    source: &serde_yaml::Value,
+    supported: &[&str],
+    handled: &[&str],
+) {
+    let Some(map) = source.as_mapping() else {
+        return;
+    };
+    for key in map.keys().filter_map(|k| k.as_str()) {
+        if !supported.contains(&key) && !handled.contains(&key) {
+            ts.push_str(&format!(
+                "// TODO: '{}' was not migrated, add it manually\n",
+                key
+            ));
+        }
+    }
+}
+
+/// Keys of `uses` steps carried over by the migration.
+const USES_STEP_KEYS: &[&str] = &[
+    "uses",
+    "id",
+    "name",
+    "with",
+    "if",
+    "env",
+    "continue-on-error",
+    "timeout-minutes",
+];
+
+/// Keys of `run` steps carried over by the migration.
+const RUN_STEP_KEYS: &[&str] = &[
+    "run",
+    "id",
+    "name",
+    "shell",
+    "if",
+    "env",
+    "working-directory",
+    "continue-on-error",
+    "timeout-minutes",
+];
+
+/// List the parts of a workflow the migration cannot carry over.
+pub(crate) fn find_unsupported(workflow: &serde_yaml::Value) -> Vec<String> {
+    let mut notes = Vec::new();
+    let unknown_keys = |value: &serde_yaml::Value, known: &[&[&str]]| -> Vec<String> {
+        value
+            .as_mapping()
+            .into_iter()
+            .flat_map(|map| map.keys())
+            .filter_map(|k| k.as_str())
+            .filter(|k| !known.iter().any(|keys| keys.contains(k)))
+            .map(String::from)
+            .collect()
+    };
+
+    for key in unknown_keys(workflow, &[&["name", "on", "jobs"], WORKFLOW_CONFIG_KEYS]) {
+        notes.push(format!("{}: unsupported workflow key", key));
+    }
+
+    let Some(jobs) = workflow.get("jobs").and_then(|j| j.as_mapping()) else {
+        return notes;
+    };
+    for (job_id, job) in jobs {
+        let job_id = job_id.as_str().unwrap_or("job");
+        let known: &[&[&str]] = if job.get("uses").is_some() {
+            &[WORKFLOW_CALL_KEYS, &["uses"]]
+        } else {
+            &[JOB_CONFIG_KEYS, &["runs-on", "steps", "outputs"]]
+        };
+        for key in unknown_keys(job, known) {
+            notes.push(format!("jobs.{}.{}: unsupported job key", job_id, key));
+        }
+
+        let steps = job.get("steps").and_then(|s| s.as_sequence());
+        for (i, step) in steps.into_iter().flatten().enumerate() {
+            let at = format!("jobs.{}.steps[{}]", job_id, i);
+            let known = if step.get("uses").and_then(|v| v.as_str()).is_some() {
+                USES_STEP_KEYS
+            } else if step.get("run").and_then(|v| v.as_str()).is_some() {
+                RUN_STEP_KEYS
+            } else {
+                notes.push(format!("{}: step has neither 'uses' nor 'run'", at));
+                continue;
+            };
+            for key in unknown_keys(step, &[known]) {
+                notes.push(format!("{}.{}: unsupported step key", at, key));
+            }
+        }
+    }
+
+    notes
+}
+
+/// Render an object key, quoting it when it is not a plain identifier.
+fn js_object_key(key: &str) -> String {
+    let needs_quotes = key == "if"
+        || key.is_empty()
+        || key.starts_with(|c: char| c.is_ascii_digit())
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if needs_quotes {
+        format!("\"{}\"", escape_js_string(key))
+    } else {
+        key.to_string()
+    }
 }
 
 /// Convert a single action.yml to TypeScript source code.
@@ -277,7 +1094,7 @@ fn generate_composite_action_ts(action: &serde_yaml::Value, action_id: &str) ->
     let mut ts = String::new();
 
     // Header
-    ts.push_str("// Migrated from YAML by gaji init --migrate\n");
+    ts.push_str(MIGRATED_STAMP);
     ts.push_str("// NOTE: This is a basic conversion. Please review and adjust as needed.\n");
 
     // Extract external actions used in steps
@@ -356,7 +1173,7 @@ fn generate_javascript_action_ts(
 ) -> Result<String> {
     let mut ts = String::new();
 
-    ts.push_str("// Migrated from YAML by gaji init --migrate\n");
+    ts.push_str(MIGRATED_STAMP);
     ts.push_str("// NOTE: This is a basic conversion. Please review and adjust as needed.\n");
     ts.push_str("import { NodeAction } from \"../generated/index.js\";\n\n");
 
@@ -439,7 +1256,7 @@ fn generate_docker_action_ts(action: &serde_yaml::Value, action_id: &str) -> Res
     let mut ts = String::new();
 
     // Header
-    ts.push_str("// Migrated from YAML by gaji init --migrate\n");
+    ts.push_str(MIGRATED_STAMP);
     ts.push_str("// NOTE: This is a basic conversion. Please review and adjust as needed.\n");
     ts.push_str("import { DockerAction } from \"../generated/index.js\";\n\n");
 
@@ -540,21 +1357,83 @@ fn generate_docker_action_ts(action: &serde_yaml::Value, action_id: &str) -> Res
 }
 
 /// Options for step generation.
-struct StepGenOptions {
+struct StepGenOptions<'a> {
     /// Whether `shell` is required for `run` steps (true for composite action steps).
     require_shell: bool,
+    /// Output references to rewrite into `output` callbacks (workflow steps only).
+    refs: Option<&'a OutputRefs>,
+    /// YAML source and path of the step, to reuse anchored `with`/`env` maps.
+    anchors: Option<(&'a YamlSource, String)>,
+    /// `with` inputs replaced by helper parameters, by input name.
+    params: Option<&'a HashMap<String, String>>,
+}
+
+impl StepGenOptions<'_> {
+    fn anchor_at(&self, key: &str) -> Option<&str> {
+        let (source, path) = self.anchors.as_ref()?;
+        source.anchor_at(&format!("{}.{}", path, key))
+    }
 }
 
 /// Generate a step call in the TypeScript output.
-fn generate_step(ts: &mut String, step: &serde_yaml::Value, actions: &[String]) {
+///
+/// Steps that read outputs of earlier action steps become `.add(output => ...)`
+/// callbacks.
+fn generate_step(
+    ts: &mut String,
+    step: &serde_yaml::Value,
+    actions: &[String],
+    refs: &mut OutputRefs,
+    job: &JobSource,
+    index: usize,
+) {
+    push_comments(ts, job.source.step_comments(job.id, index), "        ");
+
+    // A whole step shared through an anchor
+    let path = step_path(job.id, index);
+    if let Some(var) = job.source.anchor_at(&path) {
+        ts.push_str(&format!("        .add({})\n", var));
+        return;
+    }
+
+    let mut step_ts = String::new();
+    refs.take_used_steps();
     generate_step_inner(
-        ts,
+        &mut step_ts,
         step,
         actions,
         &StepGenOptions {
             require_shell: false,
+            refs: Some(refs),
+            anchors: Some((job.source, path)),
+            params: None,
         },
     );
+
+    match (refs.take_used_steps(), refs.step_param()) {
+        (true, Some(param)) => ts.push_str(&wrap_step_callback(&step_ts, param)),
+        _ => ts.push_str(&step_ts),
+    }
+
+    if let (Some(id), Some(uses)) = (
+        step.get("id").and_then(|v| v.as_str()),
+        step.get("uses").and_then(|v| v.as_str()),
+    ) {
+        if actions.iter().any(|a| a == uses) {
+            refs.add_step(id);
+        }
+    }
+}
+
+/// Turn `.add({...})` / `.add(action({...}))` into a callback taking `param`.
+fn wrap_step_callback(step_ts: &str, param: &str) -> String {
+    const PREFIX: &str = "        .add(";
+    let body = step_ts.strip_prefix(PREFIX).unwrap_or(step_ts);
+    if let Some(object) = body.strip_suffix(")\n").filter(|_| body.starts_with('{')) {
+        format!("{}{} => ({}))\n", PREFIX, param, object)
+    } else {
+        format!("{}{} => {}", PREFIX, param, body)
+    }
 }
 
 /// Generate a step call for composite action steps (shell is required for run steps).
@@ -565,6 +1444,9 @@ fn generate_composite_action_step(ts: &mut String, step: &serde_yaml::Value, act
         actions,
         &StepGenOptions {
             require_shell: true,
+            refs: None,
+            anchors: None,
+            params: None,
         },
     );
 }
@@ -597,53 +1479,54 @@ fn generate_step_inner(
 
         if let Some(name) = step.get("name").and_then(|v| v.as_str()) {
             ts.push_str(&format!(
-                "            name: \"{}\",\n",
-                escape_js_string(name)
+                "            name: {},\n",
+                js_string(name, options.refs)
             ));
         }
 
-        if let Some(with) = step.get("with").and_then(|v| v.as_mapping()) {
+        if let Some(var) = step.get("with").and(options.anchor_at("with")) {
+            ts.push_str(&format!("            with: {},\n", var));
+        } else if let Some(with) = step.get("with").and_then(|v| v.as_mapping()) {
             ts.push_str("            with: {\n");
             for (k, v) in with {
                 let key_str = k.as_str().unwrap_or("unknown");
-                let needs_quotes = key_str.contains('-') || key_str.contains('.');
-                if needs_quotes {
-                    ts.push_str(&format!(
-                        "                \"{}\": {},\n",
-                        escape_js_string(key_str),
-                        yaml_value_to_js(v, 16)
-                    ));
-                } else {
-                    ts.push_str(&format!(
-                        "                {}: {},\n",
-                        key_str,
-                        yaml_value_to_js(v, 16)
-                    ));
-                }
+                let value = match options.params.and_then(|p| p.get(key_str)) {
+                    Some(param) => param.clone(),
+                    None => yaml_value_to_js_with(v, 16, options.refs),
+                };
+                ts.push_str(&format!(
+                    "                {}: {},\n",
+                    js_object_key(key_str),
+                    value
+                ));
             }
             ts.push_str("            },\n");
         }
 
         if let Some(if_cond) = step.get("if").and_then(|v| v.as_str()) {
             ts.push_str(&format!(
-                "            \"if\": \"{}\",\n",
-                escape_js_string(if_cond)
+                "            \"if\": {},\n",
+                js_string(if_cond, options.refs)
             ));
         }
 
-        if let Some(env) = step.get("env").and_then(|v| v.as_mapping()) {
+        if let Some(var) = step.get("env").and(options.anchor_at("env")) {
+            ts.push_str(&format!("            env: {},\n", var));
+        } else if let Some(env) = step.get("env").and_then(|v| v.as_mapping()) {
             ts.push_str("            env: {\n");
             for (k, v) in env {
                 let key_str = k.as_str().unwrap_or("unknown");
                 ts.push_str(&format!(
                     "                {}: {},\n",
-                    key_str,
-                    yaml_value_to_js(v, 16)
+                    js_object_key(key_str),
+                    yaml_value_to_js_with(v, 16, options.refs)
                 ));
             }
             ts.push_str("            },\n");
         }
 
+        push_step_options(ts, step, options.refs);
+
         if is_known {
             ts.push_str("        }))\n");
         } else {
@@ -659,19 +1542,14 @@ fn generate_step_inner(
 
         if let Some(name) = step.get("name").and_then(|v| v.as_str()) {
             ts.push_str(&format!(
-                "            name: \"{}\",\n",
-                escape_js_string(name)
+                "            name: {},\n",
+                js_string(name, options.refs)
             ));
         }
-        if run.contains('\n') {
-            ts.push_str(&format!(
-                "            run: `{}`",
-                run.replace('`', "\\`").replace("${", "\\${")
-            ));
-        } else {
-            ts.push_str(&format!("            run: \"{}\"", escape_js_string(run)));
-        }
-        ts.push_str(",\n");
+        ts.push_str(&format!(
+            "            run: {},\n",
+            js_string(run, options.refs)
+        ));
 
         // shell is required for composite action run steps
         if options.require_shell {
@@ -689,19 +1567,21 @@ fn generate_step_inner(
 
         if let Some(if_cond) = step.get("if").and_then(|v| v.as_str()) {
             ts.push_str(&format!(
-                "            \"if\": \"{}\",\n",
-                escape_js_string(if_cond)
+                "            \"if\": {},\n",
+                js_string(if_cond, options.refs)
             ));
         }
 
-        if let Some(env) = step.get("env").and_then(|v| v.as_mapping()) {
+        if let Some(var) = step.get("env").and(options.anchor_at("env")) {
+            ts.push_str(&format!("            env: {},\n", var));
+        } else if let Some(env) = step.get("env").and_then(|v| v.as_mapping()) {
             ts.push_str("            env: {\n");
             for (k, v) in env {
                 let key_str = k.as_str().unwrap_or("unknown");
                 ts.push_str(&format!(
                     "                {}: {},\n",
-                    key_str,
-                    yaml_value_to_js(v, 16)
+                    js_object_key(key_str),
+                    yaml_value_to_js_with(v, 16, options.refs)
                 ));
             }
             ts.push_str("            },\n");
@@ -709,15 +1589,30 @@ fn generate_step_inner(
 
         if let Some(wd) = step.get("working-directory").and_then(|v| v.as_str()) {
             ts.push_str(&format!(
-                "            \"working-directory\": \"{}\",\n",
-                escape_js_string(wd)
+                "            \"working-directory\": {},\n",
+                js_string(wd, options.refs)
             ));
         }
 
+        push_step_options(ts, step, options.refs);
+
         ts.push_str("        })\n");
     }
 }
 
+/// Step keys that apply to both `uses` and `run` steps.
+fn push_step_options(ts: &mut String, step: &serde_yaml::Value, refs: Option<&OutputRefs>) {
+    for key in ["continue-on-error", "timeout-minutes"] {
+        if let Some(value) = step.get(key) {
+            ts.push_str(&format!(
+                "            {}: {},\n",
+                js_object_key(key),
+                yaml_value_to_js_with(value, 12, refs)
+            ));
+        }
+    }
+}
+
 /// Migrate `.gaji.toml` (and optionally `.gaji.local.toml`) to TypeScript config files.
 pub fn migrate_toml_config(root: &Path) -> Result<()> {
     let toml_path = root.join(".gaji.toml");
@@ -936,6 +1831,26 @@ pub fn action_to_var_name(action: &str) -> String {
 
 /// Recursively convert a serde_yaml::Value to JavaScript object literal syntax.
 fn yaml_value_to_js(value: &serde_yaml::Value, indent: usize) -> String {
+    yaml_value_to_js_with(value, indent, None)
+}
+
+/// Render a string value, rewriting output references when `refs` is given.
+fn js_string(s: &str, refs: Option<&OutputRefs>) -> String {
+    if let Some(expr) = refs.and_then(|refs| refs.rewrite(s)) {
+        expr
+    } else if s.contains('\n') {
+        format!("`{}`", escape_template_literal(s))
+    } else {
+        format!("\"{}\"", escape_js_string(s))
+    }
+}
+
+/// Like [`yaml_value_to_js`], rewriting output references in string values.
+fn yaml_value_to_js_with(
+    value: &serde_yaml::Value,
+    indent: usize,
+    refs: Option<&OutputRefs>,
+) -> String {
     let pad = " ".repeat(indent);
     let inner_pad = " ".repeat(indent + 4);
 
@@ -943,13 +1858,7 @@ fn yaml_value_to_js(value: &serde_yaml::Value, indent: usize) -> String {
         serde_yaml::Value::Null => "undefined".to_string(),
         serde_yaml::Value::Bool(b) => b.to_string(),
         serde_yaml::Value::Number(n) => n.to_string(),
-        serde_yaml::Value::String(s) => {
-            if s.contains('\n') {
-                format!("`{}`", s.replace('`', "\\`").replace("${", "\\${"))
-            } else {
-                format!("\"{}\"", escape_js_string(s))
-            }
-        }
+        serde_yaml::Value::String(s) => js_string(s, refs),
         serde_yaml::Value::Sequence(seq) => {
             if seq.is_empty() {
                 return "[]".to_string();
@@ -965,13 +1874,16 @@ fn yaml_value_to_js(value: &serde_yaml::Value, indent: usize) -> String {
             });
 
             if all_simple {
-                let items: Vec<String> = seq.iter().map(|v| yaml_value_to_js(v, 0)).collect();
+                let items: Vec<String> = seq
+                    .iter()
+                    .map(|v| yaml_value_to_js_with(v, 0, refs))
+                    .collect();
                 format!("[{}]", items.join(", "))
             } else {
                 let mut result = String::from("[\n");
                 for item in seq {
                     result.push_str(&inner_pad);
-                    result.push_str(&yaml_value_to_js(item, indent + 4));
+                    result.push_str(&yaml_value_to_js_with(item, indent + 4, refs));
                     result.push_str(",\n");
                 }
                 result.push_str(&pad);
@@ -991,26 +1903,12 @@ fn yaml_value_to_js(value: &serde_yaml::Value, indent: usize) -> String {
                     other => format!("{:?}", other),
                 };
 
-                let needs_quotes = key_str.contains('-')
-                    || key_str.contains('.')
-                    || key_str.contains(' ')
-                    || key_str.starts_with(|c: char| c.is_ascii_digit());
-
-                if needs_quotes {
-                    result.push_str(&format!(
-                        "{}\"{}\": {},\n",
-                        inner_pad,
-                        escape_js_string(&key_str),
-                        yaml_value_to_js(v, indent + 4)
-                    ));
-                } else {
-                    result.push_str(&format!(
-                        "{}{}: {},\n",
-                        inner_pad,
-                        key_str,
-                        yaml_value_to_js(v, indent + 4)
-                    ));
-                }
+                result.push_str(&format!(
+                    "{}{}: {},\n",
+                    inner_pad,
+                    js_object_key(&key_str),
+                    yaml_value_to_js_with(v, indent + 4, refs)
+                ));
             }
             result.push_str(&pad);
             result.push('}');
@@ -1020,6 +1918,14 @@ fn yaml_value_to_js(value: &serde_yaml::Value, indent: usize) -> String {
     }
 }
 
+/// Escape a multi-line string for a template literal. Backslashes are escaped
+/// first so shell line continuations (`\` before a newline) survive.
+fn escape_template_literal(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${")
+}
+
 fn escape_js_string(s: &str) -> String {
     s.replace('\\', "\\\\")
         .replace('"', "\\\"")
@@ -1086,7 +1992,7 @@ jobs:
       - name: Test
         run: npm test
 "#;
-        let ts = generate_typescript_from_yaml(yaml_content, "ci").unwrap();
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
 
         assert!(ts.contains("import { getAction, Job, Workflow }"));
         assert!(ts.contains(r#"getAction("actions/checkout@v5")"#));
@@ -1102,6 +2008,361 @@ jobs:
         assert!(!ts.contains(".addJob("));
     }
 
+    #[test]
+    fn test_generate_typescript_from_yaml_round_trip() {
+        let yaml_content = r#"
+name: Release
+run-name: Release ${{ github.ref_name }}
+on:
+  push:
+    tags: ["v*"]
+  workflow_dispatch:
+permissions:
+  contents: read
+env:
+  NODE_ENV: production
+concurrency:
+  group: release-${{ github.ref }}
+  cancel-in-progress: true
+defaults:
+  run:
+    shell: bash
+jobs:
+  build:
+    name: Build (${{ matrix.os }})
+    runs-on:
+      group: large-runners
+      labels: [linux, x64]
+    timeout-minutes: 30
+    continue-on-error: false
+    strategy:
+      fail-fast: false
+      matrix:
+        os: [ubuntu-latest, macos-latest]
+    environment:
+      name: staging
+      url: https://staging.example.com
+    container:
+      image: node:20
+      options: --cpus 2
+    services:
+      redis:
+        image: redis
+        ports: ["6379:6379"]
+    env:
+      MY-VAR: "1"
+    outputs:
+      version: ${{ steps.version.outputs.value }}
+    steps:
+      - uses: actions/checkout@v5
+        with:
+          fetch-depth: 0
+        timeout-minutes: 5
+      - id: version
+        run: |
+          docker build \
+            -t app .
+          echo "value=1" >> "$GITHUB_OUTPUT"
+        continue-on-error: true
+  deploy:
+    needs: build
+    if: github.ref_type == 'tag'
+    uses: org/repo/.github/workflows/deploy.yml@v1
+    with:
+      version: ${{ needs.build.outputs.version }}
+    secrets: inherit
+"#;
+        let ts = generate_typescript_from_yaml(yaml_content, "release", None).unwrap();
+
+        assert!(ts.contains("import { getAction, Job, Workflow, WorkflowCall }"));
+        assert!(ts.contains(r#"new WorkflowCall("org/repo/.github/workflows/deploy.yml@v1""#));
+        assert!(ts.contains(r#""run-name": "Release ${{ github.ref_name }}""#));
+        assert!(!ts.contains("TODO"));
+
+        let diffs =
+            super::super::verify::verify_migrated_workflow(&ts, yaml_content, &HashMap::new())
+                .unwrap();
+        assert!(diffs.is_empty(), "unexpected differences: {:#?}", diffs);
+    }
+
+    #[test]
+    fn test_generate_typescript_from_yaml_output_callbacks() {
+        let yaml_content = r#"
+on: push
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    outputs:
+      ref: ${{ steps.co.outputs.ref }}
+      version: ${{ steps.version.outputs.value }}
+    steps:
+      - id: co
+        uses: actions/checkout@v5
+      - name: Show ${{ steps.co.outputs.ref }}
+        run: echo "${{ steps.co.outputs.ref }}" ${{ steps.co.outputs.ref || 'none' }}
+      - id: version
+        run: echo "value=1" >> "$GITHUB_OUTPUT"
+  deploy:
+    needs: build
+    if: ${{ needs.build.outputs.version }}
+    runs-on: ubuntu-latest
+    steps:
+      - id: co
+        uses: actions/checkout@v5
+        with:
+          ref: ${{ needs.build.outputs.ref }}
+      - run: echo ${{ steps.co.outputs.commit }}
+"#;
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
+
+        // Step outputs of action steps go through the step callback
+        assert!(ts.contains(r#"name: `Show ${output.co.ref}`"#));
+        assert!(
+            ts.contains(r#"run: `echo "${output.co.ref}" \${{ steps.co.outputs.ref || 'none' }}`"#)
+        );
+        // Run steps have no typed outputs, so their references stay strings
+        assert!(ts.contains(r#"version: "${{ steps.version.outputs.value }}""#));
+        assert!(ts.contains(".outputs(output => ({"));
+        // Job outputs go through the job callback, without shadowing it
+        assert!(ts.contains(r#".add("deploy", output => new Job("ubuntu-latest", {"#));
+        assert!(ts.contains(r#""if": output.build.version,"#));
+        assert!(ts.contains("ref: output.build.ref,"));
+        assert!(ts.contains(r#".add(steps => ({"#));
+        assert!(ts.contains("run: `echo ${steps.co.commit}`"));
+        assert!(!ts.contains("const deploy"));
+
+        let diffs =
+            super::super::verify::verify_migrated_workflow(&ts, yaml_content, &HashMap::new())
+                .unwrap();
+        assert!(
+            diffs.is_empty(),
+            "unexpected differences: {:#?}\n{}",
+            diffs,
+            ts
+        );
+    }
+
+    #[test]
+    fn test_generate_typescript_from_yaml_comments_and_anchors() {
+        let yaml_content = r#"# Continuous integration
+name: CI
+on: push
+
+jobs:
+  # Static checks
+  lint:
+    runs-on: ubuntu-latest
+    env: &common-env
+      NODE_ENV: test
+    steps:
+      - &checkout
+        uses: actions/checkout@v5
+        with:
+          fetch-depth: 0
+      # Lint everything
+      - run: npm run lint
+  test:
+    runs-on: ubuntu-latest
+    env: *common-env
+    steps:
+      - *checkout
+      - run: npm test # unit tests only
+"#;
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
+
+        assert!(ts.contains("// Continuous integration\nimport"));
+        assert!(ts.contains("// Static checks\nconst lint = new Job("));
+        assert!(ts.contains("        // Lint everything\n        .add({"));
+        assert!(ts.contains("        // unit tests only\n        .add({"));
+        assert!(ts.contains("const commonEnv = {\n    NODE_ENV: \"test\",\n};"));
+        assert_eq!(ts.matches("env: commonEnv,").count(), 2);
+        assert_eq!(ts.matches(".add(checkout2)").count(), 2);
+        assert!(ts.contains("const checkout2 = {\n    uses: \"actions/checkout@v5\","));
+
+        let diffs =
+            super::super::verify::verify_migrated_workflow(&ts, yaml_content, &HashMap::new())
+                .unwrap();
+        assert!(diffs.is_empty(), "unexpected differences: {:#?}", diffs);
+    }
+
+    #[test]
+    fn test_generate_typescript_from_yaml_drops_generated_header() {
+        let yaml_content = r#"# Auto-generated by gaji
+# Do not edit manually - Edit workflows/ci.ts instead
+# Generated at: 2026-01-01T00:00:00Z
+
+# Continuous integration
+on: push
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - run: npm test
+"#;
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
+
+        assert!(!ts.contains("Auto-generated"));
+        assert!(!ts.contains("Do not edit manually"));
+        assert!(!ts.contains("Generated at"));
+        assert!(ts.contains("// Continuous integration\nimport"));
+    }
+
+    #[test]
+    fn test_generate_typescript_from_yaml_shared_steps() {
+        let ci = r#"
+on: push
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v5
+      # Install toolchain
+      - uses: actions/setup-node@v4
+        with:
+          node-version: "20"
+          cache: npm
+      - run: npm ci
+      - run: npm test
+"#;
+        let release = r#"
+on: push
+jobs:
+  publish:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v5
+      - uses: actions/setup-node@v4
+        with:
+          node-version: "22"
+          cache: npm
+      - run: npm ci
+      - run: npm publish
+"#;
+        let workflows: Vec<(String, serde_yaml::Value)> = [("ci", ci), ("release", release)]
+            .iter()
+            .map(|(id, yaml)| (id.to_string(), serde_yaml::from_str(yaml).unwrap()))
+            .collect();
+        let shared = SharedSteps::find(&workflows);
+
+        let shared_ts = generate_shared_steps_ts(&shared);
+        assert!(shared_ts.contains("import type { JobStep } from \"../generated/index.js\";"));
+        assert!(shared_ts.contains("// Used by ci.test, release.publish"));
+        assert!(shared_ts.contains(
+            "export function checkoutSetupNodeSteps(nodeVersion: Inputs<typeof setupNode>[\"node-version\"]): JobStep[] {"
+        ));
+        assert!(shared_ts.contains("                \"node-version\": nodeVersion,"));
+        assert!(shared_ts.contains("                cache: \"npm\","));
+
+        let modules = HashMap::from([(PathBuf::from("shared.ts"), shared_ts)]);
+        for (id, yaml) in [("ci", ci), ("release", release)] {
+            let ts = generate_typescript_from_yaml(yaml, id, Some(&shared)).unwrap();
+            assert!(ts.contains("import { checkoutSetupNodeSteps } from \"./shared.js\";"));
+            assert!(!ts.contains("setup-node"));
+
+            let diffs =
+                super::super::verify::verify_migrated_workflow(&ts, yaml, &modules).unwrap();
+            assert!(diffs.is_empty(), "{}: {:?}\n{}", id, diffs, ts);
+        }
+
+        let ts = generate_typescript_from_yaml(ci, "ci", Some(&shared)).unwrap();
+        assert!(ts.contains(
+            "        // Install toolchain\n        .add(checkoutSetupNodeSteps(\"20\"))\n        .add({\n            run: \"npm test\","
+        ));
+    }
+
+    #[test]
+    fn test_shared_steps_nested_helpers() {
+        let workflow = |node: &str| {
+            format!(
+                r#"
+on: push
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v5
+      - uses: actions/setup-node@v4
+        with:
+          node-version: "{node}"
+      - run: npm ci
+      - run: npm test
+  lint:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v5
+      - uses: actions/setup-node@v4
+        with:
+          node-version: "{node}"
+      - run: npm ci
+      - run: npm run lint
+"#
+            )
+        };
+        let (ci, release) = (workflow("20"), workflow("22"));
+        let workflows: Vec<(String, serde_yaml::Value)> = [("ci", &ci), ("release", &release)]
+            .iter()
+            .map(|(id, yaml)| (id.to_string(), serde_yaml::from_str(yaml).unwrap()))
+            .collect();
+        let shared = SharedSteps::find(&workflows);
+
+        // The common prefix is extracted once and called by both longer helpers
+        let shared_ts = generate_shared_steps_ts(&shared);
+        assert_eq!(shared_ts.matches("setupNode({").count(), 1, "{}", shared_ts);
+        assert!(shared_ts.contains(
+            "// Used by checkoutSetupNodeNpmCiNpmTestSteps, checkoutSetupNodeNpmCiNpmRunSteps"
+        ));
+        assert!(shared_ts.contains(
+            "export function checkoutSetupNodeNpmCiNpmTestSteps(nodeVersion: Inputs<typeof setupNode>[\"node-version\"]): JobStep[] {\n    return [\n        ...checkoutSetupNodeSteps(nodeVersion),\n"
+        ));
+
+        let modules = HashMap::from([(PathBuf::from("shared.ts"), shared_ts)]);
+        for (id, yaml) in [("ci", &ci), ("release", &release)] {
+            let ts = generate_typescript_from_yaml(yaml, id, Some(&shared)).unwrap();
+            // Every action moved into the helpers
+            assert!(ts.contains("import { Job, Workflow } from \"../generated/index.js\";"));
+            assert!(!ts.contains("getAction"));
+
+            let diffs =
+                super::super::verify::verify_migrated_workflow(&ts, yaml, &modules).unwrap();
+            assert!(diffs.is_empty(), "{}: {:?}\n{}", id, diffs, ts);
+        }
+    }
+
+    #[test]
+    fn test_generate_typescript_from_yaml_trigger_shorthand() {
+        let yaml_content = r#"
+on: [push, pull_request]
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - run: echo hi
+"#;
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
+
+        // No name in the original, so none is invented
+        assert!(!ts.contains("name:"));
+        let diffs =
+            super::super::verify::verify_migrated_workflow(&ts, yaml_content, &HashMap::new())
+                .unwrap();
+        assert!(diffs.is_empty(), "unexpected differences: {:#?}", diffs);
+    }
+
+    #[test]
+    fn test_generate_typescript_from_yaml_unsupported_job_key() {
+        let yaml_content = r#"
+on: push
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    future-key: true
+    steps:
+      - run: echo hi
+"#;
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
+        assert!(ts.contains("// TODO: 'future-key' was not migrated"));
+    }
+
     #[test]
     fn test_yaml_value_to_js_string() {
         let val = serde_yaml::Value::String("hello".to_string());
@@ -1157,7 +2418,7 @@ jobs:
           echo ${{ secrets.TOKEN }}
           echo done
 "#;
-        let ts = generate_typescript_from_yaml(yaml_content, "ci").unwrap();
+        let ts = generate_typescript_from_yaml(yaml_content, "ci", None).unwrap();
         assert!(
             ts.contains("\\${{ secrets.TOKEN }}"),
             "Expected escaped \\${{ but got: {}",
@@ -1222,6 +2483,131 @@ jobs:
         assert_eq!(result.len(), 2);
     }
 
+    #[test]
+    fn test_import_path() {
+        assert_eq!(
+            import_path(Path::new("workflows"), Path::new("generated")),
+            "../generated/index.js"
+        );
+        assert_eq!(
+            import_path(Path::new("ts/workflows"), Path::new("./generated")),
+            "../../generated/index.js"
+        );
+        assert_eq!(
+            import_path(Path::new("src"), Path::new("src/generated")),
+            "./generated/index.js"
+        );
+        assert!(is_action_file(Path::new(".github/actions/x/action.yaml")));
+        assert!(!is_action_file(Path::new(".github/workflows/ci.yml")));
+    }
+
+    #[test]
+    fn test_find_unsupported() {
+        let workflow: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+on: push
+x-custom: 1
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    future-key: true
+    steps:
+      - run: echo hi
+        with:
+          a: 1
+      - name: nothing to do
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            find_unsupported(&workflow),
+            vec![
+                "x-custom: unsupported workflow key",
+                "jobs.test.future-key: unsupported job key",
+                "jobs.test.steps[0].with: unsupported step key",
+                "jobs.test.steps[1]: step has neither 'uses' nor 'run'",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_workflows_options() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let wf_dir = temp.path().join(".github/workflows");
+        let out_dir = temp.path().join("workflows");
+        std::fs::create_dir_all(&wf_dir).unwrap();
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let yaml = "on: push\njobs:\n  test:\n    runs-on: ubuntu-latest\n    steps:\n      - run: echo hi\n";
+        std::fs::write(wf_dir.join("ci.yml"), yaml).unwrap();
+        std::fs::write(wf_dir.join("lint.yml"), yaml).unwrap();
+        std::fs::write(out_dir.join("lint.ts"), "// hand-written").unwrap();
+        let workflows = vec![wf_dir.join("ci.yml"), wf_dir.join("lint.yml")];
+
+        // Dry run writes nothing
+        let dry_run = MigrateOptions {
+            dry_run: true,
+            ..MigrateOptions::new(out_dir.clone())
+        };
+        migrate_workflows(&workflows, &dry_run).await.unwrap();
+        assert!(!out_dir.join("ci.ts").exists());
+
+        // Existing files are kept unless overwriting, originals stay without backup
+        let keep = MigrateOptions {
+            backup: false,
+            ..MigrateOptions::new(out_dir.clone())
+        };
+        let reports = migrate_workflows(&workflows, &keep).await.unwrap();
+        assert!(reports.is_empty());
+        assert!(out_dir.join("ci.ts").exists());
+        assert_eq!(
+            std::fs::read_to_string(out_dir.join("lint.ts")).unwrap(),
+            "// hand-written"
+        );
+        assert!(wf_dir.join("ci.yml").exists());
+
+        // Overwriting replaces the file and backs up the verified original
+        let overwrite = MigrateOptions {
+            overwrite: true,
+            ..MigrateOptions::new(out_dir.clone())
+        };
+        migrate_workflows(&workflows[1..], &overwrite)
+            .await
+            .unwrap();
+        assert!(std::fs::read_to_string(out_dir.join("lint.ts"))
+            .unwrap()
+            .contains("new Workflow("));
+        assert!(!wf_dir.join("lint.yml").exists());
+        assert!(wf_dir.join("lint.yml.backup").exists());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_workflows_differing_output_is_a_draft() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let wf_dir = temp.path().join(".github/workflows");
+        let out_dir = temp.path().join("workflows");
+        std::fs::create_dir_all(&wf_dir).unwrap();
+        let yaml = "on: push\njobs:\n  test:\n    runs-on: ubuntu-latest\n    future-key: true\n    steps:\n      - run: echo hi\n";
+        std::fs::write(wf_dir.join("ci.yml"), yaml).unwrap();
+
+        let reports = migrate_workflows(
+            &[wf_dir.join("ci.yml")],
+            &MigrateOptions::new(out_dir.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reports.len(), 1);
+        // `gaji build` only picks up .ts files, so the original stays in charge
+        assert!(!out_dir.join("ci.ts").exists());
+        assert!(std::fs::read_to_string(out_dir.join("ci.ts.draft"))
+            .unwrap()
+            .contains("new Workflow("));
+        assert_eq!(
+            std::fs::read_to_string(wf_dir.join("ci.yml")).unwrap(),
+            yaml
+        );
+        assert!(!wf_dir.join("ci.yml.backup").exists());
+    }
+
     #[test]
     fn test_classify_action_composite() {
         let yaml: serde_yaml::Value =
@@ -1,19 +1,30 @@
 pub mod interactive;
 pub mod migration;
+pub mod output_refs;
+pub mod package_manager;
+pub mod shared_steps;
+pub mod template_source;
 pub mod templates;
+pub mod verify;
+pub mod yaml_source;
 
 use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::path::Path;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use colored::Colorize;
 use tokio::fs;
 
 use crate::cache::Cache;
 use crate::config::Config;
+use crate::credentials::redact;
 use crate::generator::TypeGenerator;
 use crate::parser;
+use crate::source::SourceRouter;
+
+use package_manager::PackageManager;
+use template_source::{TemplateFiles, TemplateSource};
 
 /// CLI options passed from clap.
 pub struct InitOptions {
@@ -21,6 +32,14 @@ pub struct InitOptions {
     pub skip_examples: bool,
     pub migrate: bool,
     pub interactive: bool,
+    /// Starter workflow template: a builtin name, a local directory or
+    /// `gh:owner/repo/path@ref`. Suggested from the project when unset.
+    pub template: Option<String>,
+    /// Package manager name, or `none` to skip package.json. Detected from
+    /// lockfiles when unset.
+    pub package_manager: Option<String>,
+    /// Generate types from vendored and cached metadata only.
+    pub offline: bool,
 }
 
 /// Detected state of the target project directory.
@@ -90,12 +109,61 @@ pub fn detect_project_state(root: &Path) -> Result<ProjectState> {
     }
 }
 
+/// Suggest a starter template from the files in the project root.
+pub fn suggest_template(root: &Path) -> &'static str {
+    let markers = [
+        ("Cargo.toml", "rust"),
+        ("go.mod", "go"),
+        ("pyproject.toml", "python"),
+        ("uv.lock", "python"),
+        ("requirements.txt", "python"),
+        ("pnpm-lock.yaml", "pnpm"),
+        ("package.json", "node"),
+        ("Dockerfile", "docker"),
+    ];
+
+    markers
+        .iter()
+        .find(|(marker, _)| root.join(marker).exists())
+        .map(|(_, template)| *template)
+        .unwrap_or(templates::DEFAULT_TEMPLATE)
+}
+
+/// The template named in `options`, or the one suggested for the project.
+fn resolve_template(root: &Path, options: &InitOptions) -> Result<TemplateSource> {
+    let spec = options
+        .template
+        .as_deref()
+        .unwrap_or_else(|| suggest_template(root));
+    let source = TemplateSource::parse(spec)?;
+    if options.offline && matches!(source, TemplateSource::GitHub(_)) {
+        anyhow::bail!(
+            "Template {} is fetched from GitHub and cannot be used with --offline",
+            spec
+        );
+    }
+    Ok(source)
+}
+
+/// The package manager named in `options`, or the one detected from the
+/// project. `None` means the project gets no package.json.
+fn resolve_package_manager(root: &Path, options: &InitOptions) -> Result<Option<PackageManager>> {
+    match options.package_manager.as_deref() {
+        Some(name) => PackageManager::from_name(name),
+        None => Ok(PackageManager::detect(root)),
+    }
+}
+
 /// Main entry point for the init command.
 pub async fn init_project(root: &Path, options: InitOptions) -> Result<()> {
     println!("{} Initializing gaji project...\n", "🚀".green());
 
+    // Reject unknown template and package manager names before touching the project
+    resolve_template(root, &options)?;
+    resolve_package_manager(root, &options)?;
+
     if options.interactive {
-        return interactive::interactive_init(root).await;
+        return interactive::interactive_init(root, &options).await;
     }
 
     let project_state = detect_project_state(root)?;
@@ -115,15 +183,34 @@ pub(crate) async fn init_new_project(root: &Path, options: &InitOptions) -> Resu
 
     create_directories(root).await?;
     ensure_gitignore(root, false).await?;
+
+    // An empty directory only gets JavaScript tooling when asked for
+    let package_manager = resolve_package_manager(root, options)?;
+    if let Some(manager) = package_manager {
+        package_manager::update_manifest(root, manager).await?;
+    }
+    handle_tsconfig(root, options).await?;
+
+    // Templates may ship their own gaji.config.ts, so apply them first
+    if !options.skip_examples {
+        let template = resolve_template(root, options)?;
+        apply_template(
+            root,
+            &template,
+            &root.join("workflows"),
+            true,
+            options.force,
+        )
+        .await?;
+    }
     create_config(root)?;
 
     if !options.skip_examples {
-        create_example_workflow(root).await?;
-        try_generate_initial_types(root).await;
+        try_generate_initial_types(root, options.offline).await;
     }
 
     println!("\n{} Project initialized!\n", "✨".green());
-    print_next_steps();
+    print_next_steps(package_manager);
 
     Ok(())
 }
@@ -134,31 +221,36 @@ pub(crate) async fn init_existing_project(root: &Path, options: &InitOptions) ->
 
     create_directories(root).await?;
 
-    // Only touch package.json / tsconfig.json if the project already has them
-    if root.join("package.json").exists() {
-        update_package_json(root).await?;
-        handle_tsconfig(root, options).await?;
+    // Non-JavaScript projects only need tsconfig.json for editor support
+    let package_manager = resolve_package_manager(root, options)?;
+    match package_manager {
+        Some(manager) => package_manager::update_manifest(root, manager).await?,
+        None => println!(
+            "{} No JavaScript package manager detected, skipping package.json",
+            "⏭️ ".dimmed()
+        ),
     }
+    handle_tsconfig(root, options).await?;
 
     ensure_gitignore(root, true).await?;
-    create_config(root)?;
 
     if !options.skip_examples {
-        let example_path = root.join("workflows/ci.ts");
-        if !example_path.exists() {
-            create_example_workflow(root).await?;
-        } else {
-            println!(
-                "{} workflows/ci.ts already exists, skipping example",
-                "⏭️ ".dimmed()
-            );
-        }
+        let template = resolve_template(root, options)?;
+        apply_template(
+            root,
+            &template,
+            &root.join("workflows"),
+            true,
+            options.force,
+        )
+        .await?;
     }
+    create_config(root)?;
 
-    try_generate_initial_types(root).await;
+    try_generate_initial_types(root, options.offline).await;
 
     println!("\n{} gaji added to your project!\n", "✨".green());
-    print_next_steps();
+    print_next_steps(package_manager);
 
     Ok(())
 }
@@ -194,12 +286,19 @@ async fn init_with_workflows(root: &Path, options: &InitOptions) -> Result<()> {
     }
 
     if options.migrate {
+        let migrate_options = migration::MigrateOptions {
+            overwrite: true,
+            ..migration::MigrateOptions::new(root.join("workflows"))
+        };
+        let mut reports = Vec::new();
         if !existing_workflows.is_empty() {
-            migration::migrate_workflows(root, &existing_workflows).await?;
+            reports
+                .extend(migration::migrate_workflows(&existing_workflows, &migrate_options).await?);
         }
         if !existing_actions.is_empty() {
-            migration::migrate_actions(root, &existing_actions).await?;
+            reports.extend(migration::migrate_actions(&existing_actions, &migrate_options).await?);
         }
+        migration::print_migration_reports(&reports);
     } else if !existing_workflows.is_empty() || !existing_actions.is_empty() {
         println!(
             "{} Tip: Run with --migrate to convert existing YAML workflows and actions to TypeScript",
@@ -223,49 +322,6 @@ async fn create_directories(root: &Path) -> Result<()> {
     Ok(())
 }
 
-pub(crate) async fn update_package_json(root: &Path) -> Result<()> {
-    let path = root.join("package.json");
-    let content = fs::read_to_string(&path)
-        .await
-        .context("Failed to read package.json")?;
-    let mut package: serde_json::Value =
-        serde_json::from_str(&content).context("Failed to parse package.json")?;
-
-    // Ensure "scripts" object exists
-    if package.get("scripts").is_none() {
-        package["scripts"] = serde_json::json!({});
-    }
-
-    // Merge scripts (do NOT overwrite existing keys)
-    if let Some(scripts) = package["scripts"].as_object_mut() {
-        for &(key, value) in templates::GAJI_SCRIPTS {
-            scripts
-                .entry(key.to_string())
-                .or_insert(serde_json::Value::String(value.to_string()));
-        }
-    }
-
-    // Ensure "devDependencies" object exists
-    if package.get("devDependencies").is_none() {
-        package["devDependencies"] = serde_json::json!({});
-    }
-
-    // Merge devDependencies (do NOT overwrite existing versions)
-    if let Some(dev_deps) = package["devDependencies"].as_object_mut() {
-        for &(key, value) in templates::GAJI_DEV_DEPS {
-            dev_deps
-                .entry(key.to_string())
-                .or_insert(serde_json::Value::String(value.to_string()));
-        }
-    }
-
-    let formatted = serde_json::to_string_pretty(&package)?;
-    fs::write(&path, formatted + "\n").await?;
-    println!("{} Updated package.json", "✓".green());
-
-    Ok(())
-}
-
 pub(crate) async fn handle_tsconfig(root: &Path, options: &InitOptions) -> Result<()> {
     let path = root.join("tsconfig.json");
 
@@ -286,7 +342,7 @@ pub(crate) async fn handle_tsconfig(root: &Path, options: &InitOptions) -> Resul
                 "⚠️ ".yellow()
             );
             println!("   Consider adding to your compilerOptions:");
-            println!("   \"include\": [\"workflows/**/*\"]");
+            println!("   \"include\": [\"workflows/**/*\", \"generated/**/*\"]");
         }
     } else {
         fs::write(&path, templates::TSCONFIG_TEMPLATE).await?;
@@ -323,10 +379,46 @@ pub(crate) async fn ensure_gitignore(root: &Path, may_exist: bool) -> Result<()>
     Ok(())
 }
 
-async fn create_example_workflow(root: &Path) -> Result<()> {
-    let path = root.join("workflows/ci.ts");
-    fs::write(&path, templates::EXAMPLE_WORKFLOW_TEMPLATE).await?;
-    println!("{} Created example workflow (workflows/ci.ts)", "✓".green());
+/// Write a starter template into `workflows_dir`. Local and GitHub templates
+/// prompt for their variables and may also provide `gaji.config.ts`, which is
+/// copied to `root` when `include_config` is set.
+pub(crate) async fn apply_template(
+    root: &Path,
+    source: &TemplateSource,
+    workflows_dir: &Path,
+    include_config: bool,
+    force: bool,
+) -> Result<()> {
+    let template = match source {
+        TemplateSource::Builtin(template) => template,
+        _ => {
+            let files = TemplateFiles::load(source).await?;
+            if let Some(name) = &files.manifest.name {
+                println!("{} Applying template {}", "📋".cyan(), name);
+            }
+            let values = files.prompt_values()?;
+            return files.write(&values, root, workflows_dir, include_config, force);
+        }
+    };
+
+    let path = workflows_dir.join(template.file);
+    let display = path.strip_prefix(root).unwrap_or(&path).display();
+    if path.exists() {
+        println!(
+            "{} {} already exists, skipping example",
+            "⏭️ ".dimmed(),
+            display
+        );
+        return Ok(());
+    }
+
+    fs::write(&path, template.content).await?;
+    println!(
+        "{} Created {} workflow ({})",
+        "✓".green(),
+        template.name,
+        display
+    );
     Ok(())
 }
 
@@ -351,17 +443,21 @@ fn create_config(root: &Path) -> Result<()> {
     Ok(())
 }
 
-pub(crate) async fn try_generate_initial_types(root: &Path) {
-    match generate_initial_types(root).await {
+pub(crate) async fn try_generate_initial_types(root: &Path, offline: bool) {
+    match generate_initial_types(root, offline).await {
         Ok(()) => {}
         Err(e) => {
-            eprintln!("{} Could not generate initial types: {}", "⚠️ ".yellow(), e);
+            eprintln!(
+                "{} Could not generate initial types: {}",
+                "⚠️ ".yellow(),
+                redact(&e.to_string())
+            );
             eprintln!("   Run 'gaji dev' later to generate types.");
         }
     }
 }
 
-async fn generate_initial_types(root: &Path) -> Result<()> {
+async fn generate_initial_types(root: &Path, offline: bool) -> Result<()> {
     let workflows_path = root.join("workflows");
     if !workflows_path.exists() {
         return Ok(());
@@ -390,7 +486,12 @@ async fn generate_initial_types(root: &Path) -> Result<()> {
     let token = config.resolve_token();
     let api_url = config.resolve_api_url();
     let cache = Cache::load_or_create()?;
-    let generator = TypeGenerator::new(cache, root.join("generated"), token, api_url);
+    let generator = TypeGenerator::new(cache, root.join("generated"), token, api_url)?
+        .with_network(&config.network)?
+        .with_local_workflows(root.join(config.output_path()).join("workflows"))
+        .with_type_overrides(config.types.clone())
+        .with_sources(SourceRouter::from_config(&config)?)
+        .with_offline(offline);
     generator.generate_types_for_refs(&all_refs).await?;
 
     println!("{} Types generated!", "✨".green());
@@ -398,11 +499,21 @@ async fn generate_initial_types(root: &Path) -> Result<()> {
     Ok(())
 }
 
-pub(crate) fn print_next_steps() {
+pub(crate) fn print_next_steps(package_manager: Option<PackageManager>) {
+    let run = |script: &str, command: &str| match package_manager {
+        Some(manager) => manager.run_command(script),
+        None => command.to_string(),
+    };
+
     println!("Next steps:");
-    println!("  1. Edit workflows/*.ts");
-    println!("  2. Run: gaji dev");
-    println!("  3. Run: gaji build");
+    let mut step = 1;
+    if let Some(install) = package_manager.and_then(|m| m.install_command()) {
+        println!("  {}. Run: {}", step, install);
+        step += 1;
+    }
+    println!("  {}. Edit workflows/*.ts", step);
+    println!("  {}. Run: {}", step + 1, run("gha:dev", "gaji dev"));
+    println!("  {}. Run: {}", step + 2, run("gha:build", "gaji build"));
     println!();
     println!(
         "{} For private repos or GitHub Enterprise, create gaji.config.local.ts:",
@@ -507,7 +618,9 @@ mod tests {
         )
         .unwrap();
 
-        update_package_json(temp.path()).await.unwrap();
+        package_manager::update_manifest(temp.path(), PackageManager::Npm)
+            .await
+            .unwrap();
 
         let content = std::fs::read_to_string(temp.path().join("package.json")).unwrap();
         let pkg: serde_json::Value = serde_json::from_str(&content).unwrap();
@@ -563,7 +676,16 @@ mod tests {
     async fn test_create_example_workflow() {
         let temp = tempfile::TempDir::new().unwrap();
         std::fs::create_dir_all(temp.path().join("workflows")).unwrap();
-        create_example_workflow(temp.path()).await.unwrap();
+        let template = TemplateSource::parse("node").unwrap();
+        apply_template(
+            temp.path(),
+            &template,
+            &temp.path().join("workflows"),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
         let content = std::fs::read_to_string(temp.path().join("workflows/ci.ts")).unwrap();
         assert!(content.contains("getAction"));
         assert!(content.contains("actions/checkout@v5"));
@@ -578,6 +700,9 @@ mod tests {
             skip_examples: true,
             migrate: false,
             interactive: false,
+            template: None,
+            package_manager: None,
+            offline: false,
         };
 
         init_new_project(temp.path(), &options).await.unwrap();
@@ -588,9 +713,9 @@ mod tests {
         assert!(temp.path().join(".github/workflows").is_dir());
         assert!(temp.path().join("gaji.config.ts").is_file());
         assert!(temp.path().join(".gitignore").is_file());
-        // Should NOT create Node.js-specific files
+        // No package manifest, but tsconfig.json for editor support
         assert!(!temp.path().join("package.json").exists());
-        assert!(!temp.path().join("tsconfig.json").exists());
+        assert!(temp.path().join("tsconfig.json").is_file());
     }
 
     #[tokio::test]
@@ -607,6 +732,9 @@ mod tests {
             skip_examples: true,
             migrate: false,
             interactive: false,
+            template: None,
+            package_manager: None,
+            offline: false,
         };
 
         init_existing_project(temp.path(), &options).await.unwrap();
@@ -630,18 +758,181 @@ mod tests {
             skip_examples: true,
             migrate: false,
             interactive: false,
+            template: None,
+            package_manager: None,
+            offline: false,
         };
 
         init_existing_project(temp.path(), &options).await.unwrap();
 
-        // Should NOT create Node.js-specific files
+        // Should NOT create package.json, but tsconfig.json helps editors
         assert!(!temp.path().join("package.json").exists());
-        assert!(!temp.path().join("tsconfig.json").exists());
+        assert!(temp.path().join("tsconfig.json").is_file());
         // Gaji files should exist
         assert!(temp.path().join("gaji.config.ts").is_file());
         assert!(temp.path().join("workflows").is_dir());
     }
 
+    #[tokio::test]
+    async fn test_init_existing_project_with_pnpm() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("package.json"), r#"{"name": "my-app"}"#).unwrap();
+        std::fs::write(temp.path().join("pnpm-lock.yaml"), "lockfileVersion: '9.0'").unwrap();
+
+        let options = InitOptions {
+            force: false,
+            skip_examples: true,
+            migrate: false,
+            interactive: false,
+            template: None,
+            package_manager: None,
+            offline: false,
+        };
+
+        init_existing_project(temp.path(), &options).await.unwrap();
+
+        let content = std::fs::read_to_string(temp.path().join("package.json")).unwrap();
+        let pkg: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(pkg["scripts"]["gha:dev"], "gaji dev");
+        assert_eq!(pkg["devDependencies"]["tsx"], "^4.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_init_new_project_with_package_manager() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let options = InitOptions {
+            force: false,
+            skip_examples: true,
+            migrate: false,
+            interactive: false,
+            template: None,
+            package_manager: Some("deno".to_string()),
+            offline: false,
+        };
+
+        init_new_project(temp.path(), &options).await.unwrap();
+
+        let content = std::fs::read_to_string(temp.path().join("deno.json")).unwrap();
+        let deno: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(deno["tasks"]["gha:build"], "gaji build");
+        assert!(!temp.path().join("package.json").exists());
+        assert!(temp.path().join("tsconfig.json").is_file());
+    }
+
+    #[test]
+    fn test_suggest_template() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(suggest_template(temp.path()), "node");
+
+        std::fs::write(temp.path().join("pyproject.toml"), "[project]").unwrap();
+        assert_eq!(suggest_template(temp.path()), "python");
+
+        std::fs::write(temp.path().join("Cargo.toml"), "[package]").unwrap();
+        assert_eq!(suggest_template(temp.path()), "rust");
+    }
+
+    #[test]
+    fn test_resolve_template_unknown() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let options = InitOptions {
+            force: false,
+            skip_examples: false,
+            migrate: false,
+            interactive: false,
+            template: Some("cobol".to_string()),
+            package_manager: None,
+            offline: false,
+        };
+        let err = resolve_template(temp.path(), &options).unwrap_err();
+        assert!(err.to_string().contains("Unknown template 'cobol'"));
+        assert!(err.to_string().contains("release-please"));
+    }
+
+    #[test]
+    fn test_workflow_templates_build() {
+        use crate::executor::{execute_js, remove_imports, strip_typescript};
+        use crate::generator::templates::{
+            GET_ACTION_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE,
+        };
+
+        let runtime_js = format!(
+            "var __action_outputs = {{ 'docker/metadata-action@v5': ['tags', 'labels'] }};\n{}\n{}",
+            GET_ACTION_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE
+        );
+        for template in templates::WORKFLOW_TEMPLATES {
+            let js = strip_typescript(template.content, template.file).unwrap();
+            let bundled = format!("{}\n\n{}", remove_imports(&runtime_js), remove_imports(&js));
+            let outputs = execute_js(&bundled)
+                .unwrap_or_else(|e| panic!("template {} failed: {}", template.name, e));
+            assert_eq!(outputs.len(), 1, "template {}", template.name);
+            assert_eq!(
+                format!("{}.ts", outputs[0].id),
+                template.file,
+                "template {}",
+                template.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_init_new_project_with_template() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("workflows")).unwrap();
+        let options = InitOptions {
+            force: false,
+            skip_examples: false,
+            migrate: false,
+            interactive: false,
+            template: Some("docker".to_string()),
+            package_manager: None,
+            offline: false,
+        };
+
+        let template = resolve_template(temp.path(), &options).unwrap();
+        apply_template(
+            temp.path(),
+            &template,
+            &temp.path().join("workflows"),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+
+        let content = std::fs::read_to_string(temp.path().join("workflows/docker.ts")).unwrap();
+        assert!(content.contains("docker/build-push-action@v6"));
+        assert!(!temp.path().join("workflows/ci.ts").exists());
+    }
+
+    #[tokio::test]
+    async fn test_init_new_project_with_local_template() {
+        let template = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(template.path().join("workflows")).unwrap();
+        std::fs::write(
+            template.path().join("gaji.config.ts"),
+            "export default { workflows: \"workflows\" }; // org\n",
+        )
+        .unwrap();
+        std::fs::write(template.path().join("workflows/lint.ts"), "export {};\n").unwrap();
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let options = InitOptions {
+            force: false,
+            skip_examples: false,
+            migrate: false,
+            interactive: false,
+            template: Some(template.path().to_string_lossy().to_string()),
+            package_manager: None,
+            offline: false,
+        };
+        init_new_project(temp.path(), &options).await.unwrap();
+
+        assert!(temp.path().join("workflows/lint.ts").exists());
+        assert!(!temp.path().join("workflows/ci.ts").exists());
+        let config = std::fs::read_to_string(temp.path().join("gaji.config.ts")).unwrap();
+        assert!(config.contains("// org"));
+    }
+
     #[test]
     fn test_create_config() {
         let temp = tempfile::TempDir::new().unwrap();
@@ -0,0 +1,373 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Output references that can be expressed through the typed `output`
+/// callbacks while migrating a workflow.
+///
+/// Only expressions that consist of a single `steps.<id>.outputs.<name>` or
+/// `needs.<job>.outputs.<name>` reference are rewritten; anything more
+/// involved (`|| 'default'`, comparisons, ...) stays a plain string.
+#[derive(Debug, Default)]
+pub struct OutputRefs {
+    /// Callback parameter for step outputs, when step outputs are in scope.
+    step_param: Option<&'static str>,
+    /// Callback parameter for job outputs, when job outputs are in scope.
+    job_param: Option<&'static str>,
+    /// `getAction` steps with an `id` declared so far in the current job.
+    steps: HashSet<String>,
+    /// Jobs declared so far whose outputs are known, with their output names.
+    jobs: HashMap<String, HashSet<String>>,
+    used_steps: Cell<bool>,
+    used_jobs: Cell<bool>,
+}
+
+impl OutputRefs {
+    /// Parameter name used by `.add(output => ...)` step callbacks.
+    pub const OUTPUT: &'static str = "output";
+    /// Parameter name used by step callbacks nested in a job callback, so the
+    /// job-level `output` is not shadowed.
+    pub const STEPS: &'static str = "steps";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new job. Step ids from the previous job go out of scope.
+    pub fn enter_job(&mut self, step_param: Option<&'static str>, job_param: Option<&'static str>) {
+        self.step_param = step_param;
+        self.job_param = job_param;
+        self.steps.clear();
+        self.used_steps.set(false);
+        self.used_jobs.set(false);
+    }
+
+    /// Change which contexts are in scope without forgetting declared steps.
+    pub fn set_scope(&mut self, step_param: Option<&'static str>, job_param: Option<&'static str>) {
+        self.step_param = step_param;
+        self.job_param = job_param;
+    }
+
+    pub fn step_param(&self) -> Option<&'static str> {
+        self.step_param
+    }
+
+    pub fn add_step(&mut self, id: &str) {
+        self.steps.insert(id.to_string());
+    }
+
+    pub fn add_job(&mut self, id: &str, outputs: impl IntoIterator<Item = String>) {
+        self.jobs
+            .insert(id.to_string(), outputs.into_iter().collect());
+    }
+
+    /// Whether a step output was rewritten since the last call, resetting the flag.
+    pub fn take_used_steps(&self) -> bool {
+        self.used_steps.replace(false)
+    }
+
+    /// Whether a job output was rewritten since the last call, resetting the flag.
+    pub fn take_used_jobs(&self) -> bool {
+        self.used_jobs.replace(false)
+    }
+
+    /// Rewrite output references in `s` into a JavaScript expression.
+    ///
+    /// Returns `None` when nothing could be rewritten, so the caller emits the
+    /// string literal as before.
+    pub fn rewrite(&self, s: &str) -> Option<String> {
+        let mut parts: Vec<Part> = Vec::new();
+        let mut rewritten = false;
+        let mut rest = s;
+
+        while let Some((before, inner, after)) = next_expression(rest) {
+            match self.resolve(inner) {
+                Some(expr) => {
+                    parts.push(Part::Text(before));
+                    parts.push(Part::Expr(expr));
+                    rewritten = true;
+                }
+                None => {
+                    let consumed = s.len() - after.len();
+                    let start = s.len() - rest.len();
+                    parts.push(Part::Text(&s[start..consumed]));
+                }
+            }
+            rest = after;
+        }
+        parts.push(Part::Text(rest));
+
+        if !rewritten {
+            return None;
+        }
+
+        parts.retain(|p| !matches!(p, Part::Text("")));
+        if let [Part::Expr(expr)] = parts.as_slice() {
+            return Some(expr.clone());
+        }
+
+        let mut literal = String::from("`");
+        for part in parts {
+            match part {
+                Part::Text(text) => literal.push_str(&escape_template_text(text)),
+                Part::Expr(expr) => {
+                    literal.push_str("${");
+                    literal.push_str(&expr);
+                    literal.push('}');
+                }
+            }
+        }
+        literal.push('`');
+        Some(literal)
+    }
+
+    fn resolve(&self, inner: &str) -> Option<String> {
+        let (context, id, name) = parse_output_ref(inner)?;
+        match context {
+            "steps" => {
+                let param = self.step_param?;
+                if !self.steps.contains(id) {
+                    return None;
+                }
+                self.used_steps.set(true);
+                Some(format!("{}{}{}", param, js_property(id), js_property(name)))
+            }
+            "needs" => {
+                let param = self.job_param?;
+                if !self.jobs.get(id)?.contains(name) {
+                    return None;
+                }
+                self.used_jobs.set(true);
+                Some(format!("{}{}{}", param, js_property(id), js_property(name)))
+            }
+            _ => None,
+        }
+    }
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Expr(String),
+}
+
+/// Split off the next `${{ ... }}` expression: text before it, the trimmed
+/// expression body and the remainder after it.
+fn next_expression(s: &str) -> Option<(&str, &str, &str)> {
+    let start = s.find("${{")?;
+    let end = start + 3 + s[start + 3..].find("}}")?;
+    Some((&s[..start], s[start + 3..end].trim(), &s[end + 2..]))
+}
+
+/// Parse `steps.<id>.outputs.<name>` / `needs.<id>.outputs.<name>`.
+fn parse_output_ref(expr: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = expr.split('.');
+    let context = parts.next()?;
+    let id = parts.next()?;
+    if parts.next()? != "outputs" {
+        return None;
+    }
+    let name = parts.next()?;
+    if parts.next().is_some() || !is_name(id) || !is_name(name) {
+        return None;
+    }
+    Some((context, id, name))
+}
+
+fn is_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Property access for an id or output name: `.name` or `["name-with-dash"]`.
+fn js_property(name: &str) -> String {
+    let is_identifier = !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        format!(".{}", name)
+    } else {
+        format!("[\"{}\"]", name)
+    }
+}
+
+fn escape_template_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${")
+}
+
+/// Action outputs referenced through `steps.<id>.outputs.<name>` in a
+/// workflow, keyed by the action ref of the step with that id.
+pub fn referenced_action_outputs(
+    workflow: &serde_yaml::Value,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut referenced: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let Some(jobs) = workflow.get("jobs").and_then(|j| j.as_mapping()) else {
+        return referenced;
+    };
+
+    for job in jobs.values() {
+        let step_actions: HashMap<&str, &str> = job
+            .get("steps")
+            .and_then(|s| s.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|step| {
+                let id = step.get("id")?.as_str()?;
+                let uses = step.get("uses")?.as_str()?;
+                Some((id, uses))
+            })
+            .collect();
+
+        visit_strings(job, &mut |s| {
+            let mut rest = s;
+            while let Some((_, inner, after)) = next_expression(rest) {
+                if let Some(("steps", id, name)) = parse_output_ref(inner) {
+                    if let Some(uses) = step_actions.get(id) {
+                        referenced
+                            .entry(uses.to_string())
+                            .or_default()
+                            .insert(name.to_string());
+                    }
+                }
+                rest = after;
+            }
+        });
+    }
+
+    referenced
+}
+
+fn visit_strings(value: &serde_yaml::Value, f: &mut impl FnMut(&str)) {
+    match value {
+        serde_yaml::Value::String(s) => f(s),
+        serde_yaml::Value::Sequence(seq) => seq.iter().for_each(|v| visit_strings(v, f)),
+        serde_yaml::Value::Mapping(map) => map.values().for_each(|v| visit_strings(v, f)),
+        _ => {}
+    }
+}
+
+/// Indent every line after the first by `pad`, leaving the contents of
+/// template literals untouched so multi-line `run` scripts keep their text.
+pub fn indent_js(code: &str, pad: &str) -> String {
+    #[derive(PartialEq)]
+    enum State {
+        Code,
+        DoubleQuoted,
+        Template,
+    }
+
+    let mut result = String::with_capacity(code.len());
+    let mut state = State::Code;
+    let mut escaped = false;
+
+    for (i, line) in code.split('\n').enumerate() {
+        if i > 0 {
+            result.push('\n');
+            if state != State::Template && !line.is_empty() {
+                result.push_str(pad);
+            }
+        }
+        result.push_str(line);
+
+        for c in line.chars() {
+            if escaped {
+                escaped = false;
+                continue;
+            }
+            state = match (state, c) {
+                (State::Code, '"') => State::DoubleQuoted,
+                (State::Code, '`') => State::Template,
+                (State::DoubleQuoted, '"') | (State::Template, '`') => State::Code,
+                (s, '\\') if s != State::Code => {
+                    escaped = true;
+                    s
+                }
+                (s, _) => s,
+            };
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refs() -> OutputRefs {
+        let mut refs = OutputRefs::new();
+        refs.add_job("build", ["version".to_string()]);
+        refs.enter_job(Some(OutputRefs::OUTPUT), Some(OutputRefs::OUTPUT));
+        refs.add_step("co");
+        refs.add_step("setup-node");
+        refs
+    }
+
+    #[test]
+    fn test_rewrite_whole_expression() {
+        let refs = refs();
+        assert_eq!(
+            refs.rewrite("${{ steps.co.outputs.ref }}").unwrap(),
+            "output.co.ref"
+        );
+        assert_eq!(
+            refs.rewrite("${{ steps.setup-node.outputs.cache-hit }}")
+                .unwrap(),
+            "output[\"setup-node\"][\"cache-hit\"]"
+        );
+        assert!(refs.take_used_steps());
+        assert!(!refs.take_used_jobs());
+    }
+
+    #[test]
+    fn test_rewrite_embedded_expression() {
+        let refs = refs();
+        assert_eq!(
+            refs.rewrite("v=${{ needs.build.outputs.version }} `x` ${{ github.sha }}")
+                .unwrap(),
+            "`v=${output.build.version} \\`x\\` \\${{ github.sha }}`"
+        );
+        assert!(refs.take_used_jobs());
+    }
+
+    #[test]
+    fn test_rewrite_leaves_unknown_references() {
+        let refs = refs();
+        // Unknown step, unknown job output, and compound expressions
+        assert!(refs.rewrite("${{ steps.other.outputs.ref }}").is_none());
+        assert!(refs.rewrite("${{ needs.build.outputs.other }}").is_none());
+        assert!(refs
+            .rewrite("${{ steps.co.outputs.ref || 'main' }}")
+            .is_none());
+        assert!(!refs.take_used_steps());
+    }
+
+    #[test]
+    fn test_referenced_action_outputs() {
+        let workflow: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+jobs:
+  build:
+    steps:
+      - id: co
+        uses: actions/checkout@v5
+      - run: echo ${{ steps.co.outputs.ref }} ${{ steps.co.outputs.commit }}
+"#,
+        )
+        .unwrap();
+        let referenced = referenced_action_outputs(&workflow);
+        assert_eq!(
+            referenced["actions/checkout@v5"],
+            BTreeSet::from(["commit".to_string(), "ref".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_indent_js_skips_template_literals() {
+        let code = "new Job(\"a\")\n    .steps(s => s\n        .add({ run: `one\ntwo \\` ${x}\n` })\n    )";
+        assert_eq!(
+            indent_js(code, "  "),
+            "new Job(\"a\")\n      .steps(s => s\n          .add({ run: `one\ntwo \\` ${x}\n` })\n      )"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use tokio::fs;
+
+use super::templates;
+
+/// JavaScript package manager used by the project, which decides where the
+/// gaji scripts go and how they are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+    Deno,
+}
+
+/// Lockfiles in the order they are checked.
+const LOCKFILES: &[(&str, PackageManager)] = &[
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("yarn.lock", PackageManager::Yarn),
+    ("bun.lock", PackageManager::Bun),
+    ("bun.lockb", PackageManager::Bun),
+    ("deno.lock", PackageManager::Deno),
+    ("package-lock.json", PackageManager::Npm),
+    ("npm-shrinkwrap.json", PackageManager::Npm),
+];
+
+/// Names accepted by `--package-manager`.
+pub const PACKAGE_MANAGER_NAMES: &[&str] = &["npm", "pnpm", "yarn", "bun", "deno", "none"];
+
+impl PackageManager {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Pnpm => "pnpm",
+            Self::Yarn => "yarn",
+            Self::Bun => "bun",
+            Self::Deno => "deno",
+        }
+    }
+
+    /// Parse a `--package-manager` value. `none` means the project is not a
+    /// JavaScript project and gets no package.json.
+    pub fn from_name(name: &str) -> Result<Option<Self>> {
+        Ok(Some(match name {
+            "npm" => Self::Npm,
+            "pnpm" => Self::Pnpm,
+            "yarn" => Self::Yarn,
+            "bun" => Self::Bun,
+            "deno" => Self::Deno,
+            "none" => return Ok(None),
+            _ => bail!(
+                "Unknown package manager '{}'. Expected one of: {}",
+                name,
+                PACKAGE_MANAGER_NAMES.join(", ")
+            ),
+        }))
+    }
+
+    /// Detect the package manager from the `packageManager` field of
+    /// package.json, then lockfiles, then package.json or deno.json alone.
+    /// Returns `None` for projects without JavaScript tooling.
+    pub fn detect(root: &Path) -> Option<Self> {
+        let package_json = root.join("package.json");
+
+        if let Some(field) = std::fs::read_to_string(&package_json)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|package| package["packageManager"].as_str().map(str::to_string))
+        {
+            let name = field.split('@').next().unwrap_or_default();
+            if let Ok(Some(manager)) = Self::from_name(name) {
+                return Some(manager);
+            }
+        }
+
+        if let Some((_, manager)) = LOCKFILES.iter().find(|(file, _)| root.join(file).exists()) {
+            return Some(*manager);
+        }
+
+        if package_json.exists() {
+            Some(Self::Npm)
+        } else if root.join("deno.json").exists() || root.join("deno.jsonc").exists() {
+            Some(Self::Deno)
+        } else {
+            None
+        }
+    }
+
+    /// Dev dependencies added to package.json. Bun and Deno run TypeScript
+    /// natively, so only Node needs `tsx` for the build fallback.
+    pub fn dev_dependencies(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Npm | Self::Pnpm | Self::Yarn => templates::GAJI_DEV_DEPS,
+            Self::Bun | Self::Deno => &[],
+        }
+    }
+
+    /// Command that installs the project's dependencies, if any are needed.
+    pub fn install_command(self) -> Option<&'static str> {
+        match self {
+            Self::Npm => Some("npm install"),
+            Self::Pnpm => Some("pnpm install"),
+            Self::Yarn => Some("yarn install"),
+            Self::Bun => Some("bun install"),
+            Self::Deno => None,
+        }
+    }
+
+    /// Command that runs one of the gaji scripts.
+    pub fn run_command(self, script: &str) -> String {
+        match self {
+            Self::Npm => format!("npm run {}", script),
+            Self::Pnpm => format!("pnpm {}", script),
+            Self::Yarn => format!("yarn {}", script),
+            Self::Bun => format!("bun run {}", script),
+            Self::Deno => format!("deno task {}", script),
+        }
+    }
+}
+
+/// Add the gaji scripts to the project's manifest: `tasks` in deno.json for
+/// Deno, `scripts` and `devDependencies` in package.json otherwise. The
+/// manifest is created when missing and existing entries are never replaced.
+pub(crate) async fn update_manifest(root: &Path, manager: PackageManager) -> Result<()> {
+    if manager == PackageManager::Deno {
+        return update_deno_json(root).await;
+    }
+
+    let path = root.join("package.json");
+    let (mut package, created) = if path.exists() {
+        let content = fs::read_to_string(&path)
+            .await
+            .context("Failed to read package.json")?;
+        let package: serde_json::Value =
+            serde_json::from_str(&content).context("Failed to parse package.json")?;
+        (package, false)
+    } else {
+        let package = serde_json::json!({
+            "name": root.file_name().unwrap_or_default().to_string_lossy(),
+            "version": "0.0.0",
+            "private": true,
+        });
+        (package, true)
+    };
+
+    merge_entries(&mut package, "scripts", templates::GAJI_SCRIPTS);
+    if !manager.dev_dependencies().is_empty() {
+        merge_entries(&mut package, "devDependencies", manager.dev_dependencies());
+    }
+
+    let formatted = serde_json::to_string_pretty(&package)?;
+    fs::write(&path, formatted + "\n").await?;
+    println!(
+        "{} {} package.json ({})",
+        "✓".green(),
+        if created { "Created" } else { "Updated" },
+        manager.name()
+    );
+
+    Ok(())
+}
+
+async fn update_deno_json(root: &Path) -> Result<()> {
+    if root.join("deno.jsonc").exists() {
+        println!(
+            "{} deno.jsonc found, add the gaji tasks manually:",
+            "💡".yellow()
+        );
+        for (key, value) in templates::GAJI_SCRIPTS {
+            println!("   \"{}\": \"{}\"", key, value);
+        }
+        return Ok(());
+    }
+
+    let path = root.join("deno.json");
+    let (mut deno, created) = if path.exists() {
+        let content = fs::read_to_string(&path)
+            .await
+            .context("Failed to read deno.json")?;
+        let deno: serde_json::Value =
+            serde_json::from_str(&content).context("Failed to parse deno.json")?;
+        (deno, false)
+    } else {
+        (serde_json::json!({}), true)
+    };
+
+    merge_entries(&mut deno, "tasks", templates::GAJI_SCRIPTS);
+
+    let formatted = serde_json::to_string_pretty(&deno)?;
+    fs::write(&path, formatted + "\n").await?;
+    println!(
+        "{} {} deno.json tasks",
+        "✓".green(),
+        if created { "Created" } else { "Updated" }
+    );
+
+    Ok(())
+}
+
+/// Insert `entries` into the object at `key`, keeping existing values.
+fn merge_entries(manifest: &mut serde_json::Value, key: &str, entries: &[(&str, &str)]) {
+    if manifest.get(key).is_none() {
+        manifest[key] = serde_json::json!({});
+    }
+
+    if let Some(object) = manifest[key].as_object_mut() {
+        for &(name, value) in entries {
+            object
+                .entry(name.to_string())
+                .or_insert(serde_json::Value::String(value.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_package_manager() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(PackageManager::detect(temp.path()), None);
+
+        std::fs::write(temp.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            PackageManager::detect(temp.path()),
+            Some(PackageManager::Npm)
+        );
+
+        std::fs::write(temp.path().join("bun.lockb"), "").unwrap();
+        assert_eq!(
+            PackageManager::detect(temp.path()),
+            Some(PackageManager::Bun)
+        );
+
+        std::fs::write(temp.path().join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(
+            PackageManager::detect(temp.path()),
+            Some(PackageManager::Pnpm)
+        );
+
+        // The packageManager field wins over lockfiles
+        std::fs::write(
+            temp.path().join("package.json"),
+            r#"{"packageManager": "yarn@4.1.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            PackageManager::detect(temp.path()),
+            Some(PackageManager::Yarn)
+        );
+
+        let deno = tempfile::TempDir::new().unwrap();
+        std::fs::write(deno.path().join("deno.json"), "{}").unwrap();
+        assert_eq!(
+            PackageManager::detect(deno.path()),
+            Some(PackageManager::Deno)
+        );
+    }
+
+    #[test]
+    fn test_package_manager_from_name() {
+        assert_eq!(
+            PackageManager::from_name("pnpm").unwrap(),
+            Some(PackageManager::Pnpm)
+        );
+        assert_eq!(PackageManager::from_name("none").unwrap(), None);
+        assert!(PackageManager::from_name("pip").is_err());
+        assert_eq!(
+            PackageManager::Pnpm.run_command("gha:build"),
+            "pnpm gha:build"
+        );
+        assert_eq!(
+            PackageManager::Deno.run_command("gha:build"),
+            "deno task gha:build"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_manifest_bun() {
+        let temp = tempfile::TempDir::new().unwrap();
+        update_manifest(temp.path(), PackageManager::Bun)
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(temp.path().join("package.json")).unwrap();
+        let pkg: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(pkg["scripts"]["gha:build"], "gaji build");
+        assert!(pkg.get("devDependencies").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_manifest_deno() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("deno.json"),
+            r#"{"tasks": {"gha:dev": "custom"}}"#,
+        )
+        .unwrap();
+        update_manifest(temp.path(), PackageManager::Deno)
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(temp.path().join("deno.json")).unwrap();
+        let deno: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(deno["tasks"]["gha:dev"], "custom");
+        assert_eq!(deno["tasks"]["gha:build"], "gaji build");
+        assert!(!temp.path().join("package.json").exists());
+    }
+}
@@ -0,0 +1,561 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::Value;
+
+use super::migration::action_to_var_name;
+
+/// Shortest step sequence worth extracting into a helper.
+const MIN_SEQUENCE_LEN: usize = 2;
+
+/// Names a parameter cannot take in the generated helper.
+const RESERVED: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+/// Step sequences repeated across migrated workflows, extracted into helper
+/// functions by `gaji migrate --dedupe`.
+#[derive(Debug, Default)]
+pub struct SharedSteps {
+    pub helpers: Vec<SharedHelper>,
+}
+
+/// A helper function returning one repeated step sequence.
+#[derive(Debug)]
+pub struct SharedHelper {
+    pub name: String,
+    /// The steps of the first occurrence.
+    pub steps: Vec<Value>,
+    /// `with` inputs whose values differ between occurrences.
+    pub params: Vec<HelperParam>,
+    pub occurrences: Vec<Occurrence>,
+}
+
+/// A helper parameter standing in for one `with` input of one step.
+#[derive(Debug)]
+pub struct HelperParam {
+    pub name: String,
+    /// Index of the step in the sequence.
+    pub step: usize,
+    pub input: String,
+}
+
+/// Where a helper replaces steps, and the values it is called with.
+#[derive(Debug)]
+pub struct Occurrence {
+    /// Empty when the steps are inside another helper, named by `job`.
+    pub workflow: String,
+    pub job: String,
+    pub start: usize,
+    /// One value per helper parameter.
+    pub args: Vec<Value>,
+}
+
+/// Steps of one job or helper, with the signature used to match them.
+struct JobSteps {
+    workflow: String,
+    job: String,
+    steps: Vec<Value>,
+    signatures: Vec<Option<String>>,
+    claimed: Vec<bool>,
+}
+
+impl SharedSteps {
+    /// Find step sequences repeated in at least two jobs.
+    ///
+    /// Sequences match when their steps are identical except for `with`
+    /// input values; differing values become helper parameters. Longer
+    /// sequences are extracted first, and a step belongs to at most one
+    /// helper. A shorter sequence repeated inside a longer helper is
+    /// extracted once and called from that helper's body. `workflows` pairs
+    /// each workflow id with its parsed YAML.
+    pub fn find(workflows: &[(String, Value)]) -> Self {
+        let mut jobs: Vec<JobSteps> = Vec::new();
+        for (workflow_id, workflow) in workflows {
+            let Some(job_map) = workflow.get("jobs").and_then(|j| j.as_mapping()) else {
+                continue;
+            };
+            for (job_id, job_def) in job_map {
+                let (Some(job_id), Some(steps)) = (
+                    job_id.as_str(),
+                    job_def.get("steps").and_then(|s| s.as_sequence()),
+                ) else {
+                    continue;
+                };
+                jobs.push(JobSteps {
+                    workflow: workflow_id.clone(),
+                    job: job_id.to_string(),
+                    steps: steps.clone(),
+                    signatures: steps.iter().map(step_signature).collect(),
+                    claimed: vec![false; steps.len()],
+                });
+            }
+        }
+
+        let mut shared = SharedSteps::default();
+        let longest = jobs.iter().map(|j| j.steps.len()).max().unwrap_or(0);
+
+        for len in (MIN_SEQUENCE_LEN..=longest).rev() {
+            // Candidate windows grouped by signature, in order of first appearance
+            let mut order: Vec<Vec<String>> = Vec::new();
+            let mut windows: HashMap<Vec<String>, Vec<(usize, usize)>> = HashMap::new();
+            for (job_index, job) in jobs.iter().enumerate() {
+                for start in 0..(job.steps.len() + 1).saturating_sub(len) {
+                    let range = start..start + len;
+                    if job.claimed[range.clone()].iter().any(|c| *c) {
+                        continue;
+                    }
+                    let Some(key) = job.signatures[range].iter().cloned().collect() else {
+                        continue;
+                    };
+                    windows
+                        .entry(key)
+                        .or_insert_with_key(|key| {
+                            order.push(key.clone());
+                            Vec::new()
+                        })
+                        .push((job_index, start));
+                }
+            }
+
+            for key in order {
+                // Overlapping windows within one job cannot both be replaced
+                let mut picked: Vec<(usize, usize)> = Vec::new();
+                for &(job_index, start) in &windows[&key] {
+                    let job = &jobs[job_index];
+                    let free = !job.claimed[start..start + len].iter().any(|c| *c);
+                    let overlaps = picked
+                        .iter()
+                        .any(|&(j, s)| j == job_index && start < s + len && s < start + len);
+                    if free && !overlaps {
+                        picked.push((job_index, start));
+                    }
+                }
+                if picked.len() < 2 {
+                    continue;
+                }
+
+                for &(job_index, start) in &picked {
+                    jobs[job_index].claimed[start..start + len].fill(true);
+                }
+                let occurrences: Vec<(&JobSteps, usize)> =
+                    picked.iter().map(|&(j, s)| (&jobs[j], s)).collect();
+                let name = format!("helper{}", shared.helpers.len());
+                let helper = build_helper(&occurrences, len, name);
+                // Shorter sequences are also looked for in the helper's body
+                jobs.push(helper_body(&helper));
+                shared.helpers.push(helper);
+            }
+        }
+
+        // Shorter helpers are named first, so the sequences the others build
+        // on get the short names
+        let mut by_length: Vec<usize> = (0..shared.helpers.len()).collect();
+        by_length.sort_by_key(|&i| shared.helpers[i].steps.len());
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        for i in by_length {
+            let helper = &mut shared.helpers[i];
+            let name = helper_name(&helper.steps, &taken);
+            taken.insert(name.clone());
+            renamed.insert(std::mem::replace(&mut helper.name, name.clone()), name);
+        }
+        for occurrence in shared.helpers.iter_mut().flat_map(|h| &mut h.occurrences) {
+            if occurrence.workflow.is_empty() {
+                occurrence.job = renamed[&occurrence.job].clone();
+            }
+        }
+
+        // Parameters must not shadow the action constants of shared.ts
+        let actions: HashSet<String> = shared
+            .actions()
+            .iter()
+            .map(|a| action_to_var_name(a))
+            .collect();
+        for helper in &mut shared.helpers {
+            let mut names: HashSet<String> = actions.clone();
+            names.extend(taken.iter().cloned());
+            for param in &mut helper.params {
+                param.name = unique_name(&param.name, &names);
+                names.insert(param.name.clone());
+            }
+        }
+
+        shared
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.helpers.is_empty()
+    }
+
+    /// All action refs used by the helpers, sorted.
+    pub fn actions(&self) -> Vec<String> {
+        let mut actions: Vec<String> = self
+            .helpers
+            .iter()
+            .flat_map(|h| &h.steps)
+            .filter_map(|step| step.get("uses").and_then(|v| v.as_str()))
+            .map(String::from)
+            .collect();
+        actions.sort();
+        actions.dedup();
+        actions
+    }
+
+    /// Names of the helpers a workflow calls.
+    pub fn used_by(&self, workflow: &str) -> Vec<&str> {
+        self.helpers
+            .iter()
+            .filter(|h| h.occurrences.iter().any(|o| o.workflow == workflow))
+            .map(|h| h.name.as_str())
+            .collect()
+    }
+
+    /// Whether step `index` of `job` is replaced by a helper.
+    pub fn covers(&self, workflow: &str, job: &str, index: usize) -> bool {
+        self.helpers.iter().any(|helper| {
+            helper.occurrences.iter().any(|o| {
+                o.workflow == workflow
+                    && o.job == job
+                    && (o.start..o.start + helper.steps.len()).contains(&index)
+            })
+        })
+    }
+
+    /// The helper called from the body of `helper` at step `index`.
+    pub fn nested_at(&self, helper: &str, index: usize) -> Option<(&SharedHelper, &Occurrence)> {
+        self.at("", helper, index)
+    }
+
+    /// The helper that replaces the steps of `job` starting at `index`.
+    pub fn at(
+        &self,
+        workflow: &str,
+        job: &str,
+        index: usize,
+    ) -> Option<(&SharedHelper, &Occurrence)> {
+        self.helpers.iter().find_map(|helper| {
+            helper
+                .occurrences
+                .iter()
+                .find(|o| o.workflow == workflow && o.job == job && o.start == index)
+                .map(|occurrence| (helper, occurrence))
+        })
+    }
+}
+
+fn build_helper(occurrences: &[(&JobSteps, usize)], len: usize, name: String) -> SharedHelper {
+    let (first, first_start) = occurrences[0];
+    let steps = first.steps[first_start..first_start + len].to_vec();
+
+    // Inputs whose value is not the same everywhere become parameters
+    let mut params = Vec::new();
+    for (index, step) in steps.iter().enumerate() {
+        let Some(with) = step.get("with").and_then(|w| w.as_mapping()) else {
+            continue;
+        };
+        for (key, value) in with {
+            let differs = occurrences.iter().any(|(job, start)| {
+                job.steps[start + index]
+                    .get("with")
+                    .and_then(|w| w.get(key))
+                    != Some(value)
+            });
+            if let (true, Some(input)) = (differs, key.as_str()) {
+                params.push(HelperParam {
+                    name: action_to_var_name(input),
+                    step: index,
+                    input: input.to_string(),
+                });
+            }
+        }
+    }
+
+    let occurrences = occurrences
+        .iter()
+        .map(|(job, start)| Occurrence {
+            workflow: job.workflow.to_string(),
+            job: job.job.to_string(),
+            start: *start,
+            args: params
+                .iter()
+                .map(|param| {
+                    job.steps[start + param.step]
+                        .get("with")
+                        .and_then(|w| w.get(param.input.as_str()))
+                        .cloned()
+                        .unwrap_or(Value::Null)
+                })
+                .collect(),
+        })
+        .collect();
+
+    SharedHelper {
+        name,
+        steps,
+        params,
+        occurrences,
+    }
+}
+
+/// The steps of `helper` as a job of their own, so that shorter sequences
+/// repeated inside it can be extracted from it too. Inputs that are
+/// parameters of `helper` get a marker value no other step has, so a nested
+/// helper takes them as parameters as well.
+fn helper_body(helper: &SharedHelper) -> JobSteps {
+    let mut steps = helper.steps.clone();
+    for param in &helper.params {
+        if let Some(Value::Mapping(with)) = steps[param.step].get_mut("with") {
+            let marker = TaggedValue {
+                tag: Tag::new(PARAM_TAG),
+                value: Value::String(format!("{}.{}.{}", helper.name, param.step, param.input)),
+            };
+            with.insert(
+                Value::String(param.input.clone()),
+                Value::Tagged(Box::new(marker)),
+            );
+        }
+    }
+    JobSteps {
+        workflow: String::new(),
+        job: helper.name.clone(),
+        signatures: steps.iter().map(step_signature).collect(),
+        claimed: vec![false; steps.len()],
+        steps,
+    }
+}
+
+/// Tag of the values that stand for a parameter of the enclosing helper.
+const PARAM_TAG: &str = "param";
+
+/// Whether `value`, an argument of a nested helper call, is a parameter of
+/// the helper making the call rather than a literal value.
+pub fn is_param_marker(value: &Value) -> bool {
+    matches!(value, Value::Tagged(tagged) if tagged.tag == PARAM_TAG)
+}
+
+/// Name a helper after the actions it runs: checkout and setup-node give
+/// `checkoutSetupNodeSteps`. When that name is taken, it names every action
+/// and the commands of `run` steps as well, e.g. `checkoutSetupNodeNpmCiSteps`.
+fn helper_name(steps: &[Value], taken: &HashSet<String>) -> String {
+    let name = steps_name(steps, 3, false);
+    if taken.contains(&name) {
+        unique_name(&steps_name(steps, usize::MAX, true), taken)
+    } else {
+        unique_name(&name, taken)
+    }
+}
+
+fn steps_name(steps: &[Value], max_parts: usize, with_commands: bool) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for step in steps {
+        let part = match (step.get("uses"), step.get("run")) {
+            (Some(uses), _) => uses.as_str().map(action_to_var_name),
+            (None, Some(run)) if with_commands => run.as_str().map(command_name),
+            _ => None,
+        };
+        if let Some(part) = part.filter(|p| !p.is_empty()) {
+            if !parts.contains(&part) && parts.len() < max_parts {
+                parts.push(part);
+            }
+        }
+    }
+    if parts.is_empty() {
+        return "sharedSteps".to_string();
+    }
+
+    let mut name = parts[0].clone();
+    for part in &parts[1..] {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    name.push_str("Steps");
+    name
+}
+
+/// The first two words of a command in camelCase: `npm ci` gives `npmCi`.
+fn command_name(run: &str) -> String {
+    let mut name = String::new();
+    let words = run
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .take(2);
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            if name.is_empty() {
+                name.extend(first.to_lowercase());
+            } else {
+                name.extend(first.to_uppercase());
+            }
+            name.push_str(chars.as_str());
+        }
+    }
+    name
+}
+
+fn unique_name(base: &str, taken: &HashSet<String>) -> String {
+    let base = if RESERVED.contains(&base) || base.is_empty() {
+        format!("{}Value", base)
+    } else {
+        base.to_string()
+    };
+    if !taken.contains(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{}{}", base, n))
+        .find(|name| !taken.contains(name))
+        .unwrap_or(base)
+}
+
+/// What a step must match to share a helper: the whole step with `with`
+/// values blanked out. Steps with an `id` or that read outputs are left in
+/// place, since later steps and output callbacks refer to them.
+fn step_signature(step: &Value) -> Option<String> {
+    let map = step.as_mapping()?;
+    if map.contains_key("id") || !(map.contains_key("uses") || map.contains_key("run")) {
+        return None;
+    }
+    let yaml = serde_yaml::to_string(step).ok()?;
+    if yaml.contains(".outputs.") {
+        return None;
+    }
+
+    let mut shape = map.clone();
+    if let Some(Value::Mapping(with)) = shape.get_mut("with") {
+        for value in with.values_mut() {
+            *value = Value::Null;
+        }
+    }
+    serde_yaml::to_string(&shape).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workflow(id: &str, yaml: &str) -> (String, Value) {
+        (id.to_string(), serde_yaml::from_str(yaml).unwrap())
+    }
+
+    #[test]
+    fn test_find_shared_steps_parameterizes_inputs() {
+        let workflows = [
+            workflow(
+                "ci",
+                r#"
+jobs:
+  test:
+    steps:
+      - uses: actions/checkout@v5
+      - uses: actions/setup-node@v4
+        with:
+          node-version: "20"
+          cache: npm
+      - run: npm ci
+      - run: npm test
+"#,
+            ),
+            workflow(
+                "release",
+                r#"
+jobs:
+  publish:
+    steps:
+      - uses: actions/checkout@v5
+      - uses: actions/setup-node@v4
+        with:
+          node-version: "22"
+          cache: npm
+      - run: npm ci
+      - run: npm publish
+"#,
+            ),
+        ];
+
+        let shared = SharedSteps::find(&workflows);
+        assert_eq!(shared.helpers.len(), 1);
+
+        let helper = &shared.helpers[0];
+        assert_eq!(helper.name, "checkoutSetupNodeSteps");
+        assert_eq!(helper.steps.len(), 3);
+        assert_eq!(helper.params.len(), 1);
+        assert_eq!(helper.params[0].name, "nodeVersion");
+        assert_eq!(helper.params[0].input, "node-version");
+
+        let (_, occurrence) = shared.at("release", "publish", 0).unwrap();
+        assert_eq!(occurrence.args, vec![Value::String("22".to_string())]);
+        assert!(shared.at("release", "publish", 1).is_none());
+        assert_eq!(shared.used_by("ci"), vec!["checkoutSetupNodeSteps"]);
+        assert_eq!(
+            shared.actions(),
+            vec!["actions/checkout@v5", "actions/setup-node@v4"]
+        );
+    }
+
+    #[test]
+    fn test_find_shared_steps_skips_steps_with_id() {
+        let yaml = r#"
+jobs:
+  a:
+    steps:
+      - uses: actions/checkout@v5
+        id: co
+      - run: echo ${{ steps.co.outputs.ref }}
+      - run: make
+  b:
+    steps:
+      - uses: actions/checkout@v5
+        id: co
+      - run: echo ${{ steps.co.outputs.ref }}
+      - run: make
+"#;
+        let shared = SharedSteps::find(&[workflow("ci", yaml)]);
+        assert!(shared.is_empty());
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Input};
+use serde::Deserialize;
+
+use super::templates::{self, WorkflowTemplate};
+use crate::cache::Cache;
+use crate::config::{Config, TS_CONFIG_FILE};
+use crate::fetcher::{ActionRef, GitHubFetcher};
+
+/// Manifest at the root of a template directory.
+pub const MANIFEST_FILE: &str = "gaji-template.toml";
+
+/// Directory inside a template whose contents are copied into the project's
+/// workflows directory.
+const WORKFLOWS_DIR: &str = "workflows";
+
+/// Where a `--template` comes from.
+#[derive(Debug)]
+pub enum TemplateSource {
+    /// One of the starter workflows shipped with gaji.
+    Builtin(&'static WorkflowTemplate),
+    /// A template directory on disk.
+    Local(PathBuf),
+    /// A template directory in a GitHub repository (`gh:owner/repo/path@ref`).
+    GitHub(ActionRef),
+}
+
+impl TemplateSource {
+    /// Parse a `--template` value.
+    ///
+    /// `gh:` prefixes a GitHub reference, paths (`./`, `../`, `/` or an existing
+    /// directory) are local templates, and anything else names a builtin.
+    pub fn parse(spec: &str) -> Result<Self> {
+        if let Some(reference) = spec.strip_prefix("gh:") {
+            if !reference.contains('@') {
+                bail!(
+                    "Template '{}' is missing a ref. Expected gh:owner/repo/path@ref",
+                    spec
+                );
+            }
+            return Ok(Self::GitHub(ActionRef::parse(reference)?));
+        }
+
+        let is_path = spec.starts_with("./")
+            || spec.starts_with("../")
+            || Path::new(spec).is_absolute()
+            || Path::new(spec).is_dir();
+        if is_path {
+            return Ok(Self::Local(PathBuf::from(spec)));
+        }
+
+        match templates::find_template(spec) {
+            Some(template) => Ok(Self::Builtin(template)),
+            None => {
+                let names: Vec<&str> = templates::WORKFLOW_TEMPLATES
+                    .iter()
+                    .map(|t| t.name)
+                    .collect();
+                bail!(
+                    "Unknown template '{}'. Available templates: {} (or a ./path or gh:owner/repo/path@ref)",
+                    spec,
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Contents of `gaji-template.toml`.
+#[derive(Debug, Default, Deserialize)]
+pub struct TemplateManifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// A `{{ name }}` placeholder the user fills in during init.
+#[derive(Debug, Deserialize)]
+pub struct TemplateVariable {
+    pub name: String,
+    pub prompt: Option<String>,
+    pub default: Option<String>,
+}
+
+/// A template directory loaded into memory.
+#[derive(Debug)]
+pub struct TemplateFiles {
+    pub manifest: TemplateManifest,
+    /// Files to copy, keyed by their path relative to the template root.
+    pub files: Vec<(PathBuf, String)>,
+}
+
+impl TemplateFiles {
+    /// Load a local or GitHub template. Builtin templates are single files and
+    /// are written directly by `init`.
+    pub async fn load(source: &TemplateSource) -> Result<Self> {
+        match source {
+            TemplateSource::Builtin(template) => {
+                bail!("'{}' is a builtin template", template.name)
+            }
+            TemplateSource::Local(dir) => Self::from_dir(dir),
+            TemplateSource::GitHub(repo) => Self::from_github(repo).await,
+        }
+    }
+
+    /// Read a template from a directory on disk.
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            bail!("Template directory not found: {}", dir.display());
+        }
+
+        let manifest = match std::fs::read_to_string(dir.join(MANIFEST_FILE)) {
+            Ok(content) => parse_manifest(&content)?,
+            Err(_) => TemplateManifest::default(),
+        };
+
+        let mut files = Vec::new();
+        let config_path = dir.join(TS_CONFIG_FILE);
+        if config_path.is_file() {
+            files.push((
+                PathBuf::from(TS_CONFIG_FILE),
+                std::fs::read_to_string(&config_path)?,
+            ));
+        }
+        let workflows = dir.join(WORKFLOWS_DIR);
+        if workflows.is_dir() {
+            collect_dir(dir, &workflows, &mut files)?;
+        }
+
+        Self::new(manifest, files)
+    }
+
+    /// Fetch a template directory through the GitHub contents API, using the
+    /// token and API URL from the gaji config.
+    async fn from_github(repo: &ActionRef) -> Result<Self> {
+        let config = Config::load()?;
+        let fetcher = GitHubFetcher::new(
+            Cache::load_or_create()?,
+            config.resolve_token(),
+            config.resolve_api_url(),
+            config.build.cache_ttl_days,
+        )?
+        .with_network(&config.network)?;
+
+        let root = repo.path.clone().unwrap_or_default();
+        let prefix = if root.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", root.trim_matches('/'))
+        };
+
+        let listing = fetcher
+            .list_repo_files(repo, &root)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to list template {}/{}/{}@{}",
+                    repo.owner, repo.repo, root, repo.ref_
+                )
+            })?;
+
+        let mut manifest = TemplateManifest::default();
+        let mut files = Vec::new();
+        for path in listing {
+            let Some(relative) = path.strip_prefix(&prefix) else {
+                continue;
+            };
+            let wanted = relative == MANIFEST_FILE
+                || relative == TS_CONFIG_FILE
+                || relative.starts_with(&format!("{}/", WORKFLOWS_DIR));
+            if !wanted {
+                continue;
+            }
+
+            let content = fetcher.fetch_repo_file(repo, &path).await?;
+            if relative == MANIFEST_FILE {
+                manifest = parse_manifest(&content)?;
+            } else {
+                files.push((PathBuf::from(relative), content));
+            }
+        }
+
+        Self::new(manifest, files)
+    }
+
+    fn new(manifest: TemplateManifest, mut files: Vec<(PathBuf, String)>) -> Result<Self> {
+        if files.is_empty() {
+            bail!(
+                "Template has no {} or {}/ directory to copy",
+                TS_CONFIG_FILE,
+                WORKFLOWS_DIR
+            );
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Self { manifest, files })
+    }
+
+    /// Ask for each manifest variable. Without a terminal, defaults are used and
+    /// a variable without one is an error.
+    pub fn prompt_values(&self) -> Result<HashMap<String, String>> {
+        let interactive = std::io::stdin().is_terminal();
+        let theme = ColorfulTheme::default();
+        let mut values = HashMap::new();
+
+        for variable in &self.manifest.variables {
+            let value = if interactive {
+                let mut input = Input::<String>::with_theme(&theme)
+                    .with_prompt(variable.prompt.as_deref().unwrap_or(&variable.name));
+                if let Some(default) = &variable.default {
+                    input = input.default(default.clone());
+                }
+                input.interact_text()?
+            } else {
+                match &variable.default {
+                    Some(default) => default.clone(),
+                    None => bail!(
+                        "Template variable '{}' has no default and stdin is not a terminal",
+                        variable.name
+                    ),
+                }
+            };
+            values.insert(variable.name.clone(), value);
+        }
+
+        Ok(values)
+    }
+
+    /// Fill in the template variables and write the files into the project.
+    /// Workflows go to `workflows_dir`, `gaji.config.ts` to `root` unless
+    /// `include_config` is false. Existing files are kept unless `force`.
+    pub fn write(
+        &self,
+        values: &HashMap<String, String>,
+        root: &Path,
+        workflows_dir: &Path,
+        include_config: bool,
+        force: bool,
+    ) -> Result<()> {
+        for (relative, content) in &self.files {
+            let target = match relative.strip_prefix(WORKFLOWS_DIR) {
+                Ok(rest) => workflows_dir.join(rest),
+                Err(_) if include_config => root.join(relative),
+                Err(_) => continue,
+            };
+            let display = target.strip_prefix(root).unwrap_or(&target).display();
+
+            if target.exists() && !force {
+                println!(
+                    "{} {} already exists (use --force to overwrite)",
+                    "⏭️ ".dimmed(),
+                    display
+                );
+                continue;
+            }
+
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, render(content, values))?;
+            println!("{} Created {}", "✓".green(), display);
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_manifest(content: &str) -> Result<TemplateManifest> {
+    toml::from_str(content).with_context(|| format!("Failed to parse {}", MANIFEST_FILE))
+}
+
+fn collect_dir(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, String)>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_dir(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root)?.to_path_buf();
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            files.push((relative, content));
+        }
+    }
+    Ok(())
+}
+
+/// Replace `{{ name }}` placeholders whose name is in `values`. GitHub
+/// expressions (`${{ ... }}`) and unknown names are left untouched.
+pub fn render(content: &str, values: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("{{") {
+        let is_expression = rest[..start].ends_with('$');
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let end = start + len + 2;
+        let name = rest[start + 2..end - 2].trim();
+
+        output.push_str(&rest[..start]);
+        match values.get(name) {
+            Some(value) if !is_expression => output.push_str(value),
+            _ => output.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template_source() {
+        assert!(matches!(
+            TemplateSource::parse("rust").unwrap(),
+            TemplateSource::Builtin(t) if t.name == "rust"
+        ));
+        assert!(matches!(
+            TemplateSource::parse("./scaffolds/ci").unwrap(),
+            TemplateSource::Local(p) if p == Path::new("./scaffolds/ci")
+        ));
+
+        match TemplateSource::parse("gh:org/templates/node@v1").unwrap() {
+            TemplateSource::GitHub(repo) => {
+                assert_eq!(repo.owner, "org");
+                assert_eq!(repo.repo, "templates");
+                assert_eq!(repo.path.as_deref(), Some("node"));
+                assert_eq!(repo.ref_, "v1");
+            }
+            other => panic!("expected GitHub source, got {:?}", other),
+        }
+
+        let err = TemplateSource::parse("gh:org/templates").unwrap_err();
+        assert!(err.to_string().contains("missing a ref"));
+        let err = TemplateSource::parse("fortran").unwrap_err();
+        assert!(err.to_string().contains("Unknown template 'fortran'"));
+    }
+
+    #[test]
+    fn test_render_skips_expressions() {
+        let values = HashMap::from([("node_version".to_string(), "22".to_string())]);
+        let content =
+            r#"node-version: "{{ node_version }}", ref: "${{ github.ref }}", x: "{{other}}""#;
+        assert_eq!(
+            render(content, &values),
+            r#"node-version: "22", ref: "${{ github.ref }}", x: "{{other}}""#
+        );
+    }
+
+    #[test]
+    fn test_local_template_write() {
+        let template = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            template.path().join(MANIFEST_FILE),
+            r#"
+name = "org-ci"
+
+[[variables]]
+name = "branch"
+prompt = "Default branch"
+default = "main"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            template.path().join(TS_CONFIG_FILE),
+            "export default { workflows: \"workflows\" };\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(template.path().join("workflows/shared")).unwrap();
+        std::fs::write(
+            template.path().join("workflows/ci.ts"),
+            "on: { push: { branches: [\"{{ branch }}\"] } }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            template.path().join("workflows/shared/steps.ts"),
+            "export {};\n",
+        )
+        .unwrap();
+        std::fs::write(template.path().join("README.md"), "docs\n").unwrap();
+
+        let files = TemplateFiles::from_dir(template.path()).unwrap();
+        assert_eq!(files.manifest.name.as_deref(), Some("org-ci"));
+        assert_eq!(files.files.len(), 3);
+
+        let project = tempfile::TempDir::new().unwrap();
+        let values = HashMap::from([("branch".to_string(), "trunk".to_string())]);
+        let workflows = project.path().join("workflows");
+        files
+            .write(&values, project.path(), &workflows, true, false)
+            .unwrap();
+
+        let ci = std::fs::read_to_string(workflows.join("ci.ts")).unwrap();
+        assert!(ci.contains("[\"trunk\"]"));
+        assert!(workflows.join("shared/steps.ts").exists());
+        assert!(project.path().join(TS_CONFIG_FILE).exists());
+        assert!(!project.path().join("README.md").exists());
+
+        // Existing files are kept without force
+        std::fs::write(workflows.join("ci.ts"), "// mine\n").unwrap();
+        files
+            .write(&values, project.path(), &workflows, true, false)
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(workflows.join("ci.ts")).unwrap(),
+            "// mine\n"
+        );
+    }
+}
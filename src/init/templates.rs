@@ -19,7 +19,7 @@ pub const TSCONFIG_TEMPLATE: &str = r#"{
     "skipLibCheck": true,
     "forceConsistentCasingInFileNames": true
   },
-  "include": ["workflows/**/*"],
+  "include": ["workflows/**/*", "generated/**/*"],
   "exclude": ["node_modules", "dist"]
 }
 "#;
@@ -56,6 +56,322 @@ new Workflow({
 ).build("ci");
 "#;
 
+pub const PNPM_WORKFLOW_TEMPLATE: &str = r#"// Node.js CI with pnpm - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+const setupPnpm = getAction("pnpm/action-setup@v4");
+const setupNode = getAction("actions/setup-node@v4");
+
+new Workflow({
+    name: "CI",
+    on: {
+        push: { branches: ["main"] },
+        pull_request: { branches: ["main"] },
+    },
+}).jobs(j => j
+    .add("build",
+        new Job("ubuntu-latest")
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(setupPnpm({ name: "Setup pnpm" }))
+                .add(setupNode({
+                    name: "Setup Node.js",
+                    with: { "node-version": "20", cache: "pnpm" },
+                }))
+                .add({ name: "Install dependencies", run: "pnpm install --frozen-lockfile" })
+                .add({ name: "Lint", run: "pnpm run --if-present lint" })
+                .add({ name: "Run tests", run: "pnpm test" })
+            )
+    )
+).build("ci");
+"#;
+
+pub const RUST_WORKFLOW_TEMPLATE: &str = r#"// Rust CI - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+const rustToolchain = getAction("dtolnay/rust-toolchain@stable");
+const rustCache = getAction("Swatinem/rust-cache@v2");
+
+new Workflow({
+    name: "CI",
+    on: {
+        push: { branches: ["main"] },
+        pull_request: { branches: ["main"] },
+    },
+    env: { CARGO_TERM_COLOR: "always" },
+}).jobs(j => j
+    .add("lint",
+        new Job("ubuntu-latest")
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(rustToolchain({ with: { components: "rustfmt, clippy" } }))
+                .add(rustCache({}))
+                .add({ name: "Check formatting", run: "cargo fmt --all -- --check" })
+                .add({ name: "Clippy", run: "cargo clippy --workspace --all-targets -- -D warnings" })
+            )
+    )
+    .add("test",
+        new Job("${{ matrix.os }}", {
+            strategy: {
+                matrix: { os: ["ubuntu-latest", "macos-latest", "windows-latest"] },
+            },
+        })
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(rustToolchain({}))
+                .add(rustCache({}))
+                .add({ name: "Run tests", run: "cargo test --workspace" })
+            )
+    )
+).build("ci");
+"#;
+
+pub const PYTHON_WORKFLOW_TEMPLATE: &str = r#"// Python CI with uv - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+const setupUv = getAction("astral-sh/setup-uv@v6");
+
+new Workflow({
+    name: "CI",
+    on: {
+        push: { branches: ["main"] },
+        pull_request: { branches: ["main"] },
+    },
+}).jobs(j => j
+    .add("test",
+        new Job("ubuntu-latest", {
+            strategy: {
+                matrix: { "python-version": ["3.11", "3.12", "3.13"] },
+            },
+        })
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(setupUv({
+                    name: "Setup uv",
+                    with: { "python-version": "${{ matrix.python-version }}", "enable-cache": "true" },
+                }))
+                .add({ name: "Install dependencies", run: "uv sync --all-extras --dev" })
+                .add({ name: "Run tests", run: "uv run pytest" })
+            )
+    )
+).build("ci");
+"#;
+
+pub const GO_WORKFLOW_TEMPLATE: &str = r#"// Go CI - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+const setupGo = getAction("actions/setup-go@v5");
+
+new Workflow({
+    name: "CI",
+    on: {
+        push: { branches: ["main"] },
+        pull_request: { branches: ["main"] },
+    },
+}).jobs(j => j
+    .add("test",
+        new Job("ubuntu-latest")
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(setupGo({
+                    name: "Setup Go",
+                    with: { "go-version-file": "go.mod" },
+                }))
+                .add({ name: "Vet", run: "go vet ./..." })
+                .add({ name: "Run tests", run: "go test -race ./..." })
+            )
+    )
+).build("ci");
+"#;
+
+pub const DOCKER_WORKFLOW_TEMPLATE: &str = r#"// Docker build and push to GHCR - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+const setupBuildx = getAction("docker/setup-buildx-action@v3");
+const login = getAction("docker/login-action@v3");
+const metadata = getAction("docker/metadata-action@v5");
+const buildPush = getAction("docker/build-push-action@v6");
+
+new Workflow({
+    name: "Docker",
+    on: {
+        push: { branches: ["main"], tags: ["v*"] },
+        pull_request: { branches: ["main"] },
+    },
+}).jobs(j => j
+    .add("image",
+        new Job("ubuntu-latest", {
+            permissions: { contents: "read", packages: "write" },
+        })
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(setupBuildx({ name: "Setup Docker Buildx" }))
+                .add(login({
+                    name: "Log in to GHCR",
+                    if: "github.event_name != 'pull_request'",
+                    with: {
+                        registry: "ghcr.io",
+                        username: "${{ github.actor }}",
+                        password: "${{ secrets.GITHUB_TOKEN }}",
+                    },
+                }))
+                .add(metadata({
+                    id: "meta",
+                    name: "Image metadata",
+                    with: { images: "ghcr.io/${{ github.repository }}" },
+                }))
+                .add(output => buildPush({
+                    name: "Build and push",
+                    with: {
+                        push: "${{ github.event_name != 'pull_request' }}",
+                        tags: output.meta.tags,
+                        labels: output.meta.labels,
+                    },
+                }))
+            )
+    )
+).build("docker");
+"#;
+
+pub const RELEASE_PLEASE_WORKFLOW_TEMPLATE: &str = r#"// release-please - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const releasePlease = getAction("googleapis/release-please-action@v4");
+
+new Workflow({
+    name: "Release",
+    on: {
+        push: { branches: ["main"] },
+    },
+    permissions: { contents: "write", "pull-requests": "write" },
+}).jobs(j => j
+    .add("release-please",
+        new Job("ubuntu-latest")
+            .steps(s => s
+                .add(releasePlease({
+                    name: "Create release PR",
+                    with: { "release-type": "simple" },
+                }))
+            )
+    )
+).build("release-please");
+"#;
+
+pub const PAGES_WORKFLOW_TEMPLATE: &str = r#"// GitHub Pages deploy - Generated by gaji init
+import { getAction, Job, Workflow } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+const configurePages = getAction("actions/configure-pages@v5");
+const uploadPagesArtifact = getAction("actions/upload-pages-artifact@v3");
+const deployPages = getAction("actions/deploy-pages@v4");
+
+new Workflow({
+    name: "Pages",
+    on: {
+        push: { branches: ["main"] },
+        workflow_dispatch: {},
+    },
+    permissions: { contents: "read", pages: "write", "id-token": "write" },
+    concurrency: { group: "pages", "cancel-in-progress": false },
+}).jobs(j => j
+    .add("build",
+        new Job("ubuntu-latest")
+            .steps(s => s
+                .add(checkout({ name: "Checkout code" }))
+                .add(configurePages({ name: "Setup Pages" }))
+                .add({ name: "Build site", run: "mkdir -p _site && cp -r docs/. _site/" })
+                .add(uploadPagesArtifact({ with: { path: "_site" } }))
+            )
+    )
+    .add("deploy",
+        new Job("ubuntu-latest", {
+            needs: "build",
+            environment: {
+                name: "github-pages",
+                url: "${{ steps.deployment.outputs.page_url }}",
+            },
+        })
+            .steps(s => s
+                .add(deployPages({ id: "deployment", name: "Deploy to GitHub Pages" }))
+            )
+    )
+).build("pages");
+"#;
+
+/// A starter workflow offered by `gaji init --template`.
+#[derive(Debug)]
+pub struct WorkflowTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// File name written to the workflows directory.
+    pub file: &'static str,
+    pub content: &'static str,
+}
+
+/// Built-in templates, in the order the interactive picker lists them.
+pub const WORKFLOW_TEMPLATES: &[WorkflowTemplate] = &[
+    WorkflowTemplate {
+        name: "node",
+        description: "Node.js CI with npm",
+        file: "ci.ts",
+        content: EXAMPLE_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "pnpm",
+        description: "Node.js CI with pnpm",
+        file: "ci.ts",
+        content: PNPM_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "rust",
+        description: "Rust CI: cargo fmt, clippy and a test matrix",
+        file: "ci.ts",
+        content: RUST_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "python",
+        description: "Python CI with uv",
+        file: "ci.ts",
+        content: PYTHON_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "go",
+        description: "Go CI: vet and test",
+        file: "ci.ts",
+        content: GO_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "docker",
+        description: "Docker build and push to GHCR",
+        file: "docker.ts",
+        content: DOCKER_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "release-please",
+        description: "Release PRs and tags with release-please",
+        file: "release-please.ts",
+        content: RELEASE_PLEASE_WORKFLOW_TEMPLATE,
+    },
+    WorkflowTemplate {
+        name: "pages",
+        description: "GitHub Pages deploy",
+        file: "pages.ts",
+        content: PAGES_WORKFLOW_TEMPLATE,
+    },
+];
+
+/// Template used when nothing about the project suggests another one.
+pub const DEFAULT_TEMPLATE: &str = "node";
+
+pub fn find_template(name: &str) -> Option<&'static WorkflowTemplate> {
+    WORKFLOW_TEMPLATES.iter().find(|t| t.name == name)
+}
+
 pub const GAJI_CONFIG_TEMPLATE: &str = r#"import { defineConfig } from "./generated/index.js";
 
 export default defineConfig({
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::executor::{bundle_local_modules, execute_js, remove_imports, strip_typescript};
+use crate::generator::templates::{
+    GET_ACTION_RUNTIME_TEMPLATE, GET_WORKFLOW_RUNTIME_TEMPLATE, JOB_WORKFLOW_RUNTIME_TEMPLATE,
+};
+
+use super::migration::{normalize_triggers, workflow_triggers};
+use super::output_refs::referenced_action_outputs;
+
+/// Build migrated TypeScript in memory and compare the result with the
+/// original YAML workflow.
+///
+/// Local imports are resolved against `modules`: TypeScript sources keyed by
+/// their path relative to the migrated file (such as `shared.ts`).
+///
+/// Returns one human-readable line per difference; an empty list means the
+/// migrated workflow produces the same document as the original.
+pub fn verify_migrated_workflow(
+    ts_source: &str,
+    yaml_content: &str,
+    modules: &HashMap<PathBuf, String>,
+) -> Result<Vec<String>> {
+    let workflow_js = strip_typescript(ts_source, "migrated.ts")?;
+    let original: serde_yaml::Value = serde_yaml::from_str(yaml_content)?;
+
+    // Action metadata is not fetched yet, so assume every action declares the
+    // outputs the original workflow reads from it.
+    let action_outputs = serde_json::to_string(&referenced_action_outputs(&original))?;
+    let runtime_js = format!(
+        "var __action_outputs = {};\nvar __workflow_outputs = {{}};\n{}\n{}\n{}",
+        action_outputs,
+        GET_ACTION_RUNTIME_TEMPLATE,
+        GET_WORKFLOW_RUNTIME_TEMPLATE,
+        JOB_WORKFLOW_RUNTIME_TEMPLATE
+    );
+    let workflow_js = bundle_local_modules(Path::new("migrated.ts"), &workflow_js, &|path| {
+        modules.get(path).cloned()
+    })?;
+    let bundled = format!("{}\n\n{}", remove_imports(&runtime_js), workflow_js);
+
+    let outputs = execute_js(&bundled).context("Failed to build migrated workflow")?;
+    let output = outputs
+        .iter()
+        .find(|o| o.output_type == "workflow")
+        .ok_or_else(|| anyhow!("Migrated workflow did not call build()"))?;
+    let migrated: Value = serde_json::from_str(&output.json)?;
+
+    let original = normalize_original(&original);
+
+    let mut diffs = Vec::new();
+    diff_values(
+        "",
+        &normalize_json(&original),
+        &normalize_json(&migrated),
+        &mut diffs,
+    );
+    Ok(diffs)
+}
+
+/// Convert the original YAML to JSON, resolving the YAML 1.1 `on` key and the
+/// shorthand trigger forms the same way migration does.
+fn normalize_original(workflow: &serde_yaml::Value) -> Value {
+    let mut json = yaml_to_json(workflow);
+    if let (Some(on), Some(map)) = (workflow_triggers(workflow), json.as_object_mut()) {
+        map.remove("true");
+        map.insert("on".to_string(), yaml_to_json(&normalize_triggers(on)));
+    }
+    json
+}
+
+fn yaml_to_json(value: &serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(*b),
+        serde_yaml::Value::Number(n) => serde_json::to_value(n).unwrap_or(Value::Null),
+        serde_yaml::Value::String(s) => Value::String(s.clone()),
+        serde_yaml::Value::Sequence(seq) => Value::Array(seq.iter().map(yaml_to_json).collect()),
+        serde_yaml::Value::Mapping(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        serde_yaml::Value::String(s) => s.clone(),
+                        serde_yaml::Value::Bool(b) => b.to_string(),
+                        serde_yaml::Value::Number(n) => n.to_string(),
+                        other => format!("{:?}", other),
+                    };
+                    (key, yaml_to_json(v))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(&tagged.value),
+    }
+}
+
+/// Drop null-valued keys: `key:` in YAML and an omitted key are equivalent
+/// to GitHub Actions, and the migrated code never emits explicit nulls.
+fn normalize_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), normalize_json(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(normalize_json).collect()),
+        other => other.clone(),
+    }
+}
+
+fn diff_values(path: &str, expected: &Value, actual: &Value, diffs: &mut Vec<String>) {
+    let at = if path.is_empty() { "<root>" } else { path };
+
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            for (key, e_val) in e {
+                let child = join_path(path, key);
+                match a.get(key) {
+                    Some(a_val) => diff_values(&child, e_val, a_val, diffs),
+                    None => diffs.push(format!("{}: missing in migrated workflow", child)),
+                }
+            }
+            for key in a.keys().filter(|k| !e.contains_key(*k)) {
+                diffs.push(format!(
+                    "{}: not present in original workflow",
+                    join_path(path, key)
+                ));
+            }
+        }
+        (Value::Array(e), Value::Array(a)) => {
+            if e.len() != a.len() {
+                diffs.push(format!(
+                    "{}: expected {} items, got {}",
+                    at,
+                    e.len(),
+                    a.len()
+                ));
+                return;
+            }
+            for (i, (e_item, a_item)) in e.iter().zip(a).enumerate() {
+                diff_values(&format!("{}[{}]", path, i), e_item, a_item, diffs);
+            }
+        }
+        (Value::Number(e), Value::Number(a)) if e.as_f64() == a.as_f64() => {}
+        (e, a) if e == a => {}
+        (e, a) => diffs.push(format!("{}: expected {}, got {}", at, e, a)),
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_values_reports_paths() {
+        let expected = json!({ "jobs": { "a": { "needs": ["b"], "timeout-minutes": 10 } } });
+        let actual = json!({ "jobs": { "a": { "timeout-minutes": 10.0, "if": "x" } } });
+
+        let mut diffs = Vec::new();
+        diff_values("", &expected, &actual, &mut diffs);
+        assert_eq!(
+            diffs,
+            vec![
+                "jobs.a.needs: missing in migrated workflow".to_string(),
+                "jobs.a.if: not present in original workflow".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_mismatch() {
+        let yaml = r#"
+name: CI
+on: push
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - run: echo hi
+"#;
+        let ts = r#"
+const build = new Job("ubuntu-latest").steps(s => s.add({ run: "echo bye" }));
+new Workflow({ name: "CI", on: { push: {} } }).jobs(j => j.add("build", build)).build("ci");
+"#;
+        let diffs = verify_migrated_workflow(ts, yaml, &HashMap::new()).unwrap();
+        assert_eq!(
+            diffs,
+            vec![r#"jobs.build.steps[0].run: expected "echo hi", got "echo bye""#.to_string()]
+        );
+    }
+}
@@ -0,0 +1,412 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Comments and anchors recovered from the YAML source text.
+///
+/// `serde_yaml` drops comments and expands aliases, so the migration scans
+/// the source line by line to find out where they were. Only block-style YAML
+/// is tracked, which is what workflow files use in practice; anything the
+/// scanner does not understand is simply not carried over.
+#[derive(Debug, Default)]
+pub struct YamlSource {
+    header: Vec<String>,
+    workflow: Vec<String>,
+    jobs: HashMap<String, Vec<String>>,
+    steps: HashMap<(String, usize), Vec<String>>,
+    anchors: Vec<Anchor>,
+    used: RefCell<BTreeSet<usize>>,
+}
+
+#[derive(Debug)]
+struct Anchor {
+    name: String,
+    definition: Vec<Segment>,
+    /// Paths of the anchored node and every alias to it.
+    paths: Vec<String>,
+    var: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Item(usize),
+}
+
+impl YamlSource {
+    pub fn parse(source: &str) -> Self {
+        let mut result = Self::default();
+        let mut stack: Vec<(usize, Segment)> = Vec::new();
+        let mut pending: Vec<String> = Vec::new();
+        let mut block_scalar: Option<usize> = None;
+        let mut aliases: Vec<(String, String)> = Vec::new();
+        let mut seen_content = false;
+
+        for line in source.lines() {
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            let trimmed = line.trim();
+
+            // Block scalar (`run: |`) content is text, not structure
+            if let Some(col) = block_scalar {
+                if trimmed.is_empty() || indent > col {
+                    continue;
+                }
+                block_scalar = None;
+            }
+            if trimmed.is_empty() || trimmed == "---" || trimmed.starts_with('%') {
+                continue;
+            }
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                pending.push(comment.strip_prefix(' ').unwrap_or(comment).to_string());
+                continue;
+            }
+
+            // Sequence items: `- ` (possibly nested)
+            let mut col = indent;
+            let mut rest = trimmed;
+            let mut node_col = None;
+            while rest == "-" || rest.starts_with("- ") {
+                while stack.last().is_some_and(|(c, _)| *c > col) {
+                    stack.pop();
+                }
+                match stack.last_mut() {
+                    Some((c, Segment::Item(index))) if *c == col => *index += 1,
+                    _ => stack.push((col, Segment::Item(0))),
+                }
+                node_col = Some(col);
+                let after = &rest[1..];
+                col += 1 + (after.len() - after.trim_start().len());
+                rest = after.trim_start();
+            }
+
+            let (content, trailing) = split_comment(rest);
+            let value = match split_key(content) {
+                Some((key, value)) => {
+                    while stack.last().is_some_and(|(c, _)| *c >= col) {
+                        stack.pop();
+                    }
+                    stack.push((col, Segment::Key(key)));
+                    node_col = Some(col);
+                    value
+                }
+                None => content,
+            };
+
+            let path = render_path(stack.iter().map(|(_, s)| s));
+            if !seen_content {
+                drop_generated_header(&mut pending);
+                result.header.append(&mut pending);
+                seen_content = true;
+            }
+            let comments = pending.drain(..).chain(trailing.map(String::from));
+            result.attach(&stack, comments);
+
+            let Some(node_col) = node_col else {
+                continue;
+            };
+
+            let mut value = value.trim();
+            if let Some(anchored) = value.strip_prefix('&') {
+                let (name, after) = split_token(anchored);
+                result.anchors.push(Anchor {
+                    name: name.to_string(),
+                    definition: stack.iter().map(|(_, s)| s.clone()).collect(),
+                    paths: vec![path.clone()],
+                    var: None,
+                });
+                value = after;
+            } else if let Some(alias) = value.strip_prefix('*') {
+                aliases.push((split_token(alias).0.to_string(), path.clone()));
+            }
+
+            if value.starts_with(['|', '>'])
+                && value[1..]
+                    .chars()
+                    .all(|c| c == '-' || c == '+' || c.is_ascii_digit())
+            {
+                block_scalar = Some(node_col);
+            }
+        }
+
+        // Comments after the last node
+        result.workflow.extend(pending);
+
+        for (name, path) in aliases {
+            // An alias refers to the closest preceding anchor with that name
+            if let Some(anchor) = result.anchors.iter_mut().rev().find(|a| a.name == name) {
+                anchor.paths.push(path);
+            }
+        }
+
+        result
+    }
+
+    fn attach(&mut self, stack: &[(usize, Segment)], comments: impl Iterator<Item = String>) {
+        let segments: Vec<&Segment> = stack.iter().map(|(_, s)| s).collect();
+        let target = match segments.as_slice() {
+            [Segment::Key(jobs), Segment::Key(job), Segment::Key(steps), Segment::Item(i), ..]
+                if jobs == "jobs" && steps == "steps" =>
+            {
+                self.steps.entry((job.clone(), *i)).or_default()
+            }
+            [Segment::Key(jobs), Segment::Key(job), ..] if jobs == "jobs" => {
+                self.jobs.entry(job.clone()).or_default()
+            }
+            _ => &mut self.workflow,
+        };
+        target.extend(comments);
+    }
+
+    /// Comments before the first key of the file.
+    pub fn header_comments(&self) -> &[String] {
+        &self.header
+    }
+
+    /// Comments on workflow-level keys.
+    pub fn workflow_comments(&self) -> &[String] {
+        &self.workflow
+    }
+
+    pub fn job_comments(&self, job_id: &str) -> &[String] {
+        self.jobs.get(job_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn step_comments(&self, job_id: &str, index: usize) -> &[String] {
+        self.steps
+            .get(&(job_id.to_string(), index))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Give every anchor that is aliased at least once a variable name that
+    /// does not clash with `taken`.
+    pub fn name_anchors(&mut self, taken: &HashSet<String>) {
+        let mut taken = taken.clone();
+        for anchor in self.anchors.iter_mut().filter(|a| a.paths.len() > 1) {
+            let base = anchor_var_name(&anchor.name);
+            let mut var = base.clone();
+            let mut n = 2;
+            while taken.contains(&var) {
+                var = format!("{}{}", base, n);
+                n += 1;
+            }
+            taken.insert(var.clone());
+            anchor.var = Some(var);
+        }
+    }
+
+    /// Variable holding the anchored value at `path` (e.g. `jobs.build.env`),
+    /// if the node there is an anchor or an alias.
+    pub fn anchor_at(&self, path: &str) -> Option<&str> {
+        let (index, anchor) = self
+            .anchors
+            .iter()
+            .enumerate()
+            .find(|(_, a)| a.var.is_some() && a.paths.iter().any(|p| p == path))?;
+        self.used.borrow_mut().insert(index);
+        anchor.var.as_deref()
+    }
+
+    /// `(variable, value)` for every anchor referenced through [`anchor_at`],
+    /// in source order.
+    ///
+    /// [`anchor_at`]: Self::anchor_at
+    pub fn used_anchors<'v>(
+        &self,
+        workflow: &'v serde_yaml::Value,
+    ) -> Vec<(&str, &'v serde_yaml::Value)> {
+        self.used
+            .borrow()
+            .iter()
+            .filter_map(|&i| {
+                let anchor = &self.anchors[i];
+                let value = value_at(workflow, &anchor.definition)?;
+                Some((anchor.var.as_deref()?, value))
+            })
+            .collect()
+    }
+}
+
+/// Path of a node in the format used by [`YamlSource::anchor_at`].
+pub fn step_path(job_id: &str, index: usize) -> String {
+    format!("jobs.{}.steps[{}]", job_id, index)
+}
+
+fn render_path<'a>(segments: impl Iterator<Item = &'a Segment>) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Segment::Item(i) => path.push_str(&format!("[{}]", i)),
+        }
+    }
+    path
+}
+
+fn value_at<'v>(value: &'v serde_yaml::Value, path: &[Segment]) -> Option<&'v serde_yaml::Value> {
+    path.iter().try_fold(value, |node, segment| match segment {
+        Segment::Key(key) => match node.get(key.as_str()) {
+            Some(v) => Some(v),
+            // `on:` is read as boolean true by YAML 1.1 parsers
+            None if key == "on" => node.get(serde_yaml::Value::Bool(true)),
+            None => None,
+        },
+        Segment::Item(i) => node.get(*i),
+    })
+}
+
+/// Split `content # comment` into content and comment text.
+/// Drop the header gaji writes at the top of the YAML it builds, which would
+/// be wrong in the migrated TypeScript source.
+fn drop_generated_header(comments: &mut Vec<String>) {
+    if comments.first().map(String::as_str) != Some("Auto-generated by gaji") {
+        return;
+    }
+    let len = 1 + comments[1..]
+        .iter()
+        .take_while(|c| c.starts_with("Do not edit manually") || c.starts_with("Generated at:"))
+        .count();
+    comments.drain(..len);
+}
+
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') if prev == ' ' || prev == ':' => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if prev == ' ' || prev == '\t' => {
+                let comment = line[i + 1..].strip_prefix(' ').unwrap_or(&line[i + 1..]);
+                return (line[..i].trim_end(), Some(comment));
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    (line, None)
+}
+
+/// Split `key: value` / `key:` into the key and the rest of the line.
+fn split_key(content: &str) -> Option<(String, &str)> {
+    if let Some(quote) = content.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = content[1..].find(quote)? + 1;
+        let after = content[end + 1..].strip_prefix(':')?;
+        if !after.is_empty() && !after.starts_with(' ') {
+            return None;
+        }
+        return Some((content[1..end].to_string(), after));
+    }
+    if content.starts_with(['{', '[', '&', '*', '|', '>']) {
+        return None;
+    }
+    if let Some(key) = content.strip_suffix(':') {
+        return Some((key.to_string(), ""));
+    }
+    let (key, value) = content.split_once(": ")?;
+    Some((key.to_string(), value))
+}
+
+fn split_token(s: &str) -> (&str, &str) {
+    match s.find(char::is_whitespace) {
+        Some(i) => (&s[..i], s[i..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+/// `common-env` → `commonEnv`.
+fn anchor_var_name(name: &str) -> String {
+    let mut var = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if upper && !var.is_empty() {
+                var.push(c.to_ascii_uppercase());
+            } else {
+                var.push(c);
+            }
+            upper = false;
+        } else {
+            upper = true;
+        }
+    }
+    if var.is_empty() || var.starts_with(|c: char| c.is_ascii_digit()) {
+        var.insert(0, '_');
+    }
+    var
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r##"# Runs on every push
+name: CI # shown in the UI
+on: push
+
+env: &common-env
+  NODE_ENV: test
+  # not a header
+
+jobs:
+  # Fast checks first
+  lint:
+    runs-on: ubuntu-latest
+    env: *common-env
+    steps:
+      # Fetch sources
+      - uses: actions/checkout@v5
+      - run: |
+          # inside a script
+          npm run lint # also inside
+  test:
+    runs-on: ubuntu-latest
+    env: *common-env
+    steps:
+    - run: npm test
+    # Upload results
+    - run: echo "# not a comment"
+"##;
+
+    #[test]
+    fn test_comments_attach_to_nodes() {
+        let source = YamlSource::parse(SOURCE);
+        assert_eq!(source.header_comments(), ["Runs on every push"]);
+        assert_eq!(
+            source.workflow_comments(),
+            ["shown in the UI", "not a header"]
+        );
+        assert_eq!(source.job_comments("lint"), ["Fast checks first"]);
+        assert_eq!(source.step_comments("lint", 0), ["Fetch sources"]);
+        assert!(source.step_comments("lint", 1).is_empty());
+        assert_eq!(source.step_comments("test", 1), ["Upload results"]);
+        assert!(source.job_comments("test").is_empty());
+    }
+
+    #[test]
+    fn test_anchors_and_aliases() {
+        let mut source = YamlSource::parse(SOURCE);
+        source.name_anchors(&HashSet::from(["other".to_string()]));
+
+        assert_eq!(source.anchor_at("env"), Some("commonEnv"));
+        assert_eq!(source.anchor_at("jobs.test.env"), Some("commonEnv"));
+        assert_eq!(source.anchor_at("jobs.test.runs-on"), None);
+
+        let workflow: serde_yaml::Value = serde_yaml::from_str(SOURCE).unwrap();
+        let used = source.used_anchors(&workflow);
+        assert_eq!(used.len(), 1);
+        assert_eq!(used[0].0, "commonEnv");
+        assert_eq!(used[0].1["NODE_ENV"], "test");
+    }
+
+    #[test]
+    fn test_anchor_var_name() {
+        assert_eq!(anchor_var_name("common-env"), "commonEnv");
+        assert_eq!(anchor_var_name("setup_steps"), "setupSteps");
+        assert_eq!(anchor_var_name("1st"), "_1st");
+    }
+}
@@ -2,11 +2,14 @@ pub mod builder;
 pub mod cache;
 pub mod cli;
 pub mod config;
+pub mod credentials;
+pub mod doctor;
 pub mod executor;
 pub mod fetcher;
 pub mod generator;
 pub mod init;
 pub mod parser;
+pub mod source;
 pub mod watcher;
 
 pub use cli::Cli;
@@ -2,23 +2,38 @@ use std::io;
 use std::path::PathBuf;
 use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
 
 use gaji::builder::WorkflowBuilder;
-use gaji::cache::Cache;
-use gaji::cli::{Cli, Commands};
+use gaji::cache::{Cache, LEGACY_CACHE_FILE};
+use gaji::cli::{CacheCommand, Cli, Commands};
 use gaji::config::Config;
-use gaji::generator::TypeGenerator;
-use gaji::init::{self, InitOptions};
+use gaji::credentials;
+use gaji::doctor;
+use gaji::fetcher::{
+    is_local_ref, is_reusable_workflow_ref, vendored_path, ActionRef, GitHubFetcher, VENDOR_DIR,
+};
+use gaji::generator::{discover_local_workflows, TypeGenerator};
+use gaji::init::{self, migration, InitOptions};
 use gaji::parser;
+use gaji::source::SourceRouter;
 use gaji::watcher;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    if let Err(e) = run().await {
+        // Tokens can end up in error chains, e.g. in a rejected helper's output
+        eprintln!("Error: {}", credentials::redact(&format!("{:?}", e)));
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<()> {
     let cli = Cli::parse();
+    let offline = cli.offline;
 
     match cli.command {
         Commands::Init {
@@ -26,11 +41,22 @@ async fn main() -> Result<()> {
             skip_examples,
             migrate,
             interactive,
+            template,
+            package_manager,
         } => {
-            cmd_init(force, skip_examples, migrate, interactive).await?;
+            cmd_init(
+                force,
+                skip_examples,
+                migrate,
+                interactive,
+                template,
+                package_manager,
+                offline,
+            )
+            .await?;
         }
         Commands::Dev { input, watch } => {
-            cmd_dev(&input, watch).await?;
+            cmd_dev(&input, watch, offline).await?;
         }
         Commands::Build {
             input,
@@ -39,14 +65,41 @@ async fn main() -> Result<()> {
         } => {
             cmd_build(&input, output.as_deref(), dry_run).await?;
         }
+        Commands::Migrate {
+            files,
+            dry_run,
+            out,
+            no_backup,
+            overwrite,
+            dedupe,
+        } => {
+            cmd_migrate(
+                &files,
+                dry_run,
+                out.as_deref(),
+                no_backup,
+                overwrite,
+                dedupe,
+            )
+            .await?;
+        }
         Commands::List { input, json } => {
             cmd_list(&input, json).await?;
         }
         Commands::Add { action } => {
-            cmd_add(&action).await?;
+            cmd_add(&action, offline).await?;
         }
-        Commands::Clean { cache } => {
-            cmd_clean(cache).await?;
+        Commands::Vendor { input } => {
+            cmd_vendor(&input, offline).await?;
+        }
+        Commands::Clean { cache, unused } => {
+            cmd_clean(cache, unused).await?;
+        }
+        Commands::Cache { command } => {
+            cmd_cache(command, offline).await?;
+        }
+        Commands::Doctor { json, no_network } => {
+            cmd_doctor(json, no_network || offline).await?;
         }
         Commands::Completions { shell } => {
             cmd_completions(&shell)?;
@@ -61,6 +114,9 @@ async fn cmd_init(
     skip_examples: bool,
     migrate: bool,
     interactive: bool,
+    template: Option<String>,
+    package_manager: Option<String>,
+    offline: bool,
 ) -> Result<()> {
     let root = std::env::current_dir()?;
     let options = InitOptions {
@@ -68,11 +124,32 @@ async fn cmd_init(
         skip_examples,
         migrate,
         interactive,
+        template,
+        package_manager,
+        offline,
     };
     init::init_project(&root, options).await
 }
 
-async fn cmd_dev(inputs: &[String], watch: bool) -> Result<()> {
+async fn cmd_doctor(json_output: bool, no_network: bool) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let report = doctor::run(&root, !no_network).await;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{} Checking gaji setup...\n", "🩺".cyan());
+        report.print();
+    }
+
+    if report.has_errors() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+async fn cmd_dev(inputs: &[String], watch: bool, offline: bool) -> Result<()> {
     println!("{} Starting development mode...\n", "🚀".green());
 
     let config = Config::load()?;
@@ -80,31 +157,32 @@ async fn cmd_dev(inputs: &[String], watch: bool) -> Result<()> {
     let api_url = config.resolve_api_url();
 
     // Initial scan
-    let mut all_refs = std::collections::HashSet::new();
     let paths: Vec<PathBuf> = if inputs.is_empty() {
         vec![PathBuf::from(&config.project.workflows_dir)]
     } else {
         inputs.iter().map(PathBuf::from).collect()
     };
+    let all_refs = collect_action_refs(&paths).await?;
 
-    for path in &paths {
-        if !path.exists() {
-            continue;
-        }
-        if path.is_dir() {
-            let results = parser::analyze_directory(path).await?;
-            for refs in results.values() {
-                all_refs.extend(refs.clone());
-            }
-        } else if path.is_file() {
-            match parser::analyze_file(path).await {
-                Ok(refs) => all_refs.extend(refs),
-                Err(e) => eprintln!("Warning: Failed to parse {}: {}", path.display(), e),
-            }
-        }
-    }
+    let local_workflows_dir = config.output_path().join("workflows");
+    let has_local_workflows = !discover_local_workflows(&local_workflows_dir)
+        .await?
+        .is_empty();
 
-    if !all_refs.is_empty() {
+    let generator = TypeGenerator::with_cache_ttl(
+        Cache::load_or_create()?,
+        PathBuf::from("generated"),
+        token,
+        api_url,
+        config.build.cache_ttl_days,
+    )?
+    .with_network(&config.network)?
+    .with_local_workflows(local_workflows_dir)
+    .with_type_overrides(config.types.clone())
+    .with_sources(SourceRouter::from_config(&config)?)
+    .with_offline(offline);
+
+    if !all_refs.is_empty() || has_local_workflows {
         println!(
             "{} Found {} action reference(s), generating types...",
             "🔍".cyan(),
@@ -112,14 +190,6 @@ async fn cmd_dev(inputs: &[String], watch: bool) -> Result<()> {
         );
 
         let gen_start = Instant::now();
-        let cache = Cache::load_or_create()?;
-        let generator = TypeGenerator::with_cache_ttl(
-            cache,
-            PathBuf::from("generated"),
-            token,
-            api_url,
-            config.build.cache_ttl_days,
-        );
         generator.generate_types_for_refs(&all_refs).await?;
 
         println!(
@@ -129,8 +199,16 @@ async fn cmd_dev(inputs: &[String], watch: bool) -> Result<()> {
         );
     }
 
+    // Only a scan of the whole workflows directory knows every used ref
+    if inputs.is_empty() {
+        let removed = remove_unused_types(&generator, &config).await?;
+        if !removed.is_empty() {
+            print_removed_types(&removed);
+        }
+    }
+
     if watch {
-        watcher::watch_paths(&paths).await?;
+        watcher::watch_paths(&paths, offline).await?;
     } else {
         println!("{} Done. Run with --watch to keep watching.", "✓".green());
     }
@@ -138,6 +216,54 @@ async fn cmd_dev(inputs: &[String], watch: bool) -> Result<()> {
     Ok(())
 }
 
+/// Remove generated types that no file in the workflows directory uses.
+/// Skipped with a warning when a workflow file cannot be parsed, since its
+/// refs would count as unused.
+async fn remove_unused_types(generator: &TypeGenerator, config: &Config) -> Result<Vec<String>> {
+    let workflows_dir = PathBuf::from(&config.project.workflows_dir);
+    match generator.remove_types_unused_by(&workflows_dir).await {
+        Ok(removed) => Ok(removed),
+        Err(e) => {
+            eprintln!("{} Not removing unused types: {:#}", "⚠️".yellow(), e);
+            Ok(Vec::new())
+        }
+    }
+}
+
+fn print_removed_types(removed: &[String]) {
+    println!(
+        "{} Removed {} unused type file(s):",
+        "🧹".cyan(),
+        removed.len()
+    );
+    for reference in removed {
+        println!("   - {}", reference);
+    }
+    println!();
+}
+
+/// Action refs used by the workflow files in `paths`.
+async fn collect_action_refs(paths: &[PathBuf]) -> Result<std::collections::HashSet<String>> {
+    let mut all_refs = std::collections::HashSet::new();
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+        if path.is_dir() {
+            let results = parser::analyze_directory(path).await?;
+            for refs in results.values() {
+                all_refs.extend(refs.clone());
+            }
+        } else if path.is_file() {
+            match parser::analyze_file(path).await {
+                Ok(refs) => all_refs.extend(refs),
+                Err(e) => eprintln!("Warning: Failed to parse {}: {}", path.display(), e),
+            }
+        }
+    }
+    Ok(all_refs)
+}
+
 async fn cmd_build(inputs: &[String], output: Option<&str>, dry_run: bool) -> Result<()> {
     let start = Instant::now();
 
@@ -175,6 +301,70 @@ async fn cmd_build(inputs: &[String], output: Option<&str>, dry_run: bool) -> Re
     Ok(())
 }
 
+async fn cmd_migrate(
+    files: &[String],
+    dry_run: bool,
+    out: Option<&str>,
+    no_backup: bool,
+    overwrite: bool,
+    dedupe: bool,
+) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let config = Config::load()?;
+
+    let (workflows, actions) = if files.is_empty() {
+        (
+            migration::discover_workflows(&root)?,
+            migration::discover_actions(&root)?,
+        )
+    } else {
+        let mut workflows = Vec::new();
+        let mut actions = Vec::new();
+        for file in files {
+            let path = PathBuf::from(file);
+            if !path.is_file() {
+                anyhow::bail!("File not found: {}", path.display());
+            }
+            if migration::is_action_file(&path) {
+                actions.push(path);
+            } else {
+                workflows.push(path);
+            }
+        }
+        (workflows, actions)
+    };
+
+    if workflows.is_empty() && actions.is_empty() {
+        println!("{} No YAML workflows or actions to migrate", "⚠️".yellow());
+        return Ok(());
+    }
+
+    let out_dir = PathBuf::from(out.unwrap_or(&config.project.workflows_dir));
+    let options = migration::MigrateOptions {
+        import_path: migration::import_path(&out_dir, &config.generated_path()),
+        dry_run,
+        backup: !no_backup,
+        overwrite,
+        dedupe,
+        ..migration::MigrateOptions::new(out_dir)
+    };
+
+    let mut reports = Vec::new();
+    if !workflows.is_empty() {
+        reports.extend(migration::migrate_workflows(&workflows, &options).await?);
+    }
+    if !actions.is_empty() {
+        reports.extend(migration::migrate_actions(&actions, &options).await?);
+    }
+    migration::print_migration_reports(&reports);
+
+    if !dry_run {
+        println!("   Run 'gaji dev' to generate types for the migrated files\n");
+    }
+
+    Ok(())
+}
+
 async fn cmd_list(inputs: &[String], json_output: bool) -> Result<()> {
     let config = Config::load()?;
 
@@ -263,8 +453,11 @@ async fn cmd_list(inputs: &[String], json_output: bool) -> Result<()> {
     Ok(())
 }
 
-async fn cmd_add(action: &str) -> Result<()> {
+async fn cmd_add(action: &str, offline: bool) -> Result<()> {
     let start = Instant::now();
+    if !is_local_ref(action) {
+        ActionRef::parse(action)?;
+    }
     println!("{} Adding action: {}\n", "📦".cyan(), action);
 
     let config = Config::load()?;
@@ -278,7 +471,12 @@ async fn cmd_add(action: &str) -> Result<()> {
         token,
         api_url,
         config.build.cache_ttl_days,
-    );
+    )?
+    .with_network(&config.network)?
+    .with_local_workflows(config.output_path().join("workflows"))
+    .with_type_overrides(config.types.clone())
+    .with_sources(SourceRouter::from_config(&config)?)
+    .with_offline(offline);
 
     let mut refs = std::collections::HashSet::new();
     refs.insert(action.to_string());
@@ -295,7 +493,11 @@ async fn cmd_add(action: &str) -> Result<()> {
             );
         }
         Err(e) => {
-            eprintln!("{} Failed to generate types: {}", "❌".red(), e);
+            eprintln!(
+                "{} Failed to generate types: {}",
+                "❌".red(),
+                credentials::redact(&e.to_string())
+            );
             return Err(e);
         }
     }
@@ -303,11 +505,98 @@ async fn cmd_add(action: &str) -> Result<()> {
     Ok(())
 }
 
-async fn cmd_clean(clean_cache: bool) -> Result<()> {
+async fn cmd_vendor(inputs: &[String], offline: bool) -> Result<()> {
+    if offline {
+        anyhow::bail!("gaji vendor downloads action definitions and cannot run with --offline");
+    }
+
+    let config = Config::load()?;
+    let paths: Vec<PathBuf> = if inputs.is_empty() {
+        vec![PathBuf::from(&config.project.workflows_dir)]
+    } else {
+        inputs.iter().map(PathBuf::from).collect()
+    };
+
+    let mut refs: Vec<String> = collect_action_refs(&paths)
+        .await?
+        .into_iter()
+        .filter(|r| !is_local_ref(r))
+        .collect();
+    refs.sort();
+    if refs.is_empty() {
+        println!("{} No actions to vendor", "📋".cyan());
+        return Ok(());
+    }
+
+    println!(
+        "{} Vendoring {} action(s) into {}...\n",
+        "📦".cyan(),
+        refs.len(),
+        VENDOR_DIR
+    );
+    let fetcher = GitHubFetcher::new(
+        Cache::load_or_create()?,
+        config.resolve_token(),
+        config.resolve_api_url(),
+        config.build.cache_ttl_days,
+    )?
+    .with_network(&config.network)?
+    .with_sources(SourceRouter::from_config(&config)?);
+
+    let mut failed = 0;
+    for reference in &refs {
+        match fetcher.fetch_definition(reference).await {
+            Ok(content) => {
+                let path = vendored_path(std::path::Path::new(VENDOR_DIR), reference);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, content).await?;
+                println!("{} {}", "✓".green(), path.display());
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!(
+                    "{} {}: {}",
+                    "✗".red(),
+                    reference,
+                    credentials::redact(&e.to_string())
+                );
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("Failed to vendor {} of {} action(s)", failed, refs.len());
+    }
+
+    println!(
+        "\n{} Commit {} so builds work without network access",
+        "✨".green(),
+        VENDOR_DIR
+    );
+    Ok(())
+}
+
+async fn cmd_clean(clean_cache: bool, unused: bool) -> Result<()> {
     println!("{} Cleaning generated files...\n", "🧹".cyan());
 
-    // Remove generated directory
-    if PathBuf::from("generated").exists() {
+    if unused {
+        let config = Config::load()?;
+        let generator = TypeGenerator::new(
+            Cache::load_or_create()?,
+            PathBuf::from("generated"),
+            None,
+            None,
+        )?
+        .with_local_workflows(config.output_path().join("workflows"));
+        let removed = remove_unused_types(&generator, &config).await?;
+        if removed.is_empty() {
+            println!("{} No unused types", "✓".green());
+        } else {
+            print_removed_types(&removed);
+        }
+    } else if PathBuf::from("generated").exists() {
+        // Remove generated directory
         tokio::fs::remove_dir_all("generated").await?;
         println!("{} Removed generated/", "✓".green());
     }
@@ -316,7 +605,10 @@ async fn cmd_clean(clean_cache: bool) -> Result<()> {
     if clean_cache {
         let cache = Cache::load_or_create()?;
         cache.clear()?;
-        println!("{} Cleared cache", "✓".green());
+        if PathBuf::from(LEGACY_CACHE_FILE).exists() {
+            tokio::fs::remove_file(LEGACY_CACHE_FILE).await?;
+        }
+        println!("{} Cleared cache ({})", "✓".green(), cache.path().display());
     }
 
     println!("\n{} Clean complete!", "✨".green());
@@ -324,6 +616,189 @@ async fn cmd_clean(clean_cache: bool) -> Result<()> {
     Ok(())
 }
 
+async fn cmd_cache(command: CacheCommand, offline: bool) -> Result<()> {
+    let config = Config::load()?;
+    let cache = Cache::load_or_create()?;
+    let ttl = config.build.cache_ttl_days;
+
+    match command {
+        CacheCommand::Ls { json } => {
+            let refs = cache.refs(ttl);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&refs)?);
+                return Ok(());
+            }
+            if refs.is_empty() {
+                println!("{} Cache is empty", "📦".cyan());
+                return Ok(());
+            }
+
+            println!(
+                "{} {} cached ref(s) in {}\n",
+                "📦".cyan(),
+                refs.len(),
+                cache.path().display()
+            );
+            let now = unix_now();
+            for cached in &refs {
+                let expiry = match cached.expires_at {
+                    None => "pinned".dimmed(),
+                    Some(_) if cached.expired => "expired".yellow(),
+                    Some(at) => {
+                        format!("expires in {}", format_duration(at.saturating_sub(now))).normal()
+                    }
+                };
+                println!(
+                    "  {} {}\n    {} ago · {} · {}",
+                    "•".green(),
+                    cached.reference.bold(),
+                    format_duration(now.saturating_sub(cached.resolved_at)),
+                    &cached.content_hash[..cached.content_hash.len().min(12)],
+                    expiry
+                );
+            }
+        }
+        CacheCommand::Show { reference, json } => {
+            let metadata = if is_reusable_workflow_ref(&reference) {
+                cache
+                    .get_workflow(&reference)
+                    .map(serde_json::to_value)
+                    .transpose()?
+            } else {
+                cache
+                    .get(&reference)
+                    .map(serde_json::to_value)
+                    .transpose()?
+            };
+            let Some(metadata) = metadata else {
+                anyhow::bail!(
+                    "{} is not cached. Run 'gaji add {}' to fetch it",
+                    reference,
+                    reference
+                );
+            };
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&metadata)?);
+            } else {
+                print!("{}", serde_yaml::to_string(&strip_nulls(metadata))?);
+            }
+        }
+        CacheCommand::Refresh { reference } => {
+            let refs = match reference {
+                Some(reference) => vec![reference],
+                None => cache.list(),
+            };
+            if refs.is_empty() {
+                println!("{} Cache is empty", "📦".cyan());
+                return Ok(());
+            }
+
+            let fetcher =
+                GitHubFetcher::new(cache, config.resolve_token(), config.resolve_api_url(), ttl)?
+                    .with_network(&config.network)?
+                    .with_sources(SourceRouter::from_config(&config)?)
+                    .with_offline(offline);
+            let mut failed = 0;
+            for reference in &refs {
+                match fetcher.refresh(reference).await {
+                    Ok(()) => println!("{} Refreshed {}", "✓".green(), reference),
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!(
+                            "{} {}: {}",
+                            "✗".red(),
+                            reference,
+                            credentials::redact(&e.to_string())
+                        );
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("Failed to refresh {} of {} ref(s)", failed, refs.len());
+            }
+            println!("\n   Run 'gaji dev' to regenerate types");
+        }
+        CacheCommand::Prune {
+            unused,
+            all_projects,
+        } => {
+            if unused && !all_projects {
+                anyhow::bail!(
+                    "The cache is shared by all projects, so --unused also removes refs only other projects use. Pass --all-projects to prune anyway"
+                );
+            }
+            let used = if unused {
+                Some(workflow_refs(&config).await?)
+            } else {
+                None
+            };
+            let removed = cache.prune(used.as_ref())?;
+            println!(
+                "{} Removed {} cache entr{}",
+                "✓".green(),
+                removed,
+                if removed == 1 { "y" } else { "ies" }
+            );
+        }
+        CacheCommand::Export { file } => {
+            cache.export(std::path::Path::new(&file))?;
+            println!("{} Exported cache to {}", "✓".green(), file);
+        }
+        CacheCommand::Import { file } => {
+            let count = cache.import(std::path::Path::new(&file))?;
+            println!("{} Imported {} ref(s) from {}", "✓".green(), count, file);
+        }
+    }
+
+    Ok(())
+}
+
+/// Action refs used by the workflows in the project's workflows directory.
+/// Fails when a workflow does not parse, since its refs would count as unused.
+async fn workflow_refs(config: &Config) -> Result<std::collections::HashSet<String>> {
+    let dir = PathBuf::from(&config.project.workflows_dir);
+    if !dir.is_dir() {
+        anyhow::bail!(
+            "Workflows directory {} not found, refusing to treat every ref as unused",
+            dir.display()
+        );
+    }
+    parser::used_refs(&dir)
+        .await
+        .context("Not pruning, the used refs are unknown")
+}
+
+/// Drop `null` fields so optional metadata does not clutter the output.
+fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| (k, strip_nulls(v)))
+            .collect(),
+        serde_json::Value::Array(items) => items.into_iter().map(strip_nulls).collect(),
+        other => other,
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Coarse human duration, e.g. `3d`, `5h` or `12m`.
+fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s >= 86_400 => format!("{}d", s / 86_400),
+        s if s >= 3_600 => format!("{}h", s / 3_600),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
 fn cmd_completions(shell_str: &str) -> Result<()> {
     let shell = shell_str.parse::<Shell>().map_err(|_| {
         anyhow::anyhow!(
@@ -1,14 +1,17 @@
 use oxc_ast::ast::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub struct ActionRefExtractor {
     pub action_refs: HashSet<String>,
+    /// Byte offset of the first occurrence of each ref in the source.
+    pub offsets: HashMap<String, u32>,
 }
 
 impl ActionRefExtractor {
     pub fn new() -> Self {
         Self {
             action_refs: HashSet::new(),
+            offsets: HashMap::new(),
         }
     }
 
@@ -229,12 +232,15 @@ impl ActionRefExtractor {
     }
 
     fn visit_call_expression<'a>(&mut self, call: &CallExpression<'a>) {
-        // Check if this is a getAction call
+        // Check if this is a getAction/getWorkflow call
         match (&call.callee, call.arguments.first()) {
             (Expression::Identifier(ident), Some(Argument::StringLiteral(lit)))
-                if ident.name == "getAction" =>
+                if ident.name == "getAction" || ident.name == "getWorkflow" =>
             {
                 self.action_refs.insert(lit.value.to_string());
+                self.offsets
+                    .entry(lit.value.to_string())
+                    .or_insert(lit.span.start);
             }
             _ => {}
         }
@@ -4,12 +4,13 @@ pub mod extractor;
 use std::collections::HashSet;
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use oxc_allocator::Allocator;
 use oxc_parser::Parser;
 use oxc_span::SourceType;
 
 use self::extractor::ActionRefExtractor;
+use crate::fetcher::{is_local_ref, ActionRef};
 
 pub struct TypeScriptParser {
     allocator: Allocator,
@@ -38,10 +39,41 @@ impl TypeScriptParser {
         let mut extractor = ActionRefExtractor::new();
         extractor.visit_program(&parser_return.program);
 
+        // Catch malformed refs here, where their position is known, rather
+        // than when fetching them
+        let mut invalid: Vec<(u32, String)> = extractor
+            .offsets
+            .iter()
+            .filter(|(reference, _)| !is_local_ref(reference))
+            .filter_map(|(reference, offset)| {
+                let error = ActionRef::parse(reference).err()?;
+                let (line, column) = line_and_column(source, *offset as usize);
+                Some((
+                    *offset,
+                    format!("line {}, column {}: {}", line, column, error),
+                ))
+            })
+            .collect();
+        if !invalid.is_empty() {
+            invalid.sort();
+            let messages: Vec<String> = invalid.into_iter().map(|(_, message)| message).collect();
+            return Err(anyhow::anyhow!("{}", messages.join("\n")));
+        }
+
         Ok(extractor.action_refs)
     }
 }
 
+/// 1-based line and column of the byte `offset` in `source`.
+fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
 impl Default for TypeScriptParser {
     fn default() -> Self {
         Self::new()
@@ -54,6 +86,32 @@ pub async fn analyze_file(path: &Path) -> Result<HashSet<String>> {
     parser.extract_action_refs(&source)
 }
 
+/// Refs used by all `.ts` and `.tsx` files under `dir`. Unlike
+/// [`analyze_directory`], a file that fails to parse is an error, because
+/// its refs would be missing from the result.
+pub async fn used_refs(dir: &Path) -> Result<HashSet<String>> {
+    let mut refs = HashSet::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext == "ts" || ext == "tsx")
+            {
+                let file_refs = analyze_file(&path)
+                    .await
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                refs.extend(file_refs);
+            }
+        }
+    }
+    Ok(refs)
+}
+
 pub async fn analyze_directory(
     dir: &Path,
 ) -> Result<std::collections::HashMap<std::path::PathBuf, HashSet<String>>> {
@@ -153,4 +211,34 @@ mod tests {
         assert!(refs.contains("actions/checkout@v5"));
         assert!(refs.contains("actions/setup-node@v4"));
     }
+
+    #[test]
+    fn test_get_workflow_call() {
+        let parser = TypeScriptParser::new();
+        let source = r#"
+            const deploy = getWorkflow("org/repo/.github/workflows/deploy.yml@v1");
+            const checkout = getAction("actions/checkout@v5");
+        "#;
+        let refs = parser.extract_action_refs(source).unwrap();
+        assert!(refs.contains("org/repo/.github/workflows/deploy.yml@v1"));
+        assert!(refs.contains("actions/checkout@v5"));
+        assert_eq!(refs.len(), 2);
+    }
+
+    #[test]
+    fn test_malformed_refs_are_reported_with_position() {
+        let parser = TypeScriptParser::new();
+        let source = r#"const checkout = getAction("actions/checkout@v5");
+const local = getWorkflow("./.github/workflows/deploy.yml");
+const branch = getAction("org/tool@feature/x");
+  const bad = getAction("actions/checkout");
+const worse = getAction("org/tool@v1..v2");
+"#;
+        let error = parser.extract_action_refs(source).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "line 4, column 25: Invalid action reference 'actions/checkout': missing @ref. Expected owner/repo[/path]@ref\n\
+             line 5, column 25: Invalid action reference 'org/tool@v1..v2': 'v1..v2' is not a valid git ref. Expected owner/repo[/path]@ref"
+        );
+    }
 }
@@ -0,0 +1,866 @@
+//! Where action and reusable workflow definitions are fetched from.
+//!
+//! Each host kind implements [`MetadataSource`]; a [`SourceRouter`] picks the
+//! source for a ref by its owner, as configured under `sources` in
+//! `gaji.config.ts`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use reqwest::StatusCode;
+
+use crate::cache::{is_commit_sha, now};
+use crate::config::{Config, NetworkConfig, SourceConfig};
+use crate::credentials::{host_of, redact, remember_secret};
+use crate::fetcher::{ActionRef, FetchError};
+
+/// Source kinds accepted in the `type` field of a `sources` entry.
+pub const SOURCE_TYPES: &[&str] = &["github", "gitea", "forgejo", "dir"];
+
+/// A host that serves files from repositories at a given ref.
+pub trait MetadataSource: Send + Sync {
+    /// Where files come from, for messages, e.g. `https://api.github.com`.
+    fn describe(&self) -> String;
+
+    /// Whether fetching goes over the network, which `--offline` forbids.
+    fn is_remote(&self) -> bool {
+        true
+    }
+
+    /// Content of `path` in the repository of `repo` at `repo.ref_`, or
+    /// `None` if the file does not exist.
+    fn fetch_file<'a>(
+        &'a self,
+        repo: &'a ActionRef,
+        path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>>>;
+
+    /// Commit SHA `repo.ref_` points to, or `None` if the source cannot
+    /// tell. Fails with [`FetchError::RefNotFound`] when the ref does not
+    /// exist.
+    fn resolve_commit<'a>(&'a self, _repo: &'a ActionRef) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// github.com, GitHub Enterprise Server, or a GitHub Enterprise Cloud host
+/// with data residency (`*.ghe.com`).
+pub struct GitHubSource {
+    client: reqwest::Client,
+    token: Option<String>,
+    api_base: String,
+    /// `raw.githubusercontent.com`, which only exists for github.com.
+    /// Other hosts serve files through the contents API.
+    raw_base: Option<String>,
+}
+
+impl GitHubSource {
+    pub fn github_com(client: reqwest::Client, token: Option<String>) -> Self {
+        Self {
+            client,
+            token,
+            api_base: "https://api.github.com".to_string(),
+            raw_base: Some("https://raw.githubusercontent.com".to_string()),
+        }
+    }
+
+    /// A GitHub host other than github.com, from its web URL:
+    /// `https://octocorp.ghe.com` uses `https://api.octocorp.ghe.com`, any
+    /// other URL is a GitHub Enterprise Server with its API under `/api/v3`.
+    pub fn enterprise(client: reqwest::Client, url: &str, token: Option<String>) -> Self {
+        let url = url.trim_end_matches('/');
+        let api_base = match url.split_once("://") {
+            Some((scheme, host)) if host.ends_with(".ghe.com") => {
+                format!("{}://api.{}", scheme, host)
+            }
+            _ => format!("{}/api/v3", url),
+        };
+        Self {
+            client,
+            token,
+            api_base,
+            raw_base: None,
+        }
+    }
+
+    /// github.com when `api_url` is unset, otherwise an enterprise host.
+    pub fn from_api_url(
+        client: reqwest::Client,
+        api_url: Option<&str>,
+        token: Option<String>,
+    ) -> Self {
+        match api_url {
+            Some(url) => Self::enterprise(client, url, token),
+            None => Self::github_com(client, token),
+        }
+    }
+
+    fn file_url(&self, repo: &ActionRef, path: &str) -> String {
+        match &self.raw_base {
+            Some(raw) => format!(
+                "{}/{}/{}/{}/{}",
+                raw,
+                repo.owner,
+                repo.repo,
+                encode_path(&repo.ref_),
+                encode_path(path)
+            ),
+            None => format!(
+                "{}/repos/{}/{}/contents/{}?ref={}",
+                self.api_base,
+                repo.owner,
+                repo.repo,
+                encode_path(path),
+                encode_component(&repo.ref_)
+            ),
+        }
+    }
+
+    fn commit_url(&self, repo: &ActionRef) -> String {
+        format!(
+            "{}/repos/{}/{}/commits/{}",
+            self.api_base,
+            repo.owner,
+            repo.repo,
+            encode_component(&repo.ref_)
+        )
+    }
+
+    fn headers(&self, accept: &str) -> Vec<(&'static str, String)> {
+        let mut headers = vec![("Accept", accept.to_string())];
+        if let Some(token) = &self.token {
+            headers.push(("Authorization", format!("token {}", token)));
+        }
+        headers
+    }
+}
+
+impl MetadataSource for GitHubSource {
+    fn describe(&self) -> String {
+        self.api_base.clone()
+    }
+
+    fn fetch_file<'a>(
+        &'a self,
+        repo: &'a ActionRef,
+        path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move {
+            let url = self.file_url(repo, path);
+            let headers = self.headers("application/vnd.github.raw+json");
+            Ok(http_get(&self.client, &url, &headers).await?)
+        })
+    }
+
+    fn resolve_commit<'a>(&'a self, repo: &'a ActionRef) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move {
+            let url = self.commit_url(repo);
+            let headers = self.headers("application/vnd.github.sha");
+            let Some(sha) =
+                lookup_commit(&self.client, &url, &headers, repo, self.describe()).await?
+            else {
+                return Ok(None);
+            };
+            let sha = sha.trim().to_string();
+            Ok(is_commit_sha(&sha).then_some(sha))
+        })
+    }
+}
+
+/// A Gitea or Forgejo instance, which share the same API.
+pub struct GiteaSource {
+    client: reqwest::Client,
+    token: Option<String>,
+    base: String,
+}
+
+impl GiteaSource {
+    pub fn new(client: reqwest::Client, url: &str, token: Option<String>) -> Self {
+        Self {
+            client,
+            token,
+            base: format!("{}/api/v1", url.trim_end_matches('/')),
+        }
+    }
+
+    fn file_url(&self, repo: &ActionRef, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}/raw/{}?ref={}",
+            self.base,
+            repo.owner,
+            repo.repo,
+            encode_path(path),
+            encode_component(&repo.ref_)
+        )
+    }
+
+    fn commit_url(&self, repo: &ActionRef) -> String {
+        format!(
+            "{}/repos/{}/{}/commits?sha={}&limit=1&stat=false",
+            self.base,
+            repo.owner,
+            repo.repo,
+            encode_component(&repo.ref_)
+        )
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        self.token
+            .iter()
+            .map(|token| ("Authorization", format!("token {}", token)))
+            .collect()
+    }
+}
+
+impl MetadataSource for GiteaSource {
+    fn describe(&self) -> String {
+        self.base.clone()
+    }
+
+    fn fetch_file<'a>(
+        &'a self,
+        repo: &'a ActionRef,
+        path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move {
+            let url = self.file_url(repo, path);
+            Ok(http_get(&self.client, &url, &self.headers()).await?)
+        })
+    }
+
+    fn resolve_commit<'a>(&'a self, repo: &'a ActionRef) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move {
+            let url = self.commit_url(repo);
+            let Some(body) =
+                lookup_commit(&self.client, &url, &self.headers(), repo, self.describe()).await?
+            else {
+                return Ok(None);
+            };
+            let commits: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+            let sha = commits[0]["sha"].as_str().unwrap_or_default().to_string();
+            Ok(is_commit_sha(&sha).then_some(sha))
+        })
+    }
+}
+
+/// Checkouts on disk, laid out as `<root>/<owner>/<repo>`. The ref is
+/// ignored: whatever is checked out is used.
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl MetadataSource for DirSource {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    fn fetch_file<'a>(
+        &'a self,
+        repo: &'a ActionRef,
+        path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move {
+            let file = self.root.join(&repo.owner).join(&repo.repo).join(path);
+            if !file.is_file() {
+                return Ok(None);
+            }
+            tokio::fs::read_to_string(&file)
+                .await
+                .map(Some)
+                .with_context(|| format!("Failed to read {}", file.display()))
+        })
+    }
+}
+
+/// Files held in memory, keyed by `owner/repo@ref` and path. Useful in tests.
+#[derive(Default)]
+pub struct MemorySource {
+    files: HashMap<String, String>,
+    commits: HashMap<String, String>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `content` as `path` in `repo` (`owner/repo@ref`).
+    pub fn with_file(mut self, repo: &str, path: &str, content: &str) -> Self {
+        self.files
+            .insert(format!("{}:{}", repo, path), content.to_string());
+        self
+    }
+
+    /// Resolve `repo` (`owner/repo@ref`) to `sha`.
+    pub fn with_commit(mut self, repo: &str, sha: &str) -> Self {
+        self.commits.insert(repo.to_string(), sha.to_string());
+        self
+    }
+
+    fn repo_key(repo: &ActionRef) -> String {
+        format!("{}/{}@{}", repo.owner, repo.repo, repo.ref_)
+    }
+}
+
+impl MetadataSource for MemorySource {
+    fn describe(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    fn fetch_file<'a>(
+        &'a self,
+        repo: &'a ActionRef,
+        path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>>> {
+        let key = format!("{}:{}", Self::repo_key(repo), path);
+        Box::pin(async move { Ok(self.files.get(&key).cloned()) })
+    }
+
+    fn resolve_commit<'a>(&'a self, repo: &'a ActionRef) -> BoxFuture<'a, Result<Option<String>>> {
+        let sha = self.commits.get(&Self::repo_key(repo)).cloned();
+        Box::pin(async move { Ok(sha) })
+    }
+}
+
+/// Picks the source for a ref by its owner. Routes are `owner/repo`,
+/// `owner/*` or `*`; the most specific match wins, and refs that match no
+/// route use the default source.
+pub struct SourceRouter {
+    routes: HashMap<String, Arc<dyn MetadataSource>>,
+    default: Arc<dyn MetadataSource>,
+}
+
+impl SourceRouter {
+    pub fn new(default: Arc<dyn MetadataSource>) -> Self {
+        Self {
+            routes: HashMap::new(),
+            default,
+        }
+    }
+
+    /// Send refs matching `pattern` to `source`.
+    pub fn route(mut self, pattern: &str, source: Arc<dyn MetadataSource>) -> Result<Self> {
+        let valid = pattern == "*"
+            || matches!(pattern.split_once('/'), Some((owner, repo))
+                if !owner.is_empty() && !owner.contains('*') && !repo.is_empty()
+                    && !repo.contains('/') && (repo == "*" || !repo.contains('*')));
+        if !valid {
+            anyhow::bail!(
+                "Invalid source pattern '{}'. Expected owner/repo, owner/* or *",
+                pattern
+            );
+        }
+        self.routes.insert(pattern.to_string(), source);
+        Ok(self)
+    }
+
+    /// The github.com or `github.apiUrl` host as default, plus the
+    /// `sources` routes of the config, all fetching with the `network`
+    /// settings.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let client = http_client(&config.network)?;
+        let default = GitHubSource::from_api_url(
+            client.clone(),
+            config.resolve_api_url().as_deref(),
+            config.resolve_token(),
+        );
+        let mut router = Self::new(Arc::new(default));
+
+        for (pattern, source) in &config.sources {
+            router = router
+                .route(pattern, build_source(source, config, &client)?)
+                .with_context(|| format!("Invalid `sources[\"{}\"]`", pattern))?;
+        }
+        Ok(router)
+    }
+
+    pub fn source_for(&self, repo: &ActionRef) -> &dyn MetadataSource {
+        let exact = format!("{}/{}", repo.owner, repo.repo);
+        let owner = format!("{}/*", repo.owner);
+        [exact.as_str(), owner.as_str(), "*"]
+            .iter()
+            .find_map(|pattern| self.routes.get(*pattern))
+            .unwrap_or(&self.default)
+            .as_ref()
+    }
+}
+
+/// Source for a `sources` entry. Without `token`, the token comes from the
+/// credential chain for the entry's host.
+fn build_source(
+    config: &SourceConfig,
+    gaji: &Config,
+    client: &reqwest::Client,
+) -> Result<Arc<dyn MetadataSource>> {
+    let url = || {
+        config
+            .url
+            .as_deref()
+            .with_context(|| format!("A `{}` source needs `url`", config.source_type))
+    };
+    let token = || match &config.token {
+        Some(token) => {
+            remember_secret(token);
+            Some(token.clone())
+        }
+        None => gaji.token_for_host(&host_of(config.url.as_deref())),
+    };
+
+    Ok(match config.source_type.as_str() {
+        "github" => Arc::new(GitHubSource::from_api_url(
+            client.clone(),
+            config.url.as_deref(),
+            token(),
+        )),
+        "gitea" | "forgejo" => Arc::new(GiteaSource::new(client.clone(), url()?, token())),
+        "dir" => {
+            let path = config
+                .path
+                .as_deref()
+                .context("A `dir` source needs `path`")?;
+            Arc::new(DirSource::new(path))
+        }
+        other => anyhow::bail!(
+            "Unknown source type '{}'. Expected one of: {}",
+            other,
+            SOURCE_TYPES.join(", ")
+        ),
+    })
+}
+
+/// HTTP client shared by the sources and the fetcher, set up with the
+/// proxy, certificates and timeouts of `network`.
+pub fn http_client(network: &NetworkConfig) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().user_agent("gaji");
+
+    if network.timeout_secs > 0 {
+        builder = builder.timeout(Duration::from_secs(network.timeout_secs));
+    }
+    if network.connect_timeout_secs > 0 {
+        builder = builder.connect_timeout(Duration::from_secs(network.connect_timeout_secs));
+    }
+
+    if let Some(proxy) = &network.proxy {
+        let proxy = reqwest::Proxy::all(proxy.as_str())
+            .with_context(|| format!("Invalid `network.proxy` URL '{}'", redact(proxy)))?
+            .no_proxy(reqwest::NoProxy::from_string(&network.no_proxy.join(",")));
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &network.ca_certs {
+        let pem = std::fs::read(path)
+            .with_context(|| format!("Failed to read CA bundle '{}' in `network.caCerts`", path))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("'{}' in `network.caCerts` is not a PEM bundle", path))?;
+        builder = builder.tls_certs_merge(certs);
+    }
+
+    if let Some(cert) = &network.client_cert {
+        let mut pem = std::fs::read(cert).with_context(|| {
+            format!(
+                "Failed to read client certificate '{}' in `network.clientCert`",
+                cert
+            )
+        })?;
+        if let Some(key) = &network.client_key {
+            pem.push(b'\n');
+            pem.extend(std::fs::read(key).with_context(|| {
+                format!("Failed to read client key '{}' in `network.clientKey`", key)
+            })?);
+        }
+        let identity = reqwest::Identity::from_pem(&pem).with_context(|| {
+            format!(
+                "Invalid client certificate '{}'. Expected a PEM certificate and private key",
+                cert
+            )
+        })?;
+        builder = builder.identity(identity);
+    } else if network.client_key.is_some() {
+        anyhow::bail!("`network.clientKey` needs `network.clientCert`");
+    }
+
+    builder.build().context("Failed to create HTTP client")
+}
+
+/// Percent-encode everything but unreserved characters (RFC 3986), for a
+/// path segment or query value.
+pub(crate) fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Percent-encode each segment of a `/`-separated path.
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Retries after rate limits, server errors and network errors.
+const MAX_RETRIES: u32 = 3;
+
+/// Longest rate-limit wait worth sleeping through. Longer ones, such as the
+/// hourly GitHub limit, fail right away.
+const MAX_RATE_LIMIT_WAIT_SECS: u64 = 60;
+
+/// GET `url`, retrying rate limits, server errors and network errors.
+/// Returns `None` for 404.
+pub(crate) async fn http_get(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &[(&str, String)],
+) -> Result<Option<String>, FetchError> {
+    get_with_retries(client, url, headers, MAX_RETRIES).await
+}
+
+/// Look up the commit of `repo` once, without retries. Fails when the ref
+/// does not exist or the token is rejected. Other failures, such as an API
+/// rate limit the file download may not hit, give `None`.
+async fn lookup_commit(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &[(&str, String)],
+    repo: &ActionRef,
+    host: String,
+) -> Result<Option<String>, FetchError> {
+    match get_with_retries(client, url, headers, 0).await {
+        Ok(Some(body)) => Ok(Some(body)),
+        Ok(None) => Err(FetchError::RefNotFound {
+            repo: format!("{}/{}", repo.owner, repo.repo),
+            ref_: repo.ref_.clone(),
+            host,
+        }),
+        Err(error @ FetchError::Auth { .. }) => Err(error),
+        Err(_) => Ok(None),
+    }
+}
+
+async fn get_with_retries(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &[(&str, String)],
+    max_retries: u32,
+) -> Result<Option<String>, FetchError> {
+    let mut retries = 0;
+
+    loop {
+        let mut request = client.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+
+        let backoff = 2_u64.pow(retries + 1);
+        let (error, wait) = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    return response
+                        .text()
+                        .await
+                        .map(Some)
+                        .map_err(|e| network_error(url, &e));
+                }
+                if status == StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+
+                let header = |name: &str| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                };
+                let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+                    || (status == StatusCode::FORBIDDEN
+                        && (header("x-ratelimit-remaining") == Some("0")
+                            || header("retry-after").is_some()));
+
+                if rate_limited {
+                    let retry_in =
+                        rate_limit_wait(header("retry-after"), header("x-ratelimit-reset"), now());
+                    let error = FetchError::RateLimited {
+                        url: url.to_string(),
+                        retry_in,
+                    };
+                    match retry_in {
+                        Some(secs) if secs > MAX_RATE_LIMIT_WAIT_SECS => return Err(error),
+                        Some(secs) => (error, secs),
+                        None => (error, backoff),
+                    }
+                } else if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+                    return Err(FetchError::Auth {
+                        url: url.to_string(),
+                        status: status.as_u16(),
+                    });
+                } else {
+                    let error = FetchError::Http {
+                        url: url.to_string(),
+                        status: status.as_u16(),
+                    };
+                    if !status.is_server_error() {
+                        return Err(error);
+                    }
+                    (error, backoff)
+                }
+            }
+            Err(e) => (network_error(url, &e), backoff),
+        };
+
+        if retries >= max_retries {
+            return Err(error);
+        }
+        retries += 1;
+        eprintln!(
+            "{}, retrying in {} seconds ({}/{})",
+            redact(error.to_string().lines().next().unwrap_or_default()),
+            wait,
+            retries,
+            max_retries
+        );
+        tokio::time::sleep(Duration::from_secs(wait)).await;
+    }
+}
+
+/// Seconds to wait before retrying a rate-limited request, from the
+/// `Retry-After` header (seconds or an HTTP date) or else the
+/// `X-RateLimit-Reset` Unix time.
+fn rate_limit_wait(retry_after: Option<&str>, reset: Option<&str>, now: u64) -> Option<u64> {
+    if let Some(value) = retry_after.map(str::trim) {
+        if let Ok(secs) = value.parse() {
+            return Some(secs);
+        }
+        if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+            return Some(
+                u64::try_from(date.timestamp())
+                    .unwrap_or_default()
+                    .saturating_sub(now),
+            );
+        }
+    }
+    let reset: u64 = reset?.trim().parse().ok()?;
+    // The limit resets at the start of that second
+    Some(reset.saturating_sub(now) + 1)
+}
+
+fn network_error(url: &str, error: &reqwest::Error) -> FetchError {
+    let message = if error.is_timeout() {
+        "the request timed out".to_string()
+    } else {
+        let mut message = error.to_string();
+        let mut source = std::error::Error::source(error);
+        while let Some(cause) = source {
+            message = format!("{}: {}", message, cause);
+            source = cause.source();
+        }
+        message
+    };
+    FetchError::Network {
+        url: url.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(reference: &str) -> ActionRef {
+        ActionRef::parse(reference).unwrap()
+    }
+
+    #[test]
+    fn test_github_urls() {
+        let source = GitHubSource::github_com(reqwest::Client::new(), None);
+        assert_eq!(
+            source.file_url(&repo("actions/checkout@v5"), "action.yml"),
+            "https://raw.githubusercontent.com/actions/checkout/v5/action.yml"
+        );
+        assert_eq!(
+            source.file_url(&repo("owner/repo/sub/path@main"), "sub/path/action.yaml"),
+            "https://raw.githubusercontent.com/owner/repo/main/sub/path/action.yaml"
+        );
+        assert_eq!(
+            source.commit_url(&repo("actions/checkout@v5")),
+            "https://api.github.com/repos/actions/checkout/commits/v5"
+        );
+
+        let ghes = GitHubSource::enterprise(reqwest::Client::new(), "https://ghe.corp.com/", None);
+        assert_eq!(
+            ghes.file_url(
+                &repo("org/repo/.github/workflows/deploy.yml@v1"),
+                ".github/workflows/deploy.yml"
+            ),
+            "https://ghe.corp.com/api/v3/repos/org/repo/contents/.github/workflows/deploy.yml?ref=v1"
+        );
+
+        let residency =
+            GitHubSource::enterprise(reqwest::Client::new(), "https://octocorp.ghe.com", None);
+        assert_eq!(
+            residency.commit_url(&repo("org/repo@v1")),
+            "https://api.octocorp.ghe.com/repos/org/repo/commits/v1"
+        );
+    }
+
+    #[test]
+    fn test_urls_encode_refs_and_paths() {
+        let github = GitHubSource::github_com(reqwest::Client::new(), None);
+        let branch = repo("org/tool/lint+fix@feature/x+y");
+        assert_eq!(
+            github.file_url(&branch, "lint+fix/action.yml"),
+            "https://raw.githubusercontent.com/org/tool/feature/x%2By/lint%2Bfix/action.yml"
+        );
+        assert_eq!(
+            github.commit_url(&branch),
+            "https://api.github.com/repos/org/tool/commits/feature%2Fx%2By"
+        );
+
+        let gitea = GiteaSource::new(reqwest::Client::new(), "https://codeberg.org", None);
+        assert_eq!(
+            gitea.commit_url(&branch),
+            "https://codeberg.org/api/v1/repos/org/tool/commits?sha=feature%2Fx%2By&limit=1&stat=false"
+        );
+    }
+
+    #[test]
+    fn test_gitea_urls() {
+        let source = GiteaSource::new(reqwest::Client::new(), "https://codeberg.org/", None);
+        assert_eq!(
+            source.file_url(&repo("forge/action@v2"), "action.yml"),
+            "https://codeberg.org/api/v1/repos/forge/action/raw/action.yml?ref=v2"
+        );
+        assert_eq!(
+            source.commit_url(&repo("forge/action@v2")),
+            "https://codeberg.org/api/v1/repos/forge/action/commits?sha=v2&limit=1&stat=false"
+        );
+    }
+
+    #[test]
+    fn test_http_client_reports_bad_network_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = |network: NetworkConfig| format!("{:#}", http_client(&network).unwrap_err());
+
+        assert!(http_client(&NetworkConfig::default()).is_ok());
+        assert!(error(NetworkConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        })
+        .starts_with("Invalid `network.proxy` URL 'not a url'"));
+
+        let missing = dir.path().join("missing.pem").display().to_string();
+        assert!(error(NetworkConfig {
+            ca_certs: vec![missing.clone()],
+            ..Default::default()
+        })
+        .starts_with(&format!(
+            "Failed to read CA bundle '{}' in `network.caCerts`",
+            missing
+        )));
+
+        let garbage = dir.path().join("garbage.pem");
+        std::fs::write(&garbage, "not a certificate").unwrap();
+        assert!(error(NetworkConfig {
+            client_cert: Some(garbage.display().to_string()),
+            ..Default::default()
+        })
+        .starts_with("Invalid client certificate"));
+
+        assert_eq!(
+            error(NetworkConfig {
+                client_key: Some("key.pem".to_string()),
+                ..Default::default()
+            }),
+            "`network.clientKey` needs `network.clientCert`"
+        );
+    }
+
+    #[test]
+    fn test_router_prefers_most_specific_route() {
+        let named = |name: &str| -> Arc<dyn MetadataSource> { Arc::new(DirSource::new(name)) };
+        let router = SourceRouter::new(named("default"))
+            .route("myorg/*", named("org"))
+            .unwrap()
+            .route("myorg/special", named("special"))
+            .unwrap();
+
+        let describe = |reference: &str| router.source_for(&repo(reference)).describe();
+        assert_eq!(describe("myorg/tool@v1"), "org");
+        assert_eq!(describe("myorg/special/sub@v1"), "special");
+        assert_eq!(describe("actions/checkout@v5"), "default");
+
+        assert!(SourceRouter::new(named("default"))
+            .route("my*/tool", named("bad"))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_and_dir_sources() {
+        let memory = MemorySource::new().with_file("org/action@v1", "action.yml", "name: Mem");
+        assert_eq!(
+            memory
+                .fetch_file(&repo("org/action@v1"), "action.yml")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("name: Mem")
+        );
+        assert!(memory
+            .fetch_file(&repo("org/action@v2"), "action.yml")
+            .await
+            .unwrap()
+            .is_none());
+
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("org/action/sub")).unwrap();
+        std::fs::write(temp.path().join("org/action/sub/action.yml"), "name: Dir").unwrap();
+        let dir = DirSource::new(temp.path());
+        assert_eq!(
+            dir.fetch_file(&repo("org/action/sub@any"), "sub/action.yml")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("name: Dir")
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        let now = 1_700_000_000;
+        assert_eq!(rate_limit_wait(Some("30"), None, now), Some(30));
+        // Retry-After wins over the reset time
+        assert_eq!(rate_limit_wait(Some("5"), Some("1700000100"), now), Some(5));
+        assert_eq!(rate_limit_wait(None, Some("1700000100"), now), Some(101));
+        assert_eq!(
+            rate_limit_wait(Some("Tue, 14 Nov 2023 22:13:40 GMT"), None, now),
+            Some(20)
+        );
+        // A reset in the past means retrying right away
+        assert_eq!(rate_limit_wait(None, Some("1600000000"), now), Some(1));
+        assert_eq!(rate_limit_wait(None, None, now), None);
+    }
+}
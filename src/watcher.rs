@@ -9,12 +9,14 @@ use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watche
 
 use crate::cache::Cache;
 use crate::config::Config as GajiConfig;
+use crate::credentials::redact;
 use crate::generator::TypeGenerator;
 use crate::parser;
+use crate::source::SourceRouter;
 
 const DEBOUNCE_DURATION: Duration = Duration::from_millis(300);
 
-pub async fn watch_paths(paths: &[PathBuf]) -> Result<()> {
+pub async fn watch_paths(paths: &[PathBuf], offline: bool) -> Result<()> {
     let display: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
     println!(
         "{} Watching {} for changes...",
@@ -69,8 +71,12 @@ pub async fn watch_paths(paths: &[PathBuf]) -> Result<()> {
 
                 if should_process_event(&event, &ignored_patterns, has_file_filter, &watched_files)
                 {
-                    if let Err(e) = handle_event(&event).await {
-                        eprintln!("{} Error handling event: {}", "❌".red(), e);
+                    if let Err(e) = handle_event(&event, offline).await {
+                        eprintln!(
+                            "{} Error handling event: {}",
+                            "❌".red(),
+                            redact(&e.to_string())
+                        );
                     }
                 }
             }
@@ -126,7 +132,7 @@ fn should_process_event(
     false
 }
 
-async fn handle_event(event: &Event) -> Result<()> {
+async fn handle_event(event: &Event, offline: bool) -> Result<()> {
     for path in &event.paths {
         println!(
             "{} {} changed",
@@ -161,7 +167,12 @@ async fn handle_event(event: &Event) -> Result<()> {
             token,
             api_url,
             gaji_config.build.cache_ttl_days,
-        );
+        )?
+        .with_network(&gaji_config.network)?
+        .with_local_workflows(gaji_config.output_path().join("workflows"))
+        .with_type_overrides(gaji_config.types.clone())
+        .with_sources(SourceRouter::from_config(&gaji_config)?)
+        .with_offline(offline);
 
         let new_refs: std::collections::HashSet<String> = action_refs
             .into_iter()
@@ -196,7 +207,11 @@ async fn handle_event(event: &Event) -> Result<()> {
                 }
             }
             Err(e) => {
-                eprintln!("   {} Failed to generate types: {}", "❌".red(), e);
+                eprintln!(
+                    "   {} Failed to generate types: {}",
+                    "❌".red(),
+                    redact(&e.to_string())
+                );
             }
         }
     }
@@ -435,6 +435,42 @@ new Workflow({
     assert!(!yaml_str.contains("toJSON"));
 }
 
+/// Test that list-typed inputs are joined into newline-separated strings.
+#[test]
+fn test_action_list_inputs_joined() {
+    use gaji::executor;
+
+    let runtime_js = format!(
+        "var __action_outputs = {{}};\n{}\n{}",
+        gaji::generator::templates::GET_ACTION_RUNTIME_TEMPLATE,
+        gaji::generator::templates::JOB_WORKFLOW_RUNTIME_TEMPLATE,
+    );
+
+    let workflow_js = r#"
+var cache = getAction("actions/cache@v4");
+
+new Workflow({
+    name: "List Inputs",
+    on: { push: {} },
+}).jobs(function(j) { return j
+    .add("build",
+        new Job("ubuntu-latest")
+            .steps(function(s) { return s
+                .add(cache({ with: { path: ["~/.npm", "node_modules"], key: "deps" } }))
+            })
+    )
+}).build("list-inputs");
+"#;
+
+    let bundled = format!("{}\n\n{}", strip_module_syntax(&runtime_js), workflow_js);
+    let outputs = executor::execute_js(&bundled).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&outputs[0].json).unwrap();
+
+    let step = &json["jobs"]["build"]["steps"][0];
+    assert_eq!(step["with"]["path"], "~/.npm\nnode_modules");
+    assert_eq!(step["with"]["key"], "deps");
+}
+
 /// Test that action step outputs are empty when no `id` is provided.
 #[test]
 fn test_action_step_outputs_without_id() {
@@ -689,6 +725,114 @@ async fn test_list_action_refs_from_directory() {
     assert_eq!(action_to_files["actions/cache@v4"], vec!["deploy.ts"]);
 }
 
+/// Test that types of refs no workflow uses anymore are removed, while used
+/// refs, locally built workflows and hand-written declarations are kept, and
+/// that a workflow that fails to parse blocks the removal.
+#[tokio::test]
+async fn test_remove_unused_types() {
+    use gaji::source::{MemorySource, SourceRouter};
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let workflow_dir = dir.path().join("workflows");
+    std::fs::create_dir_all(&workflow_dir).unwrap();
+    std::fs::write(
+        workflow_dir.join("ci.ts"),
+        r#"const lint = getAction("org/lint@v2");"#,
+    )
+    .unwrap();
+
+    // A reusable workflow gaji built, which no getWorkflow() call mentions
+    let local_workflows = dir.path().join(".github").join("workflows");
+    std::fs::create_dir_all(&local_workflows).unwrap();
+    std::fs::write(
+        local_workflows.join("deploy.yml"),
+        "# Auto-generated by gaji\non:\n  workflow_call: {}\njobs: {}\n",
+    )
+    .unwrap();
+
+    let generated_dir = dir.path().join("generated");
+    let memory = MemorySource::new()
+        .with_file("org/lint@v1", "action.yml", "name: Lint\n")
+        .with_file("org/lint@v2", "action.yml", "name: Lint\n");
+    let generator = gaji::generator::TypeGenerator::new(
+        gaji::cache::Cache::load_from(dir.path().join("cache.json")).unwrap(),
+        generated_dir.clone(),
+        None,
+        None,
+    )
+    .unwrap()
+    .with_local_workflows(local_workflows)
+    .with_sources(SourceRouter::new(Arc::new(memory)));
+    generator
+        .generate_types_for_refs(&HashSet::from([
+            "org/lint@v1".to_string(),
+            "org/lint@v2".to_string(),
+        ]))
+        .await
+        .unwrap();
+
+    // Left by a gaji without the manifest, and written by hand
+    std::fs::write(
+        generated_dir.join("org-old-v1.d.ts"),
+        "// Auto-generated from org/old@v1\n// Do not edit manually\n",
+    )
+    .unwrap();
+    std::fs::write(
+        generated_dir.join("custom.d.ts"),
+        "declare const custom: string;\n",
+    )
+    .unwrap();
+    std::fs::write(
+        generated_dir.join("org-fake-v1.d.ts"),
+        "// Auto-generated from org/other@v1\n",
+    )
+    .unwrap();
+
+    // A file that fails to parse could hold any ref, so nothing is removed
+    std::fs::write(workflow_dir.join("broken.ts"), "const x = ;").unwrap();
+    let error = generator
+        .remove_types_unused_by(&workflow_dir)
+        .await
+        .unwrap_err();
+    assert!(format!("{:#}", error).contains("broken.ts"));
+    assert!(generated_dir.join("org-lint-v1.d.ts").exists());
+    assert!(generated_dir.join("org-old-v1.d.ts").exists());
+    std::fs::remove_file(workflow_dir.join("broken.ts")).unwrap();
+
+    let removed = generator
+        .remove_types_unused_by(&workflow_dir)
+        .await
+        .unwrap();
+    assert_eq!(removed, vec!["org/lint@v1", "org/old@v1"]);
+
+    assert!(!generated_dir.join("org-lint-v1.d.ts").exists());
+    assert!(!generated_dir.join("org-old-v1.d.ts").exists());
+    assert!(generated_dir.join("org-lint-v2.d.ts").exists());
+    assert!(generated_dir
+        .join("github-workflows-deploy-yml.d.ts")
+        .exists());
+    assert!(generated_dir.join("custom.d.ts").exists());
+    assert!(generated_dir.join("org-fake-v1.d.ts").exists());
+    assert!(generated_dir.join("base.d.ts").exists());
+
+    let index_dts = std::fs::read_to_string(generated_dir.join("index.d.ts")).unwrap();
+    assert!(!index_dts.contains("org/lint@v1"));
+    assert!(index_dts.contains("ref: 'org/lint@v2'"));
+    assert!(index_dts.contains("ref: './.github/workflows/deploy.yml'"));
+    let manifest = std::fs::read_to_string(generated_dir.join("manifest.json")).unwrap();
+    assert!(!manifest.contains("org/lint@v1"));
+    assert!(manifest.contains("org/lint@v2"));
+    assert!(manifest.contains("./.github/workflows/deploy.yml"));
+
+    assert!(generator
+        .remove_types_unused_by(&workflow_dir)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
 /// Test that step callbacks receive previous step outputs via the `output` context.
 #[test]
 fn test_step_builder_callback_context() {
@@ -836,6 +980,85 @@ new Workflow({
     );
 }
 
+/// Test that a workflow importing a local helper module (as written by
+/// `gaji migrate --dedupe`) builds, and that `StepBuilder.add` accepts the
+/// step arrays such helpers return.
+#[test]
+fn test_workflow_with_shared_module() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let workflows_dir = dir.path().join("workflows");
+    let generated_dir = dir.path().join("generated");
+    std::fs::create_dir_all(&workflows_dir).unwrap();
+    std::fs::create_dir_all(&generated_dir).unwrap();
+
+    let runtime_js = format!(
+        "var __action_outputs = {{}};\n{}\n{}",
+        gaji::generator::templates::GET_ACTION_RUNTIME_TEMPLATE,
+        gaji::generator::templates::JOB_WORKFLOW_RUNTIME_TEMPLATE
+    );
+    let runtime_path = generated_dir.join("index.js");
+    std::fs::write(&runtime_path, runtime_js).unwrap();
+
+    std::fs::write(
+        workflows_dir.join("shared.ts"),
+        r#"import { getAction } from "../generated/index.js";
+import type { JobStep } from "../generated/index.js";
+
+const checkout = getAction("actions/checkout@v5");
+
+export function checkoutSteps(fetchDepth: number): JobStep[] {
+    return [
+        checkout({ with: { "fetch-depth": fetchDepth } }),
+        { run: "npm ci" },
+    ];
+}
+"#,
+    )
+    .unwrap();
+    let workflow_path = workflows_dir.join("ci.ts");
+    std::fs::write(
+        &workflow_path,
+        r#"import { getAction, Job, Workflow } from "../generated/index.js";
+import { checkoutSteps } from "./shared.js";
+
+const checkout = getAction("actions/checkout@v4");
+
+new Workflow({ name: "CI", on: { push: {} } })
+    .jobs(j => j
+        .add("test", new Job("ubuntu-latest")
+            .steps(s => s
+                .add(checkoutSteps(0))
+                .add(checkout({}))
+                .add({ run: "npm test" })
+            )
+        )
+    )
+    .build("ci");
+"#,
+    )
+    .unwrap();
+
+    let outputs = gaji::executor::execute_workflow(&workflow_path, &runtime_path).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&outputs[0].json).unwrap();
+    let steps = &json["jobs"]["test"]["steps"];
+    assert_eq!(steps[0]["uses"], "actions/checkout@v5");
+    assert_eq!(steps[0]["with"]["fetch-depth"], 0);
+    assert_eq!(steps[1]["run"], "npm ci");
+    assert_eq!(steps[2]["uses"], "actions/checkout@v4");
+    assert_eq!(steps[3]["run"], "npm test");
+
+    // The helper module is bundled into workflows, not built on its own
+    assert_eq!(
+        gaji::executor::local_module_paths(
+            &workflow_path,
+            &std::fs::read_to_string(&workflow_path).unwrap()
+        ),
+        vec![gaji::executor::normalize_path(
+            &workflows_dir.join("shared.ts")
+        )]
+    );
+}
+
 /// Test WorkflowBuilder.build_all with an empty directory.
 #[tokio::test]
 async fn test_build_all_empty_directory() {
@@ -892,3 +1115,247 @@ export default defineConfig({
         std::path::PathBuf::from("src/generated")
     );
 }
+
+/// Every bundled type override must have a fixture `action.yml` and a matching
+/// `.d.ts` snapshot. Run with `UPDATE_SNAPSHOTS=1` to rewrite the snapshots.
+#[test]
+fn test_bundled_override_snapshots() {
+    use gaji::fetcher::ActionMetadata;
+    use gaji::generator::overrides::bundled_refs;
+    use gaji::generator::{action_ref_to_filename, types::generate_type_definition};
+
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let update = std::env::var("UPDATE_SNAPSHOTS").is_ok();
+
+    for action_ref in bundled_refs() {
+        let filename = action_ref_to_filename(action_ref);
+        let fixture_path = root
+            .join("fixtures/actions")
+            .join(filename.replace(".d.ts", ".yml"));
+        let snapshot_path = root.join("snapshots").join(&filename);
+
+        let fixture = std::fs::read_to_string(&fixture_path)
+            .unwrap_or_else(|_| panic!("missing fixture {}", fixture_path.display()));
+        let metadata: ActionMetadata = serde_yaml::from_str(&fixture).unwrap();
+        let generated = generate_type_definition(action_ref, &metadata, None);
+
+        if update {
+            std::fs::write(&snapshot_path, &generated).unwrap();
+            continue;
+        }
+
+        let snapshot = std::fs::read_to_string(&snapshot_path)
+            .unwrap_or_else(|_| panic!("missing snapshot {}", snapshot_path.display()));
+        assert_eq!(
+            generated, snapshot,
+            "{} does not match its snapshot",
+            action_ref
+        );
+    }
+}
+
+/// Raw HTTP response with `status` (e.g. `200 OK`) and extra header lines.
+fn http_response(status: &str, headers: &[&str], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\n", status);
+    for header in headers {
+        response.push_str(header);
+        response.push_str("\r\n");
+    }
+    format!(
+        "{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response,
+        body.len(),
+        body
+    )
+}
+
+/// Minimal HTTP server standing in for a forge. Serves `routes` (path and
+/// query → raw response), answers 404 for anything else, and records every
+/// request.
+async fn serve_http(
+    routes: Vec<(String, String)>,
+) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+            let request = String::from_utf8_lossy(&buf);
+            let path = request.split(' ').nth(1).unwrap_or_default().to_string();
+            recorded.lock().unwrap().push(path.clone());
+
+            let response = match routes.iter().find(|(route, _)| *route == path) {
+                Some((_, response)) => response.clone(),
+                None => http_response("404 Not Found", &[], ""),
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (base, requests)
+}
+
+/// Refs are routed by owner: `myorg/*` goes to a GitHub Enterprise Server
+/// stand-in and `forge/*` to a Gitea stand-in, each with its own API layout.
+#[tokio::test]
+async fn test_fetch_routes_refs_to_configured_sources() {
+    use gaji::config::NetworkConfig;
+    use gaji::source::{http_client, GitHubSource, GiteaSource, MemorySource, SourceRouter};
+    use std::sync::Arc;
+
+    let sha = "0123456789abcdef0123456789abcdef01234567";
+    let (ghes_url, ghes_requests) = serve_http(vec![
+        (
+            "/api/v3/repos/myorg/tool/commits/v1".to_string(),
+            http_response("200 OK", &[], sha),
+        ),
+        (
+            format!("/api/v3/repos/myorg/tool/contents/action.yml?ref={}", sha),
+            http_response("200 OK", &[], "name: Enterprise Tool\n"),
+        ),
+    ])
+    .await;
+    let (gitea_url, gitea_requests) = serve_http(vec![
+        (
+            "/api/v1/repos/forge/lint/commits?sha=v2&limit=1&stat=false".to_string(),
+            http_response("200 OK", &[], &format!(r#"[{{"sha": "{}"}}]"#, sha)),
+        ),
+        (
+            format!("/api/v1/repos/forge/lint/raw/sub/action.yaml?ref={}", sha),
+            http_response("200 OK", &[], "name: Forge Lint\n"),
+        ),
+    ])
+    .await;
+
+    let client = http_client(&NetworkConfig::default()).unwrap();
+    let router = SourceRouter::new(Arc::new(MemorySource::new()))
+        .route(
+            "myorg/*",
+            Arc::new(GitHubSource::enterprise(client.clone(), &ghes_url, None)),
+        )
+        .unwrap()
+        .route(
+            "forge/*",
+            Arc::new(GiteaSource::new(client, &gitea_url, None)),
+        )
+        .unwrap();
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let cache = gaji::cache::Cache::load_from(dir.path().join("cache.json")).unwrap();
+    let fetcher = gaji::fetcher::GitHubFetcher::new(cache, None, None, 30)
+        .unwrap()
+        .with_sources(router);
+
+    let tool = fetcher
+        .fetch_action_metadata("myorg/tool@v1")
+        .await
+        .unwrap();
+    assert_eq!(tool.name, "Enterprise Tool");
+    let lint = fetcher
+        .fetch_action_metadata("forge/lint/sub@v2")
+        .await
+        .unwrap();
+    assert_eq!(lint.name, "Forge Lint");
+
+    // Refs no route matches use the default source
+    let err = fetcher
+        .fetch_action_metadata("actions/checkout@v5")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("memory"));
+
+    assert_eq!(ghes_requests.lock().unwrap().len(), 2);
+    let gitea_requests = gitea_requests.lock().unwrap();
+    assert_eq!(gitea_requests.len(), 3);
+    assert!(gitea_requests[1].contains("/raw/sub/action.yml?"));
+}
+
+/// Each kind of failure surfaces as its own `FetchError`, and refs that do
+/// not exist are not looked up again while the miss is cached.
+#[tokio::test]
+async fn test_fetch_errors_are_categorized() {
+    use gaji::config::NetworkConfig;
+    use gaji::fetcher::FetchError;
+    use gaji::source::{http_client, GitHubSource, SourceRouter};
+    use std::sync::Arc;
+
+    let sha = "0123456789abcdef0123456789abcdef01234567";
+    let (url, requests) = serve_http(vec![
+        (
+            "/api/v3/repos/org/tool/commits/v1".to_string(),
+            http_response("200 OK", &[], sha),
+        ),
+        (
+            "/api/v3/repos/org/private/commits/v1".to_string(),
+            http_response("401 Unauthorized", &[], ""),
+        ),
+        (
+            "/api/v3/repos/org/busy/commits/v1".to_string(),
+            http_response("403 Forbidden", &["X-RateLimit-Remaining: 0"], ""),
+        ),
+        (
+            "/api/v3/repos/org/busy/contents/action.yml?ref=v1".to_string(),
+            http_response("429 Too Many Requests", &["Retry-After: 3600"], ""),
+        ),
+    ])
+    .await;
+
+    let client = http_client(&NetworkConfig::default()).unwrap();
+    let router = SourceRouter::new(Arc::new(GitHubSource::enterprise(client, &url, None)));
+    let dir = tempfile::TempDir::new().unwrap();
+    let cache = gaji::cache::Cache::load_from(dir.path().join("cache.json")).unwrap();
+    let fetcher = gaji::fetcher::GitHubFetcher::new(cache, None, None, 30)
+        .unwrap()
+        .with_sources(router);
+
+    let fetch_error = |result: anyhow::Result<gaji::fetcher::ActionMetadata>| {
+        result
+            .unwrap_err()
+            .downcast::<FetchError>()
+            .expect("a FetchError")
+    };
+
+    let error = fetch_error(fetcher.fetch_action_metadata("org/typo@v9").await);
+    assert!(matches!(error, FetchError::RefNotFound { .. }));
+    assert!(error
+        .to_string()
+        .starts_with("Ref 'v9' not found in org/typo"));
+
+    let error = fetch_error(fetcher.fetch_action_metadata("org/tool/missing@v1").await);
+    assert!(matches!(error, FetchError::FileNotFound { .. }));
+
+    let error = fetch_error(fetcher.fetch_action_metadata("org/private@v1").await);
+    assert!(matches!(error, FetchError::Auth { status: 401, .. }));
+
+    // The commit lookup is rate limited too, but the file fetch decides; a
+    // wait of an hour is reported instead of slept through
+    let error = fetch_error(fetcher.fetch_action_metadata("org/busy@v1").await);
+    assert!(matches!(
+        error,
+        FetchError::RateLimited {
+            retry_in: Some(3600),
+            ..
+        }
+    ));
+    assert!(error.to_string().contains("resets in 60 min"));
+
+    let count = requests.lock().unwrap().len();
+    let error = fetch_error(fetcher.fetch_action_metadata("org/typo@v9").await);
+    assert!(matches!(error, FetchError::RefNotFound { .. }));
+    assert_eq!(requests.lock().unwrap().len(), count);
+}
@@ -0,0 +1,57 @@
+// Auto-generated from actions/cache@v4
+// Do not edit manually
+
+import type { JobStep } from './base';
+
+/**
+ * Cache artifacts like dependencies and build outputs to improve workflow execution time
+ * @see https://github.com/actionscache/v4
+ */
+export interface ActionsCacheV4Inputs {
+    /**
+     * An optional boolean when enabled, allows windows runners to save or restore caches that can be restored or saved respectively on other platforms
+     * @default false
+     */
+    enableCrossOsArchive?: boolean | string;
+    /**
+     * Fail the workflow if cache entry is not found
+     * @default false
+     */
+    'fail-on-cache-miss'?: boolean | string;
+    /**
+     * An explicit key for restoring and saving the cache
+     */
+    key: string;
+    /**
+     * Check if a cache entry exists for the given input(s) (key, restore-keys) without downloading the cache
+     * @default false
+     */
+    'lookup-only'?: boolean | string;
+    /**
+     * A list of files, directories, and wildcard patterns to cache and restore
+     */
+    path: string | string[];
+    /**
+     * An ordered multiline string listing the prefix-matched keys, that are used for restoring stale cache if no cache hit occurred for key. Note `cache-hit` returns false in this case.
+     */
+    'restore-keys'?: string | string[];
+    /**
+     * Run the post step to save the cache even if another step before fails
+     * @default false
+     * @deprecated save-always does not work as intended and will be removed in a future release.
+
+     */
+    'save-always'?: boolean | string;
+    /**
+     * The chunk size used to split up large files during upload, in bytes
+     */
+    'upload-chunk-size'?: number | string;
+}
+
+export interface ActionsCacheV4Outputs {
+    /**
+     * A boolean value to indicate an exact match was found for the primary key
+     */
+    'cache-hit': 'true' | 'false';
+}
+
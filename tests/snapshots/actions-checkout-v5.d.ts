@@ -0,0 +1,94 @@
+// Auto-generated from actions/checkout@v5
+// Do not edit manually
+
+import type { JobStep } from './base';
+
+/**
+ * Checkout a Git repository at a particular version
+ * @see https://github.com/actionscheckout/v5
+ */
+export interface ActionsCheckoutV5Inputs {
+    /**
+     * Whether to execute `git clean -ffdx && git reset --hard HEAD` before fetching
+     * @default true
+     */
+    clean?: boolean | string;
+    /**
+     * Number of commits to fetch. 0 indicates all history for all branches and tags.
+     * @default 1
+     */
+    'fetch-depth'?: number | string;
+    /**
+     * Whether to fetch tags, even if fetch-depth > 0.
+     * @default false
+     */
+    'fetch-tags'?: boolean | string;
+    /**
+     * Partially clone against a given filter.
+     */
+    filter?: string;
+    /**
+     * Whether to download Git-LFS files
+     * @default false
+     */
+    lfs?: boolean | string;
+    /**
+     * Relative path under $GITHUB_WORKSPACE to place the repository
+     */
+    path?: string;
+    /**
+     * Whether to configure the token or SSH key with the local git config
+     * @default true
+     */
+    'persist-credentials'?: boolean | string;
+    /**
+     * The branch, tag or SHA to checkout.
+     */
+    ref?: string;
+    /**
+     * Repository name with owner. For example, actions/checkout
+     * @default ${{ github.repository }}
+     */
+    repository?: string;
+    /**
+     * Add repository path as safe.directory for Git global config by running `git config --global --add safe.directory <path>`
+     * @default true
+     */
+    'set-safe-directory'?: boolean | string;
+    /**
+     * Whether to show progress status output when fetching.
+     * @default true
+     */
+    'show-progress'?: boolean | string;
+    /**
+     * Do a sparse checkout on given patterns. Each pattern should be separated with new lines.
+     */
+    'sparse-checkout'?: string | string[];
+    /**
+     * Specifies whether to use cone-mode when doing a sparse checkout.
+     * @default true
+     */
+    'sparse-checkout-cone-mode'?: boolean | string;
+    /**
+     * Whether to checkout submodules: `true` to checkout submodules or `recursive` to recursively checkout submodules.
+     * @default false
+     */
+    submodules?: boolean | 'recursive' | `\${{${string}}}`;
+    /**
+     * Personal access token (PAT) used to fetch the repository.
+     * @default ${{ github.token }}
+     */
+    token?: string;
+}
+
+export interface ActionsCheckoutV5Outputs {
+    /**
+     * The commit SHA that was checked out
+     */
+    commit: string;
+    /**
+     * The branch, tag or SHA that was checked out
+     */
+    ref: string;
+}
+
@@ -0,0 +1,67 @@
+// Auto-generated from actions/setup-python@v5
+// Do not edit manually
+
+import type { JobStep } from './base';
+
+/**
+ * Set up a specific version of Python and add the command-line tools to the PATH.
+ * @see https://github.com/actionssetuppython/v5
+ */
+export interface ActionsSetupPythonV5Inputs {
+    /**
+     * When 'true', a version range passed to 'python-version' input will match prerelease versions if no GA versions are found. Only 'x.y' version range is supported for CPython.
+     * @default false
+     */
+    'allow-prereleases'?: boolean | string;
+    /**
+     * The target architecture (x86, x64, arm64) of the Python or PyPy interpreter.
+     */
+    architecture?: 'x86' | 'x64' | 'arm64' | `\${{${string}}}`;
+    /**
+     * Used to specify a package manager for caching in the default directory. Supported values: pip, pipenv, poetry.
+     */
+    cache?: 'pip' | 'pipenv' | 'poetry' | `\${{${string}}}`;
+    /**
+     * Used to specify the path to dependency files. Supports wildcards or a list of file names for caching multiple dependencies.
+     */
+    'cache-dependency-path'?: string | string[];
+    /**
+     * Set this option if you want the action to check for the latest available version that satisfies the version spec.
+     * @default false
+     */
+    'check-latest'?: boolean | string;
+    /**
+     * Version range or exact version of Python or PyPy to use, using SemVer's version range syntax. Reads from .python-version if unset.
+     */
+    'python-version'?: string;
+    /**
+     * File containing the Python version to use. Example: .python-version
+     */
+    'python-version-file'?: string;
+    /**
+     * The token used to authenticate when fetching Python distributions from https://github.com/actions/python-versions. When running this action on github.com, the default value is sufficient. When running on GHES, you can pass a personal access token for github.com if you are experiencing rate limiting.
+     * @default ${{ github.server_url == 'https://github.com' && github.token || '' }}
+     */
+    token?: string;
+    /**
+     * Set this option if you want the action to update environment variables.
+     * @default true
+     */
+    'update-environment'?: boolean | string;
+}
+
+export interface ActionsSetupPythonV5Outputs {
+    /**
+     * A boolean value to indicate a cache entry was found
+     */
+    'cache-hit': 'true' | 'false';
+    /**
+     * The absolute path to the Python or PyPy executable.
+     */
+    'python-path': string;
+    /**
+     * The installed Python or PyPy version. Useful when given a version range as input.
+     */
+    'python-version': string;
+}
+
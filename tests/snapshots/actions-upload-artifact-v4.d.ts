@@ -0,0 +1,64 @@
+// Auto-generated from actions/upload-artifact@v4
+// Do not edit manually
+
+import type { JobStep } from './base';
+
+/**
+ * Upload a build artifact that can be used by subsequent workflow steps
+ * @see https://github.com/actionsuploadartifact/v4
+ */
+export interface ActionsUploadArtifactV4Inputs {
+    /**
+     * The level of compression for Zlib to be applied to the artifact archive. The value can range from 0 to 9.
+     * @default 6
+     */
+    'compression-level'?: 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | `\${{${string}}}`;
+    /**
+     * The desired behavior if no files are found using the provided path.
+     * Available Options:
+     * warn: Output a warning but do not fail the action
+     * error: Fail the action with an error message
+     * ignore: Do not output any warnings or errors, the action does not fail
+     * @default warn
+     */
+    'if-no-files-found'?: 'warn' | 'error' | 'ignore' | `\${{${string}}}`;
+    /**
+     * If true, hidden files will be included in the artifact.
+     * @default false
+     */
+    'include-hidden-files'?: boolean | string;
+    /**
+     * Artifact name
+     * @default artifact
+     */
+    name?: string;
+    /**
+     * If true, an artifact with a matching name will be deleted before a new one is uploaded.
+     * @default false
+     */
+    overwrite?: boolean | string;
+    /**
+     * A file, directory or wildcard pattern that describes what to upload
+     */
+    path: string | string[];
+    /**
+     * Duration after which artifact will expire in days. 0 means using default retention.
+     */
+    'retention-days'?: number | string;
+}
+
+export interface ActionsUploadArtifactV4Outputs {
+    /**
+     * SHA-256 digest for the artifact that was just uploaded. Empty if the artifact upload failed.
+     */
+    'artifact-digest': string;
+    /**
+     * A unique identifier for the artifact that was just uploaded. Empty if the artifact upload failed.
+     */
+    'artifact-id': string;
+    /**
+     * A download URL for the artifact that was just uploaded. Empty if the artifact upload failed.
+     */
+    'artifact-url': string;
+}
+